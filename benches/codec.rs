@@ -0,0 +1,44 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use wf_codec::encoding::EncodingKind;
+use wf_common::common::remove_all_invalid_hex_characters;
+
+/// a valid value for every [`EncodingKind`]
+const VALUES: &[(EncodingKind, &str)] = &[
+    (EncodingKind::BIN, "1011"),
+    (EncodingKind::DEC, "8765"),
+    (
+        EncodingKind::HEX,
+        "3efb4e0cfa83122b242634254c1920a769d615dfcc4c670bb53eb6f12843c3ae",
+    ),
+    (EncodingKind::UTF8, "https://organisation.int/whiteflag"),
+    (EncodingKind::DATETIME, "2013-08-31T04:29:15Z"),
+    (EncodingKind::DURATION, "P00D00H00M"),
+    (EncodingKind::LAT, "+30.79658"),
+    (EncodingKind::LONG, "-037.82602"),
+];
+
+fn encoding(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Encoding");
+    for (kind, value) in VALUES {
+        let encoding = kind.get_encoding();
+        let encoded = encoding.encode(value);
+        let bit_length = encoding.convert_to_bit_length(value.len());
+
+        group.bench_function(format!("encode/{:?}", kind), |b| {
+            b.iter(|| encoding.encode(black_box(value)))
+        });
+        group.bench_function(format!("decode/{:?}", kind), |b| {
+            b.iter(|| encoding.decode(black_box(&encoded), bit_length).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn hex_characters(c: &mut Criterion) {
+    c.bench_function("remove_all_invalid_hex_characters", |b| {
+        b.iter(|| remove_all_invalid_hex_characters(black_box("2013-08-31T04:29:15Z")))
+    });
+}
+
+criterion_group!(benches, encoding, hex_characters);
+criterion_main!(benches);
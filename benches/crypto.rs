@@ -0,0 +1,59 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use fennel_whiteflag::Message;
+use wf_account::{account::WfAccount, whiteflag_account::WhiteflagAccount};
+use wf_buffer::WhiteflagBuffer;
+use wf_crypto::{wf_encryption_key::WhiteflagEncryptionKey, WhiteflagCipher};
+
+const MESSAGE: &str = "5746313223000000000088888889111111119999999a22222222aaaaaaab33333333bbbbbbbb0983098309830983118b118b118b118b1993199319931993219b219b219b219b29a329a329a329a331ab31ab31ab31a9b1b9b1b9b1b9b1b9c1c9c1c9c1c9c1c8";
+const PRESHARED_KEY: &str = "32676187ba7badda85ea63a69870a7133909f1999774abb2eed251073616a6e7";
+const ADDRESS: &str = "007a0baf6f84f0fa7402ea972686e56d50b707c9b67b108866";
+const IV: [u8; 16] = [
+    64, 170, 133, 1, 93, 36, 228, 96, 20, 72, 193, 186, 141, 123, 241, 170,
+];
+
+fn account(shared_key: bool) -> WhiteflagAccount {
+    let mut account = WhiteflagAccount::new(false);
+    account.set_address(hex::decode(ADDRESS).unwrap());
+    if shared_key {
+        account.set_shared_key(WhiteflagEncryptionKey::from_preshared_key(PRESHARED_KEY));
+    }
+    account
+}
+
+fn cipher(c: &mut Criterion) {
+    let mut key = WhiteflagEncryptionKey::from_preshared_key(PRESHARED_KEY);
+    key.set_context(&hex::decode(ADDRESS).unwrap());
+    let cipher = key.aes_256_ctr_cipher();
+    let plaintext = WhiteflagBuffer::decode_from_hexadecimal(MESSAGE).unwrap();
+
+    c.bench_function("Aes256Ctr::encrypt", |b| {
+        b.iter(|| cipher.encrypt(black_box(&plaintext), &IV).unwrap())
+    });
+    c.bench_function("WhiteflagEncryptionKey::set_context", |b| {
+        b.iter(|| {
+            let mut key = WhiteflagEncryptionKey::from_preshared_key(PRESHARED_KEY);
+            key.set_context(black_box(ADDRESS.as_bytes()));
+            key
+        })
+    });
+}
+
+fn message(c: &mut Criterion) {
+    let mut message = Message::decode_from_hexadecimal(MESSAGE).unwrap();
+    message.set_originator(account(false));
+    message.set_recipient(account(true));
+    let encrypted = message.encrypt(&IV).unwrap().as_hex();
+
+    c.bench_function("Message::encrypt", |b| {
+        b.iter(|| black_box(&message).encrypt(&IV).unwrap())
+    });
+    c.bench_function("Message::decrypt", |b| {
+        b.iter(|| {
+            let buffer = WhiteflagBuffer::decode_from_hexadecimal(black_box(&encrypted)).unwrap();
+            Message::decrypt(buffer, account(true), account(false), &IV).unwrap()
+        })
+    });
+}
+
+criterion_group!(benches, cipher, message);
+criterion_main!(benches);
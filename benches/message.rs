@@ -0,0 +1,48 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use fennel_whiteflag::{decode_from_hex, encode_from_json, Message};
+
+const MESSAGES: &[(&str, &str)] = &[
+    ("authentication", "5746313020800000000000000000000000000000000000000000000000000000000000000000b43a3a38399d1797b7b933b0b734b9b0ba34b7b71734b73a17bbb434ba32b33630b380"),
+    ("sign", "57463130a6a1f7da7067d41891592131a12a60c9053b4eb0aefe6263385da9f5b789421e1d7401009841882148a800000114c1e596006f04c050eca6420084"),
+    ("test", "57463130aa19f7da7067d41891592131a12a60c9053b4eb0aefe6263385da9f5b789421e1d726c01009841882148a800000114c1e596006f04c050eca6420084"),
+];
+
+fn encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Message::encode");
+    for (name, hex) in MESSAGES {
+        let message = Message::decode_from_hexadecimal(hex).unwrap();
+        group.bench_function(*name, |b| b.iter(|| black_box(&message).encode()));
+    }
+    group.finish();
+}
+
+fn decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Message::decode_from_hexadecimal");
+    for (name, hex) in MESSAGES {
+        group.bench_function(*name, |b| {
+            b.iter(|| Message::decode_from_hexadecimal(black_box(hex)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn json(c: &mut Criterion) {
+    let mut group = c.benchmark_group("json");
+    for (name, hex) in MESSAGES {
+        let json = decode_from_hex(hex).unwrap();
+
+        group.bench_function(format!("decode_from_hex/{}", name), |b| {
+            b.iter(|| decode_from_hex(black_box(hex)).unwrap())
+        });
+        group.bench_function(format!("encode_from_json/{}", name), |b| {
+            b.iter(|| encode_from_json(black_box(&json)).unwrap())
+        });
+        group.bench_function(format!("round_trip/{}", name), |b| {
+            b.iter(|| encode_from_json(decode_from_hex(black_box(hex)).unwrap()).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, encode, decode, json);
+criterion_main!(benches);
@@ -0,0 +1,43 @@
+use super::vectors;
+use crate::{decode_from_hex, encode_from_json, Message};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct MessageVector {
+    description: String,
+    fields: Vec<String>,
+    encoded: String,
+}
+
+fn message_vectors() -> Vec<MessageVector> {
+    vectors(include_str!("vectors/messages.json"))
+}
+
+#[test]
+fn message_encoding_vectors() {
+    for v in message_vectors() {
+        let message = Message::compile(&v.fields).unwrap();
+        assert_eq!(v.encoded, message.encode_as_hex(), "{}", v.description);
+    }
+}
+
+#[test]
+fn message_decoding_vectors() {
+    for v in message_vectors() {
+        let message = Message::decode_from_hexadecimal(&v.encoded).unwrap();
+        assert_eq!(v.fields.concat(), message.serialize(), "{}", v.description);
+    }
+}
+
+#[test]
+fn message_json_vectors() {
+    for v in message_vectors() {
+        let json = decode_from_hex(&v.encoded).unwrap();
+        assert_eq!(
+            v.encoded,
+            encode_from_json(json).unwrap(),
+            "{}",
+            v.description
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use super::{bytes, vectors};
+use crate::Message;
+use serde::Deserialize;
+use wf_account::{account::WfAccount, whiteflag_account::WhiteflagAccount};
+use wf_buffer::WhiteflagBuffer;
+use wf_crypto::{ecdh_keypair::WhiteflagECDHKeyPair, wf_encryption_key::WhiteflagEncryptionKey};
+use x25519_dalek::StaticSecret;
+
+/// a vector of method 2 has a pre-shared key, one of method 1 the ECDH private keys of both parties
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EncryptionVector {
+    description: String,
+    preshared_key: Option<String>,
+    originator_private_key: Option<String>,
+    recipient_private_key: Option<String>,
+    originator_address: String,
+    iv: String,
+    encoded: String,
+    encrypted: String,
+}
+
+impl EncryptionVector {
+    /// the originator's account, our own when encrypting and the other party's when decrypting
+    fn originator(&self, owned: bool) -> WhiteflagAccount {
+        let mut originator = WhiteflagAccount::new(owned);
+        originator.set_address(bytes(&self.originator_address));
+        self.set_keys(&mut originator, &self.originator_private_key, owned);
+        originator
+    }
+
+    fn recipient(&self, owned: bool) -> WhiteflagAccount {
+        let mut recipient = WhiteflagAccount::new(owned);
+        self.set_keys(&mut recipient, &self.recipient_private_key, owned);
+        recipient
+    }
+
+    /// our own account gets the ECDH key pair, the other party's its public key and the pre-shared key
+    fn set_keys(&self, account: &mut WhiteflagAccount, private_key: &Option<String>, owned: bool) {
+        if let Some(private_key) = private_key {
+            let keypair = keypair(private_key);
+            match owned {
+                true => account.set_ecdh_keypair(keypair).unwrap(),
+                false => account.set_ecdh_public_key(*keypair.as_ref()).unwrap(),
+            }
+        }
+        if let (Some(key), false) = (&self.preshared_key, owned) {
+            account.set_shared_key(WhiteflagEncryptionKey::from_preshared_key(key));
+        }
+    }
+}
+
+fn keypair(private_key: &str) -> WhiteflagECDHKeyPair {
+    let mut secret = [0; 32];
+    secret.copy_from_slice(&bytes(private_key));
+    WhiteflagECDHKeyPair::from_secret(StaticSecret::from(secret))
+}
+
+#[test]
+fn encryption_vectors() {
+    for v in vectors::<EncryptionVector>(include_str!("vectors/encryption.json")) {
+        let mut message = Message::decode_from_hexadecimal(&v.encoded).unwrap();
+        message.set_originator(v.originator(true));
+        message.set_recipient(v.recipient(false));
+
+        let encrypted = message.encrypt(&bytes(&v.iv)).unwrap();
+        assert_eq!(v.encrypted, encrypted.as_hex(), "{}", v.description);
+    }
+}
+
+#[test]
+fn decryption_vectors() {
+    for v in vectors::<EncryptionVector>(include_str!("vectors/encryption.json")) {
+        let encrypted = WhiteflagBuffer::decode_from_hexadecimal(&v.encrypted).unwrap();
+        let message = Message::decrypt(
+            encrypted,
+            v.originator(false),
+            v.recipient(true),
+            &bytes(&v.iv),
+        )
+        .unwrap();
+
+        assert_eq!(v.encoded, message.encode_as_hex(), "{}", v.description);
+    }
+}
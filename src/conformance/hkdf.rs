@@ -0,0 +1,22 @@
+use super::{bytes, vectors};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct HkdfVector {
+    description: String,
+    ikm: String,
+    salt: String,
+    info: String,
+    length: usize,
+    okm: String,
+}
+
+#[test]
+fn hkdf_vectors() {
+    for v in vectors::<HkdfVector>(include_str!("vectors/hkdf.json")) {
+        let okm =
+            wf_crypto::hkdf(&bytes(&v.ikm), &bytes(&v.salt), &bytes(&v.info), v.length).unwrap();
+
+        assert_eq!(v.okm, hex::encode(okm), "{}", v.description);
+    }
+}
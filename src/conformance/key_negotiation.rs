@@ -0,0 +1,44 @@
+use super::{bytes, vectors};
+use serde::Deserialize;
+use wf_crypto::ecdh_keypair::WhiteflagECDHKeyPair;
+use x25519_dalek::StaticSecret;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KeyNegotiationVector {
+    description: String,
+    private_key_a: String,
+    public_key_a: String,
+    private_key_b: String,
+    public_key_b: String,
+    shared_secret: String,
+}
+
+fn keypair(private_key: &str) -> WhiteflagECDHKeyPair {
+    let mut secret = [0; 32];
+    secret.copy_from_slice(&bytes(private_key));
+    WhiteflagECDHKeyPair::from_secret(StaticSecret::from(secret))
+}
+
+#[test]
+fn key_negotiation_vectors() {
+    for v in vectors::<KeyNegotiationVector>(include_str!("vectors/key_negotiation.json")) {
+        let a = keypair(&v.private_key_a);
+        let b = keypair(&v.private_key_b);
+
+        assert_eq!(v.public_key_a, hex::encode(a.as_ref()), "{}", v.description);
+        assert_eq!(v.public_key_b, hex::encode(b.as_ref()), "{}", v.description);
+        assert_eq!(
+            v.shared_secret,
+            hex::encode(a.negotiate(b.as_ref())),
+            "{}",
+            v.description
+        );
+        assert_eq!(
+            v.shared_secret,
+            hex::encode(b.negotiate(a.as_ref())),
+            "{}",
+            v.description
+        );
+    }
+}
@@ -0,0 +1,20 @@
+//! Whiteflag conformance suite
+//!
+//! every test in this module runs all vectors in the matching data file under `vectors/`,
+//! so new official test vectors only need to be added to the data files
+
+mod encoding;
+mod encryption;
+mod hkdf;
+mod key_negotiation;
+mod token;
+
+use serde::de::DeserializeOwned;
+
+fn vectors<T: DeserializeOwned>(data: &str) -> Vec<T> {
+    serde_json::from_str(data).expect("conformance vectors should be valid json")
+}
+
+fn bytes(hex: &str) -> Vec<u8> {
+    hex::decode(hex).expect("conformance vectors should be hexadecimal")
+}
@@ -0,0 +1,27 @@
+use super::{bytes, vectors};
+use serde::Deserialize;
+use wf_auth::WhiteflagAuthToken;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenVector {
+    description: String,
+    secret: String,
+    context: String,
+    verification_data: String,
+}
+
+#[test]
+fn token_derivation_vectors() {
+    for v in vectors::<TokenVector>(include_str!("vectors/token.json")) {
+        let token = WhiteflagAuthToken::new(bytes(&v.secret));
+        let verification_data = token.get_verification_data(bytes(&v.context)).unwrap();
+
+        assert_eq!(
+            v.verification_data,
+            hex::encode(verification_data),
+            "{}",
+            v.description
+        );
+    }
+}
@@ -5,9 +5,27 @@ pub enum WhiteflagError {
     #[error("wf_field::Error")]
     WFFieldError(#[from] wf_field::Error),
     #[error("error")]
-    Serde(serde_json::Error),
+    Serde(#[source] serde_json::Error),
     #[error("issue decoding hex string")]
     HexDecode(#[from] hex::FromHexError),
+    #[error("wf_codec::CodecError")]
+    Codec(#[from] wf_codec::CodecError),
+    #[error("wf_crypto::CryptoError")]
+    Crypto(#[from] wf_crypto::CryptoError),
+    #[error("message has no originator account to encrypt or decrypt with")]
+    MissingOriginator,
+    #[error("message has no recipient account to encrypt or decrypt with")]
+    MissingRecipient,
+    #[error("originator account has no address to use as encryption context")]
+    MissingOriginatorAddress,
+    #[error("cannot retrieve encryption key: {0}")]
+    CannotRetrieveKey(&'static str),
+    #[error("wf_auth::SignatureError")]
+    Signature(#[from] wf_auth::SignatureError),
+    #[error("message is not an authentication message with an internet resource (A1)")]
+    NotSignatureAuthentication,
+    #[error("{bit_length} bits do not fit {bytes} bytes padded with zero bits")]
+    InvalidBitLength { bit_length: usize, bytes: usize },
     #[error(transparent)]
     Other(#[from] Box<dyn std::error::Error>),
 }
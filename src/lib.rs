@@ -1,6 +1,15 @@
 pub use error::WhiteflagError;
+pub use wf_core::{
+    duress::{receive, DuressPolicy, ReceivedMessage},
+    message::Message,
+    stream::{MessageReader, MessageWriter, StreamError, StreamFormat, CONTAINER_HEADER},
+    CryptMode, EncodedMessage,
+};
 use wf_field::{Header, MessageBodyType};
 
+#[cfg(test)]
+mod conformance;
+
 mod error;
 #[allow(dead_code)]
 mod wf_core;
@@ -76,6 +85,18 @@ impl WhiteflagMessage {
         })
     }
 
+    /// creates a message with the duress indicator set, signalling that it is sent under duress
+    ///
+    /// 4.2.1.5 Duress Indicator Field
+    pub fn new_under_duress(code: String) -> Result<Self, WhiteflagError> {
+        let header = Header::new(code).with_duress(true);
+        let body = header.to_body()?;
+        Ok(Self {
+            json: body.to_string()?,
+            body,
+        })
+    }
+
     pub fn new_with_reference(
         code: String,
         reference_indicator: String,
@@ -0,0 +1,100 @@
+use super::message::Message;
+use crate::WhiteflagError;
+use wf_account::{account::WfAccount, whiteflag_account::WhiteflagAccount};
+use wf_auth::{
+    SignatureError, WhiteflagAuthSignature, WhiteflagSignaturePayload, WhiteflagSigningKey,
+};
+
+const ADDRESS: &str = "007a0baf6f84f0fa7402ea972686e56d50b707c9b67b108866";
+const URL: &str = "https://organisation.int/whiteflag";
+
+fn originator(address: &str) -> WhiteflagAccount {
+    let mut originator = WhiteflagAccount::new(false);
+    originator.set_address(hex::decode(address).unwrap());
+    originator
+}
+
+fn authentication_message(method: &str, data: &str) -> Message {
+    let mut message = Message::compile(&[
+        "WF",
+        "1",
+        "0",
+        "0",
+        "A",
+        "0",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        method,
+        data,
+    ])
+    .unwrap();
+    message.set_originator(originator(ADDRESS));
+    message
+}
+
+fn signature(key: &WhiteflagSigningKey) -> WhiteflagAuthSignature {
+    let payload = WhiteflagSignaturePayload::new(ADDRESS, "Whiteflag Test Organisation", URL);
+    WhiteflagAuthSignature::sign(&payload, key).unwrap()
+}
+
+#[test]
+fn verify_authentication_signature() {
+    let key = WhiteflagSigningKey::eddsa_from_bytes(&[1; 32]).unwrap();
+    let message = authentication_message("1", URL);
+
+    let payload = message
+        .verify_authentication_signature(&signature(&key), &key.verifying_key())
+        .unwrap();
+
+    assert_eq!(ADDRESS, payload.addr);
+    assert_eq!(URL, payload.url);
+}
+
+#[test]
+fn signature_of_other_originator_is_rejected() {
+    let key = WhiteflagSigningKey::es256_from_bytes(&[1; 32]).unwrap();
+    let mut message = authentication_message("1", URL);
+    message.set_originator(originator("00112233"));
+
+    assert!(matches!(
+        message.verify_authentication_signature(&signature(&key), &key.verifying_key()),
+        Err(WhiteflagError::Signature(SignatureError::PayloadMismatch(
+            "addr"
+        )))
+    ));
+}
+
+#[test]
+fn signature_requires_internet_resource_authentication() {
+    let key = WhiteflagSigningKey::eddsa_from_bytes(&[1; 32]).unwrap();
+    let message = authentication_message(
+        "2",
+        "b01218a30dd3c23d050af254bfcce31a715fecdff6a23fd59609612e6e0ef263",
+    );
+
+    assert!(matches!(
+        message.verify_authentication_signature(&signature(&key), &key.verifying_key()),
+        Err(WhiteflagError::NotSignatureAuthentication)
+    ));
+}
+
+#[test]
+fn signature_requires_authentication_message() {
+    let key = WhiteflagSigningKey::eddsa_from_bytes(&[1; 32]).unwrap();
+    let mut message = Message::compile(&[
+        "WF",
+        "1",
+        "0",
+        "0",
+        "F",
+        "0",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        URL,
+    ])
+    .unwrap();
+    message.set_originator(originator(ADDRESS));
+
+    assert!(matches!(
+        message.verify_authentication_signature(&signature(&key), &key.verifying_key()),
+        Err(WhiteflagError::NotSignatureAuthentication)
+    ));
+}
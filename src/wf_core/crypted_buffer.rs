@@ -1,5 +1,5 @@
-use aes_tools::FennelCipher;
 use wf_buffer::WhiteflagBuffer;
+use wf_crypto::{CryptoResult, WhiteflagCipher};
 use wf_field::definitions::WhiteflagFields;
 
 /// everything is encrypted in a whiteflag buffer except for the first few header fields
@@ -40,16 +40,21 @@ impl CryptedBuffer {
     }
 
     /// creates an encrypted [`WhiteflagBuffer`] or decrypted [`WhiteflagBuffer`] depending on [`CryptMode`]
-    pub fn crypt<T: FennelCipher>(self, cipher: &T, mode: CryptMode) -> WhiteflagBuffer {
+    pub fn crypt<T: WhiteflagCipher>(
+        self,
+        cipher: &T,
+        iv: &[u8],
+        mode: CryptMode,
+    ) -> CryptoResult<WhiteflagBuffer> {
         let mut buffer = WhiteflagBuffer::default();
 
         let crypted_half = match mode {
-            CryptMode::Encrypt => cipher.encrypt(&self.encrypted_second_half),
-            CryptMode::Decrypt => cipher.decrypt(&self.encrypted_second_half),
+            CryptMode::Encrypt => cipher.encrypt(&self.encrypted_second_half, iv)?,
+            CryptMode::Decrypt => cipher.decrypt(&self.encrypted_second_half, iv)?,
         };
 
         buffer.append(self.unencrypted_first_half, None);
-        buffer.append(crypted_half.into(), None);
-        buffer
+        buffer.append(crypted_half, None);
+        Ok(buffer)
     }
 }
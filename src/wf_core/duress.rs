@@ -0,0 +1,78 @@
+use super::message::Message;
+
+/// receive-side handling of messages sent under duress
+///
+/// the originator of a message with the duress indicator set may not be acting freely,
+/// so such messages are surfaced separately and automatic responses can be withheld
+///
+/// Whiteflag Specification 4.2.1.5 Duress Indicator Field
+pub trait DuressPolicy {
+    /// called for every received message that has the duress indicator set
+    fn on_duress(&mut self, message: &Message);
+
+    /// whether automatic responses to a message sent under duress should be suppressed
+    fn suppress_auto_response(&self) -> bool {
+        true
+    }
+}
+
+/// any closure can be used as a policy that only gets notified of messages sent under duress
+impl<F: FnMut(&Message)> DuressPolicy for F {
+    fn on_duress(&mut self, message: &Message) {
+        self(message)
+    }
+}
+
+/// a received message, classified by its duress indicator
+pub enum ReceivedMessage {
+    Message(Message),
+    UnderDuress {
+        message: Message,
+        suppress_auto_response: bool,
+    },
+}
+
+impl ReceivedMessage {
+    pub fn is_under_duress(&self) -> bool {
+        matches!(self, ReceivedMessage::UnderDuress { .. })
+    }
+
+    /// whether the application may respond to this message automatically
+    pub fn allows_auto_response(&self) -> bool {
+        match self {
+            ReceivedMessage::Message(_) => true,
+            ReceivedMessage::UnderDuress {
+                suppress_auto_response,
+                ..
+            } => !suppress_auto_response,
+        }
+    }
+
+    pub fn message(&self) -> &Message {
+        match self {
+            ReceivedMessage::Message(message) => message,
+            ReceivedMessage::UnderDuress { message, .. } => message,
+        }
+    }
+
+    pub fn into_message(self) -> Message {
+        match self {
+            ReceivedMessage::Message(message) => message,
+            ReceivedMessage::UnderDuress { message, .. } => message,
+        }
+    }
+}
+
+/// classifies a received message and notifies the policy if it was sent under duress
+pub fn receive<P: DuressPolicy>(message: Message, policy: &mut P) -> ReceivedMessage {
+    if !message.is_under_duress() {
+        return ReceivedMessage::Message(message);
+    }
+
+    policy.on_duress(&message);
+
+    ReceivedMessage::UnderDuress {
+        suppress_auto_response: policy.suppress_auto_response(),
+        message,
+    }
+}
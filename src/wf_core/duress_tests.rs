@@ -0,0 +1,99 @@
+use super::{
+    duress::{receive, DuressPolicy, ReceivedMessage},
+    message::Message,
+};
+use crate::WhiteflagMessage;
+
+const SIGNAL_UNDER_DURESS: &str = "57463130a6a1f7da7067d41891592131a12a60c9053b4eb0aefe6263385da9f5b789421e1d7401009841882148a800000114c1e596006f04c050eca6420084";
+const AUTH_MESSAGE: &str = "5746313020800000000000000000000000000000000000000000000000000000000000000000b43a3a38399d1797b7b933b0b734b9b0ba34b7b71734b73a17bbb434ba32b33630b380";
+
+struct RespondingPolicy {
+    flagged: Vec<String>,
+}
+
+impl DuressPolicy for RespondingPolicy {
+    fn on_duress(&mut self, message: &Message) {
+        self.flagged.push(message.serialize());
+    }
+
+    fn suppress_auto_response(&self) -> bool {
+        false
+    }
+}
+
+#[test]
+fn received_message_under_duress_is_flagged() {
+    let message = Message::decode_from_hexadecimal(SIGNAL_UNDER_DURESS).unwrap();
+    let mut flagged = 0;
+
+    let received = receive(message, &mut |_: &Message| flagged += 1);
+
+    assert_eq!(1, flagged, "policy should be notified of duress");
+    assert!(received.is_under_duress());
+    assert!(
+        !received.allows_auto_response(),
+        "auto responses should be suppressed by default"
+    );
+}
+
+#[test]
+fn received_message_without_duress_passes_through() {
+    let message = Message::decode_from_hexadecimal(AUTH_MESSAGE).unwrap();
+    let mut policy = RespondingPolicy { flagged: vec![] };
+
+    let received = receive(message, &mut policy);
+
+    assert!(policy.flagged.is_empty());
+    assert!(matches!(received, ReceivedMessage::Message(_)));
+    assert!(received.allows_auto_response());
+}
+
+#[test]
+fn duress_policy_can_allow_auto_response() {
+    let message = Message::decode_from_hexadecimal(SIGNAL_UNDER_DURESS).unwrap();
+    let serialized = message.serialize();
+    let mut policy = RespondingPolicy { flagged: vec![] };
+
+    let received = receive(message, &mut policy);
+
+    assert_eq!(vec![serialized], policy.flagged);
+    assert!(received.is_under_duress());
+    assert!(received.allows_auto_response());
+}
+
+#[test]
+fn send_message_under_duress() {
+    let hex = WhiteflagMessage::new_under_duress("F".to_string())
+        .unwrap()
+        .as_hex()
+        .unwrap();
+    let message = Message::decode_from_hexadecimal(hex).unwrap();
+
+    assert!(message.is_under_duress());
+}
+
+#[test]
+fn send_signal_under_duress() {
+    let template = WhiteflagMessage::new_under_duress("P".to_string()).unwrap();
+    let message = Message::decode_from_hexadecimal(template.as_hex().unwrap()).unwrap();
+
+    assert!(message.is_under_duress());
+    assert!(template
+        .as_json()
+        .contains("\"dateTime\":\"2013-08-31T04:29:15Z\""));
+}
+
+#[test]
+fn set_duress_indicator() {
+    let mut message = Message::decode_from_hexadecimal(AUTH_MESSAGE).unwrap();
+    assert!(!message.is_under_duress());
+
+    message.set_duress_indicator(true);
+    let reencoded = Message::decode_from_hexadecimal(message.encode_as_hex()).unwrap();
+
+    assert!(reencoded.is_under_duress());
+    assert_eq!(
+        "WF101A000000000000000000000000000000000000000000000000000000000000000001https://organisation.int/whiteflag",
+        reencoded.serialize()
+    );
+}
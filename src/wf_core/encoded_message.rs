@@ -0,0 +1,91 @@
+use super::message::Message;
+use crate::error::WhiteflagError;
+use wf_buffer::WhiteflagBuffer;
+use wf_common::{common::byte_length, constants::BYTE};
+
+/// an encoded message with its exact bit length
+///
+/// messages rarely end on a byte boundary; the canonical byte representation pads the last byte with
+/// zero bits. decoding bytes without the bit length reads padding of four or more bits as a trailing
+/// `0` of a hexadecimal or decimal last field, decoding an [`EncodedMessage`] does not
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncodedMessage {
+    buffer: WhiteflagBuffer,
+}
+
+impl EncodedMessage {
+    pub(crate) fn new(mut buffer: WhiteflagBuffer) -> Self {
+        buffer.crop();
+        EncodedMessage { buffer }
+    }
+
+    /// an encoded message from its canonical bytes and bit length
+    ///
+    /// fails unless the bit length ends in the last byte and the padding bits are zero
+    pub fn from_bytes(bytes: Vec<u8>, bit_length: usize) -> Result<Self, WhiteflagError> {
+        let invalid = || WhiteflagError::InvalidBitLength {
+            bit_length,
+            bytes: bytes.len(),
+        };
+        if byte_length(bit_length) != bytes.len() {
+            return Err(invalid());
+        }
+
+        let padding = bytes.len() * BYTE - bit_length;
+        if bytes
+            .last()
+            .is_some_and(|last| last & ((1 << padding) - 1) != 0)
+        {
+            return Err(invalid());
+        }
+
+        Ok(EncodedMessage {
+            buffer: WhiteflagBuffer::new(bytes, bit_length),
+        })
+    }
+
+    /// the number of bits of the encoded fields, excluding padding
+    pub fn bit_length(&self) -> usize {
+        self.buffer.bit_length()
+    }
+
+    /// the number of zero bits padding the last byte
+    pub fn padding(&self) -> usize {
+        self.as_bytes().len() * BYTE - self.bit_length()
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.buffer.as_ref()
+    }
+
+    pub fn as_hex(&self) -> String {
+        self.buffer.as_hex()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buffer.into()
+    }
+
+    /// decodes the message using its exact bit length
+    pub fn decode(self) -> Result<Message, WhiteflagError> {
+        Message::decode_from_buffer(self.buffer)
+    }
+}
+
+impl AsRef<[u8]> for EncodedMessage {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl From<EncodedMessage> for WhiteflagBuffer {
+    fn from(message: EncodedMessage) -> Self {
+        message.buffer
+    }
+}
+
+impl From<EncodedMessage> for Vec<u8> {
+    fn from(message: EncodedMessage) -> Self {
+        message.into_bytes()
+    }
+}
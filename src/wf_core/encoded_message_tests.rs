@@ -0,0 +1,174 @@
+use super::{decode, message::Message, EncodedMessage};
+use crate::error::WhiteflagError;
+use wf_codec::CodecError;
+
+const REFERENCED_MESSAGE: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// prefix, version, encryption and duress indicators, message code, reference indicator and message
+const HEADER_BIT_LENGTH: usize = 16 + 8 + 8 + 1 + 8 + 4 + 256;
+
+fn message(code: &str, body: &[&str]) -> Message {
+    let mut values = vec!["WF", "1", "0", "0", code, "0", REFERENCED_MESSAGE];
+    values.extend_from_slice(body);
+    Message::compile(&values).unwrap()
+}
+
+/// decodes the message from its exact bit length and from its hexadecimal bytes
+fn round_trip(message: &Message) -> (String, String) {
+    let encoded = message.encode();
+    let from_hex = decode(encoded.as_hex()).unwrap().serialize();
+    let exact = encoded.decode().unwrap().serialize();
+    (exact, from_hex)
+}
+
+fn padding_error(hex: &str) -> CodecError {
+    match decode(hex) {
+        Err(WhiteflagError::WFFieldError(wf_field::Error::Codec(e))) => e,
+        _ => panic!("{} should fail to decode", hex),
+    }
+}
+
+#[test]
+fn text_keeps_bit_length() {
+    for text in ["a", "hello", "twelve chars"] {
+        let message = message("F", &[text]);
+        let encoded = message.encode();
+
+        assert_eq!(HEADER_BIT_LENGTH + 8 * text.len(), encoded.bit_length());
+        assert_eq!(3, encoded.padding());
+
+        let (exact, from_hex) = round_trip(&message);
+        assert_eq!(message.serialize(), exact);
+        assert_eq!(message.serialize(), from_hex);
+    }
+}
+
+#[test]
+fn verification_data_keeps_bit_length() {
+    let message = message("A", &["1", "https://organisation.int/whiteflag"]);
+    let encoded = message.encode();
+
+    assert_eq!(HEADER_BIT_LENGTH + 4 + 8 * 34, encoded.bit_length());
+    assert_eq!(7, encoded.padding());
+
+    let (exact, from_hex) = round_trip(&message);
+    assert_eq!(message.serialize(), exact);
+    assert_eq!(message.serialize(), from_hex);
+}
+
+#[test]
+fn crypto_data_keeps_bit_length() {
+    for crypto_data in ["a1", "abcd0123"] {
+        let message = message("K", &["11", crypto_data]);
+        assert_eq!(
+            HEADER_BIT_LENGTH + 8 + 4 * crypto_data.len(),
+            message.encode().bit_length()
+        );
+
+        let (exact, from_hex) = round_trip(&message);
+        assert_eq!(message.serialize(), exact);
+        assert_eq!(message.serialize(), from_hex);
+    }
+}
+
+#[test]
+fn odd_crypto_data_needs_bit_length() {
+    let message = message("K", &["11", "abc"]);
+    let encoded = message.encode();
+    assert_eq!(7, encoded.padding());
+
+    let (exact, from_hex) = round_trip(&message);
+    assert!(exact.ends_with("11abc"));
+    /* without the bit length, four of the padding bits are read as another hexadecimal digit */
+    assert!(from_hex.ends_with("11abc0"));
+}
+
+#[test]
+fn request_pairs_keep_bit_length() {
+    let signal = [
+        "80",
+        "2013-08-31T04:29:15Z",
+        "P01D00H00M",
+        "22",
+        "+31.79658",
+        "-033.82602",
+        "8799",
+        "3210",
+        "000",
+    ];
+    let without_pairs = message("Q", &signal).encode().bit_length();
+
+    for pairs in [&["10", "02"][..], &["10", "02", "20", "03"]] {
+        let mut body = signal.to_vec();
+        body.extend_from_slice(pairs);
+        let message = message("Q", &body);
+
+        assert_eq!(
+            without_pairs + 8 * pairs.len(),
+            message.encode().bit_length()
+        );
+
+        let (exact, from_hex) = round_trip(&message);
+        assert_eq!(message.serialize(), exact);
+        assert_eq!(message.serialize(), from_hex);
+    }
+}
+
+#[test]
+fn nonzero_padding_is_rejected() {
+    let mut bytes = message("F", &["hello"]).encode().into_bytes();
+    *bytes.last_mut().unwrap() |= 1;
+
+    assert_eq!(
+        CodecError::InvalidPadding { bits: 3 },
+        padding_error(&hex::encode(bytes))
+    );
+}
+
+#[test]
+fn trailing_bytes_are_rejected() {
+    let encoded = message(
+        "P",
+        &[
+            "80",
+            "2013-08-31T04:29:15Z",
+            "P00D00H00M",
+            "22",
+            "+30.79658",
+            "-037.82602",
+            "8765",
+            "3210",
+            "042",
+        ],
+    )
+    .encode();
+    let hex = encoded.as_hex() + "00";
+
+    assert_eq!(
+        CodecError::InvalidPadding {
+            bits: encoded.padding() + 8
+        },
+        padding_error(&hex)
+    );
+}
+
+#[test]
+fn from_bytes_checks_bit_length() {
+    let encoded = message("F", &["hello"]).encode();
+    let bit_length = encoded.bit_length();
+    let bytes = encoded.clone().into_bytes();
+
+    assert_eq!(
+        encoded,
+        EncodedMessage::from_bytes(bytes.clone(), bit_length).unwrap()
+    );
+    assert!(EncodedMessage::from_bytes(bytes.clone(), bit_length - 8).is_err());
+    assert!(EncodedMessage::from_bytes(bytes.clone(), bit_length + 8).is_err());
+
+    let mut padded = bytes;
+    *padded.last_mut().unwrap() |= 1;
+    assert!(matches!(
+        EncodedMessage::from_bytes(padded, bit_length),
+        Err(WhiteflagError::InvalidBitLength { bytes: 43, .. })
+    ));
+}
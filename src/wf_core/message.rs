@@ -2,6 +2,7 @@ use std::str::FromStr;
 
 use super::{
     crypted_buffer::{CryptMode, CryptedBuffer},
+    encoded_message::EncodedMessage,
     segment::MessageSegment,
     wf_message_builder::{
         builder_from_encoded, builder_from_field_values, builder_from_serialized,
@@ -9,16 +10,20 @@ use super::{
     FieldValue,
 };
 use crate::error::WhiteflagError;
-use aes_tools::FennelCipher;
-use wf_account::whiteflag_account::WhiteflagAccount;
-use wf_buffer::WhiteflagBuffer;
-use wf_crypto::encryption_method::WhiteflagEncryptionMethod;
-use wf_field::{Field, MessageCodeType};
+use wf_account::{account::WfAccount, whiteflag_account::WhiteflagAccount};
+use wf_auth::{WhiteflagAuthSignature, WhiteflagSignaturePayload, WhiteflagVerifyingKey};
+use wf_buffer::{BufferReader, WhiteflagBuffer};
+use wf_crypto::{
+    encryption_method::WhiteflagEncryptionMethod, wf_encryption_key::WhiteflagEncryptionKey,
+    WhiteflagCipher,
+};
+use wf_field::{definitions, Field, MessageCodeType, MessageHeaderOrder, DURESS, NO_DURESS};
 use wf_parser::Parser;
 
 const METAKEY_ORIGINATOR: &str = "originatorAddress";
 const METAKEY_RECIPIENT: &str = "recipientAddress";
 const FIELD_ENCRYPTIONINDICATOR: &str = "EncryptionIndicator";
+const INTERNET_RESOURCE: &str = "1";
 
 pub struct Message {
     message_code: MessageCodeType,
@@ -96,49 +101,177 @@ impl Message {
         Ok(Self::from_parser(builder_from_encoded(buffer)?))
     }
 
-    /// decode a hexadecimal encoded whiteflag message
+    /// decode a whiteflag message from a buffer, using the bit length of the buffer
+    ///
+    /// a buffer of whole bytes may end with less than a byte of zero padding, see [`EncodedMessage`]
     pub fn decode_from_buffer(message: WhiteflagBuffer) -> Result<Self, WhiteflagError> {
         Ok(Self::from_parser(builder_from_encoded(message)?))
     }
 
-    pub fn encode_and_crypt<T: FennelCipher>(
+    /// encodes the message and encrypts it with the given cipher and initialisation vector,
+    /// unless the encryption indicator says the message is not encrypted
+    pub fn encode_and_crypt<T: WhiteflagCipher>(
         &self,
         cipher: &T,
+        iv: &[u8],
         mode: CryptMode,
-    ) -> WhiteflagBuffer {
-        let encryption_indicator_index = 2_usize;
-        let encryption_indicator = &self.header[encryption_indicator_index]; // the encryption indicator is the 3rd index in the header
-        let method = WhiteflagEncryptionMethod::from_str(encryption_indicator.get()).unwrap();
-        let encoded: WhiteflagBuffer = self.encode().into();
+    ) -> Result<WhiteflagBuffer, WhiteflagError> {
+        let method = self.encryption_method()?;
+        /* the padding of the last byte is encrypted too, as in the reference implementation */
+        let encoded: WhiteflagBuffer = self.encode().into_bytes().into();
 
         match method {
-            WhiteflagEncryptionMethod::NoEncryption => encoded,
-            _ => CryptedBuffer::new(encoded).crypt(cipher, mode),
+            WhiteflagEncryptionMethod::NoEncryption => Ok(encoded),
+            _ => Ok(CryptedBuffer::new(encoded).crypt(cipher, iv, mode)?),
+        }
+    }
+
+    /// 4.2.1.5 Duress Indicator Field
+    pub fn is_under_duress(&self) -> bool {
+        self.header[MessageHeaderOrder::DuressIndicator.as_usize()].get() == DURESS
+    }
+
+    /// sets or clears the duress indicator, signalling whether the message is sent under duress
+    pub fn set_duress_indicator(&mut self, duress: bool) {
+        let value = if duress { DURESS } else { NO_DURESS };
+        self.header[MessageHeaderOrder::DuressIndicator.as_usize()] =
+            Field::new(definitions::header::DURESS_INDICATOR, value.to_string());
+    }
+
+    pub fn set_originator(&mut self, originator: WhiteflagAccount) {
+        self.originator = Some(originator);
+    }
+
+    pub fn set_recipient(&mut self, recipient: WhiteflagAccount) {
+        self.recipient = Some(recipient);
+    }
+
+    pub fn originator(&self) -> Option<&WhiteflagAccount> {
+        self.originator.as_ref()
+    }
+
+    pub fn recipient(&self) -> Option<&WhiteflagAccount> {
+        self.recipient.as_ref()
+    }
+
+    /// encodes the message and encrypts it for the recipient with the method in the encryption indicator
+    ///
+    /// the key is taken from the originator and recipient accounts:
+    /// - method 1 negotiates a key from the originator's ECDH key pair and the recipient's ECDH public key
+    /// - method 2 uses the pre-shared key held on the recipient's account
+    ///
+    /// Whiteflag Specification 5.2.4 Message Encryption
+    pub fn encrypt(&self, iv: &[u8]) -> Result<WhiteflagBuffer, WhiteflagError> {
+        let method = self.encryption_method()?;
+        /* the padding of the last byte is encrypted too, as in the reference implementation */
+        let encoded: WhiteflagBuffer = self.encode().into_bytes().into();
+
+        if let WhiteflagEncryptionMethod::NoEncryption = method {
+            return Ok(encoded);
+        }
+
+        let originator = self
+            .originator
+            .as_ref()
+            .ok_or(WhiteflagError::MissingOriginator)?;
+        let recipient = self
+            .recipient
+            .as_ref()
+            .ok_or(WhiteflagError::MissingRecipient)?;
+
+        let key = encryption_key(&method, originator, recipient, originator)?;
+        Ok(CryptedBuffer::new(encoded).crypt(&key.aes_256_ctr_cipher(), iv, CryptMode::Encrypt)?)
+    }
+
+    /// decrypts and decodes a message received from the originator
+    ///
+    /// this is the counterpart of [`Message::encrypt`]: for method 1 the key is negotiated from the
+    /// recipient's ECDH key pair and the originator's ECDH public key, for method 2 the pre-shared key
+    /// held on the originator's account is used
+    pub fn decrypt(
+        message: WhiteflagBuffer,
+        originator: WhiteflagAccount,
+        recipient: WhiteflagAccount,
+        iv: &[u8],
+    ) -> Result<Self, WhiteflagError> {
+        let indicator = definitions::header::ENCRYPTION_INDICATOR.read(&message)?;
+        let method = WhiteflagEncryptionMethod::from_str(&indicator)?;
+
+        let buffer = match method {
+            WhiteflagEncryptionMethod::NoEncryption => message,
+            _ => {
+                let key = encryption_key(&method, &recipient, &originator, &originator)?;
+                CryptedBuffer::new(message).crypt(
+                    &key.aes_256_ctr_cipher(),
+                    iv,
+                    CryptMode::Decrypt,
+                )?
+            }
+        };
+
+        let mut decrypted = Self::decode_from_buffer(buffer)?;
+        decrypted.set_originator(originator);
+        decrypted.set_recipient(recipient);
+        Ok(decrypted)
+    }
+
+    /// verifies the authentication signature published at the internet resource of an A1 message
+    ///
+    /// the signature must be signed with the originator's key, and state the originator's address and
+    /// the url in the verification data of this message
+    ///
+    /// Whiteflag Specification 5.1.2 Authentication Signature
+    pub fn verify_authentication_signature(
+        &self,
+        signature: &WhiteflagAuthSignature,
+        key: &WhiteflagVerifyingKey,
+    ) -> Result<WhiteflagSignaturePayload, WhiteflagError> {
+        if self.message_code != MessageCodeType::Authentication {
+            return Err(WhiteflagError::NotSignatureAuthentication);
         }
+        let verification_data = match (self.body.first(), self.body.get(1)) {
+            (Some(method), Some(data)) if method.get() == INTERNET_RESOURCE => data,
+            _ => return Err(WhiteflagError::NotSignatureAuthentication),
+        };
+
+        let address = self
+            .originator
+            .as_ref()
+            .ok_or(WhiteflagError::MissingOriginator)?
+            .get_address()
+            .ok_or(WhiteflagError::MissingOriginatorAddress)?;
+
+        Ok(signature.verify_authentication(key, verification_data.get(), &hex::encode(address))?)
+    }
+
+    fn encryption_method(&self) -> Result<WhiteflagEncryptionMethod, WhiteflagError> {
+        let indicator = &self.header[MessageHeaderOrder::EncryptionIndicator.as_usize()];
+        Ok(WhiteflagEncryptionMethod::from_str(indicator.get())?)
     }
 
     /// decode a hexadecimal encoded and encrypted whiteflag message
-    pub fn decode_and_crypt<T: FennelCipher>(
+    pub fn decode_and_crypt<T: WhiteflagCipher>(
         message: WhiteflagBuffer,
         cipher: &T,
+        iv: &[u8],
     ) -> Result<Self, WhiteflagError> {
-        let buffer = CryptedBuffer::new(message).crypt(cipher, CryptMode::Decrypt);
+        let buffer = CryptedBuffer::new(message).crypt(cipher, iv, CryptMode::Decrypt)?;
 
         Self::decode_from_buffer(buffer)
     }
 
-    pub fn encode(&self) -> Vec<u8> {
+    /// encodes the message, keeping its exact bit length
+    pub fn encode(&self) -> EncodedMessage {
         let mut buffer = WhiteflagBuffer::default();
 
         buffer.encode(&self.header);
         buffer.encode(&self.body);
 
-        buffer.crop();
-        buffer.into()
+        EncodedMessage::new(buffer)
     }
 
     pub fn encode_as_hex(&self) -> String {
-        hex::encode(self.encode())
+        self.encode().as_hex()
     }
 
     pub fn get_fields(&self) -> Vec<&Field> {
@@ -149,6 +282,52 @@ impl Message {
     }
 }
 
+/// selects the key for the encryption method from our own account and the other party's account
+/// and derives the message key from it with the originator's address as context
+///
+/// Whiteflag Specification 5.2.3 Key and Token Derivation
+fn encryption_key(
+    method: &WhiteflagEncryptionMethod,
+    own: &WhiteflagAccount,
+    other: &WhiteflagAccount,
+    originator: &WhiteflagAccount,
+) -> Result<WhiteflagEncryptionKey, WhiteflagError> {
+    let mut key = match method {
+        WhiteflagEncryptionMethod::Aes256CtrEcdh => {
+            let keypair = own
+                .get_ecdh_keypair()
+                .ok_or(WhiteflagError::CannotRetrieveKey(
+                    "own account has no ECDH key pair to negotiate a key with",
+                ))?;
+            let public_key =
+                other
+                    .get_ecdh_public_key()
+                    .ok_or(WhiteflagError::CannotRetrieveKey(
+                        "other account has no ECDH public key to negotiate a key with",
+                    ))?;
+            WhiteflagEncryptionKey::from_ecdh_key(public_key, keypair)
+        }
+        WhiteflagEncryptionMethod::Aes256CtrPsk => other
+            .get_shared_key()
+            .ok_or(WhiteflagError::CannotRetrieveKey(
+                "other account has no pre-shared key",
+            ))?
+            .clone(),
+        _ => {
+            return Err(WhiteflagError::CannotRetrieveKey(
+                "encryption method is not supported",
+            ))
+        }
+    };
+
+    let address = originator
+        .get_address()
+        .ok_or(WhiteflagError::MissingOriginatorAddress)?;
+    key.set_context(address);
+
+    Ok(key)
+}
+
 impl<T: FieldValue> From<&[T]> for Message {
     fn from(data: &[T]) -> Self {
         Message::compile(data).unwrap()
@@ -1,4 +1,6 @@
 use super::{crypted_buffer::CryptMode, message::Message};
+use crate::WhiteflagError;
+use wf_account::{account::WfAccount, whiteflag_account::WhiteflagAccount};
 use wf_buffer::WhiteflagBuffer;
 use wf_crypto::{ecdh_keypair::WhiteflagECDHKeyPair, wf_encryption_key::WhiteflagEncryptionKey};
 
@@ -237,13 +239,16 @@ fn message_encryption_1() {
     let iv = vec![
         64, 170, 133, 1, 93, 36, 228, 96, 20, 72, 193, 186, 141, 123, 241, 170,
     ];
-    let cipher = key.aes_256_ctr_cipher(&iv);
+    let cipher = key.aes_256_ctr_cipher();
 
     let message = Message::decode_from_hexadecimal(encoded_msg).unwrap();
 
     assert_eq!(
         encrypted_msg,
-        hex::encode(message.encode_and_crypt(&cipher, CryptMode::Encrypt))
+        message
+            .encode_and_crypt(&cipher, &iv, CryptMode::Encrypt)
+            .unwrap()
+            .as_hex()
     );
 }
 
@@ -262,12 +267,14 @@ fn message_encryption_2() {
 
     key.set_context(address.to_byte_array());
 
-    let iv = aes_tools::generate_random_buffer(16);
-    let cipher = key.aes_256_ctr_cipher(&iv);
+    let iv = wf_crypto::generate_iv();
+    let cipher = key.aes_256_ctr_cipher();
 
     let message1 = Message::deserialize(message_serialized).unwrap();
-    let encrypted_message = message1.encode_and_crypt(&cipher, CryptMode::Encrypt);
-    let message2 = Message::decode_and_crypt(encrypted_message, &cipher).unwrap();
+    let encrypted_message = message1
+        .encode_and_crypt(&cipher, &iv, CryptMode::Encrypt)
+        .unwrap();
+    let message2 = Message::decode_and_crypt(encrypted_message, &cipher, &iv).unwrap();
 
     assert_eq!(message_serialized, message2.serialize());
 }
@@ -287,8 +294,8 @@ fn message_encryption_3() {
 
     key.set_context(address.to_byte_array());
 
-    let iv = aes_tools::generate_random_buffer(16);
-    let cipher = key.aes_256_ctr_cipher(&iv);
+    let iv = wf_crypto::generate_iv();
+    let cipher = key.aes_256_ctr_cipher();
 
     let message1 = Message::deserialize(message_serialized).unwrap();
     assert_eq!(
@@ -296,27 +303,124 @@ fn message_encryption_3() {
         message1.serialize(),
         "failing immediately"
     );
-    let encrypted_message = message1.encode_and_crypt(&cipher, CryptMode::Encrypt);
-    let message2 = Message::decode_and_crypt(encrypted_message, &cipher).unwrap();
+    let encrypted_message = message1
+        .encode_and_crypt(&cipher, &iv, CryptMode::Encrypt)
+        .unwrap();
+    let message2 = Message::decode_and_crypt(encrypted_message, &cipher, &iv).unwrap();
 
     assert_eq!(message_serialized, message2.serialize());
 }
 
-/*
+#[test]
+fn message_encryption_4() {
+    let mut originator = WhiteflagAccount::new(false);
+    let recipient = WhiteflagAccount::new(true);
+    originator.set_address(
+        hex::decode("b77b1cdb02efe1acccf0e277021cb303117bd83c689ea8a64fc549229dba").unwrap(),
+    );
+
+    let mut message = Message::deserialize("WF111Q13efb4e0cfa83122b242634254c1920a769d615dfcc4c670bb53eb6f12843c3ae802013-08-31T04:29:15ZP01D00H00M22+31.79658-033.826028799321000010022003").unwrap();
+    message.set_originator(originator);
+    message.set_recipient(recipient);
+
+    let iv = wf_crypto::generate_iv();
+    assert!(
+        matches!(
+            message.encrypt(&iv),
+            Err(WhiteflagError::CannotRetrieveKey(_))
+        ),
+        "encryption should fail without an ECDH key pair on the originator's account"
+    );
+}
 
 #[test]
-#[should_panic(expected = "WhiteflagError")]
-fn testMessageEncryption4() {
+fn message_encryption_with_preshared_key_account() {
+    let encoded_msg = "5746313223000000000088888889111111119999999a22222222aaaaaaab33333333bbbbbbbb0983098309830983118b118b118b118b1993199319931993219b219b219b219b29a329a329a329a331ab31ab31ab31a9b1b9b1b9b1b9b1b9c1c9c1c9c1c9c1c8";
+    let encrypted_msg = "574631326d7658e7d17479677a0de95076989fcd7825b709349b143f2b17644e5cb2c8ded5c7f18d77447cf9dc2115e0c1c81d717b57fadaeedf27bfef8926448ff666d3d9a65168827c94b393974ebbe6b7f0599e184bfd1ace3569117c23ae17c5640f2f2d";
+    let iv = hex::decode("40aa85015d24e4601448c1ba8d7bf1aa").unwrap();
+    let preshared_key = WhiteflagEncryptionKey::from_preshared_key(
+        "32676187ba7badda85ea63a69870a7133909f1999774abb2eed251073616a6e7",
+    );
+
+    let mut originator = WhiteflagAccount::new(true);
+    originator
+        .set_address(hex::decode("007a0baf6f84f0fa7402ea972686e56d50b707c9b67b108866").unwrap());
+    let mut recipient = WhiteflagAccount::new(false);
+    recipient.set_shared_key(preshared_key.clone());
+
+    let mut message = Message::decode_from_hexadecimal(encoded_msg).unwrap();
+    message.set_originator(originator);
+    message.set_recipient(recipient);
+
+    let encrypted = message.encrypt(&iv).unwrap();
+    assert_eq!(encrypted_msg, encrypted.as_hex());
+
+    /* the receiving side holds the pre-shared key on the originator's account */
+    let mut originator = WhiteflagAccount::new(false);
+    originator
+        .set_address(hex::decode("007a0baf6f84f0fa7402ea972686e56d50b707c9b67b108866").unwrap());
+    originator.set_shared_key(preshared_key);
+    let recipient = WhiteflagAccount::new(true);
+
+    let decrypted = Message::decrypt(encrypted, originator, recipient, &iv).unwrap();
+    assert_eq!(message.serialize(), decrypted.serialize());
+}
+
+#[test]
+fn message_encryption_with_negotiated_key_account() {
+    let message_serialized = "WF111Q13efb4e0cfa83122b242634254c1920a769d615dfcc4c670bb53eb6f12843c3ae802013-08-31T04:29:15ZP01D00H00M22+31.79658-033.826028799321000010022003";
+    let address =
+        hex::decode("b77b1cdb02efe1acccf0e277021cb303117bd83c689ea8a64fc549229dba").unwrap();
+    let originator_keypair = WhiteflagECDHKeyPair::default();
+    let recipient_keypair = WhiteflagECDHKeyPair::default();
+    let iv = wf_crypto::generate_iv();
+
+    /* sending side: own originator account and the recipient's public key */
+    let mut originator = WhiteflagAccount::new(true);
+    originator.set_address(address.clone());
+    originator
+        .set_ecdh_keypair(originator_keypair.clone())
+        .unwrap();
+    let mut recipient = WhiteflagAccount::new(false);
+    recipient
+        .set_ecdh_public_key(*recipient_keypair.as_ref())
+        .unwrap();
+
+    let mut message = Message::deserialize(message_serialized).unwrap();
+    message.set_originator(originator);
+    message.set_recipient(recipient);
+    let encrypted = message.encrypt(&iv).unwrap();
+
+    /* receiving side: own recipient account and the originator's public key */
     let mut originator = WhiteflagAccount::new(false);
+    originator.set_address(address);
+    originator
+        .set_ecdh_public_key(*originator_keypair.as_ref())
+        .unwrap();
     let mut recipient = WhiteflagAccount::new(true);
-    originator.set_address("b77b1cdb02efe1acccf0e277021cb303117bd83c689ea8a64fc549229dba".to_string());
-    recipient.set_ecdh_keypair(generate_wfkeypair());
+    recipient.set_ecdh_keypair(recipient_keypair).unwrap();
+
+    let decrypted = Message::decrypt(encrypted, originator, recipient, &iv).unwrap();
+    assert_eq!(message_serialized, decrypted.serialize());
+}
 
-    let message = BasicMessage::deserialize("WF111Q13efb4e0cfa83122b242634254c1920a769d615dfcc4c670bb53eb6f12843c3ae802013-08-31T04:29:15ZP01D00H00M22+31.79658-033.826028799321000010022003").unwrap();
+#[test]
+fn message_encryption_without_originator_address() {
+    let mut originator = WhiteflagAccount::new(true);
+    originator
+        .set_ecdh_keypair(WhiteflagECDHKeyPair::default())
+        .unwrap();
+    let mut recipient = WhiteflagAccount::new(false);
+    recipient
+        .set_ecdh_public_key(*WhiteflagECDHKeyPair::default().as_ref())
+        .unwrap();
+
+    let mut message = Message::deserialize("WF111Q13efb4e0cfa83122b242634254c1920a769d615dfcc4c670bb53eb6f12843c3ae802013-08-31T04:29:15ZP01D00H00M22+31.79658-033.826028799321000010022003").unwrap();
     message.set_originator(originator);
     message.set_recipient(recipient);
 
-    message.encode();
-    message.get_init_vector();
+    assert!(matches!(
+        message.encrypt(&wf_crypto::generate_iv()),
+        Err(WhiteflagError::MissingOriginatorAddress)
+    ));
 }
- */
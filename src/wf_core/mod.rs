@@ -7,12 +7,33 @@ mod message_feature_parity_tests;
 #[cfg(test)]
 mod edge_case_test;
 
+#[cfg(test)]
+mod duress_tests;
+
+#[cfg(test)]
+mod authentication_tests;
+
+#[cfg(test)]
+mod encoded_message_tests;
+
+#[cfg(test)]
+mod stream_tests;
+
+#[cfg(test)]
+mod transport_tests;
+
 mod crypted_buffer;
+pub mod duress;
+mod encoded_message;
 pub mod error;
 pub mod message;
 mod segment;
+pub mod stream;
 mod wf_message_builder;
 
+pub use crypted_buffer::CryptMode;
+pub use encoded_message::EncodedMessage;
+
 use crate::error::WhiteflagError;
 use message::Message;
 use wf_field::FieldValue;
@@ -20,8 +41,7 @@ use wf_field::FieldValue;
 /// encode an array of values, ordered according to the WF specification, into a hexadecimal string
 pub fn encode<T: FieldValue>(fields: &[T]) -> Result<String, WhiteflagError> {
     let basic_message: Message = Message::compile(fields)?;
-    let message_encoded = basic_message.encode();
-    Ok(hex::encode(message_encoded))
+    Ok(basic_message.encode().as_hex())
 }
 
 /// decode a hexadecimal encoded whiteflag message
@@ -0,0 +1,244 @@
+use super::{encoded_message::EncodedMessage, message::Message};
+use crate::error::WhiteflagError;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use thiserror::Error;
+use wf_codec::CodecError;
+
+/// the first bytes of a length-prefixed container, a magic number and the format version
+pub const CONTAINER_HEADER: &[u8; 4] = b"WFC\x01";
+
+/// the layout of messages in a feed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StreamFormat {
+    /// one hexadecimal encoded message per line
+    HexLines,
+    /// one json message per line
+    JsonLines,
+    /// the [`CONTAINER_HEADER`] followed by records of the bit length of a message as a 32 bit big
+    /// endian integer and the encoded message, its last byte padded with zero bits
+    LengthPrefixed,
+}
+
+#[derive(Error, Debug)]
+pub enum StreamError {
+    #[error("cannot read the stream")]
+    Io(#[from] io::Error),
+    #[error("record {record} is not a valid message")]
+    Record {
+        record: usize,
+        #[source]
+        error: WhiteflagError,
+    },
+    #[error("the stream does not start with the container header")]
+    InvalidHeader,
+    #[error("record {record} ends {missing} bytes early")]
+    Truncated { record: usize, missing: usize },
+}
+
+/// iterates the messages of a feed
+///
+/// a record that cannot be decoded, or a line that is not UTF-8, yields [`StreamError::Record`] and
+/// reading continues with the next one; records are numbered from 1, by line for the line formats.
+/// blank lines are skipped. errors that leave the position in the stream unknown end the iteration
+pub struct MessageReader<R: Read> {
+    reader: BufReader<R>,
+    format: StreamFormat,
+    record: usize,
+    line: Vec<u8>,
+    started: bool,
+    finished: bool,
+}
+
+impl<R: Read> MessageReader<R> {
+    pub fn new(reader: R, format: StreamFormat) -> Self {
+        MessageReader {
+            reader: BufReader::new(reader),
+            format,
+            record: 0,
+            line: Vec::new(),
+            started: false,
+            finished: false,
+        }
+    }
+
+    /// the number of the last record read
+    pub fn record(&self) -> usize {
+        self.record
+    }
+
+    fn next_line(&mut self) -> Option<Result<Message, StreamError>> {
+        loop {
+            self.line.clear();
+            match self.reader.read_until(b'\n', &mut self.line) {
+                Ok(0) => return None,
+                Ok(_) => self.record += 1,
+                Err(e) => return Some(Err(self.fail(e.into()))),
+            }
+
+            let line = match std::str::from_utf8(&self.line) {
+                Ok(line) => line.trim(),
+                Err(e) => {
+                    return Some(Err(StreamError::Record {
+                        record: self.record,
+                        error: CodecError::from(e).into(),
+                    }))
+                }
+            };
+            if line.is_empty() {
+                continue;
+            }
+
+            let message = match self.format {
+                StreamFormat::JsonLines => Message::deserialize_from_json(line),
+                _ => Message::decode_from_hexadecimal(line),
+            };
+            return Some(message.map_err(|error| StreamError::Record {
+                record: self.record,
+                error,
+            }));
+        }
+    }
+
+    fn next_record(&mut self) -> Option<Result<Message, StreamError>> {
+        if !self.started {
+            self.started = true;
+            let mut header = [0; CONTAINER_HEADER.len()];
+            if let Err(e) = self.read_exact(&mut header) {
+                return e.map(|e| Err(self.fail(e)));
+            }
+            if &header != CONTAINER_HEADER {
+                return Some(Err(self.fail(StreamError::InvalidHeader)));
+            }
+        }
+
+        let mut prefix = [0; 4];
+        if let Err(e) = self.read_exact(&mut prefix) {
+            return e.map(|e| Err(self.fail(e)));
+        }
+        self.record += 1;
+
+        let bit_length = u32::from_be_bytes(prefix) as usize;
+        let length = bit_length.div_ceil(8);
+        let mut bytes = Vec::new();
+        if let Err(e) = (&mut self.reader)
+            .take(length as u64)
+            .read_to_end(&mut bytes)
+        {
+            return Some(Err(self.fail(e.into())));
+        }
+        if bytes.len() < length {
+            return Some(Err(self.fail(StreamError::Truncated {
+                record: self.record,
+                missing: length - bytes.len(),
+            })));
+        }
+
+        let message =
+            EncodedMessage::from_bytes(bytes, bit_length).and_then(EncodedMessage::decode);
+        Some(message.map_err(|error| StreamError::Record {
+            record: self.record,
+            error,
+        }))
+    }
+
+    /// fills `buffer`, failing with `None` at the end of the stream and
+    /// [`StreamError::Truncated`] if it ends part way
+    fn read_exact(&mut self, buffer: &mut [u8]) -> Result<(), Option<StreamError>> {
+        let mut read = 0;
+        while read < buffer.len() {
+            match self.reader.read(&mut buffer[read..]) {
+                Ok(0) if read == 0 => return Err(None),
+                Ok(0) => {
+                    return Err(Some(StreamError::Truncated {
+                        record: self.record + 1,
+                        missing: buffer.len() - read,
+                    }))
+                }
+                Ok(n) => read += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(Some(e.into())),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fail(&mut self, error: StreamError) -> StreamError {
+        self.finished = true;
+        error
+    }
+}
+
+impl<R: Read> Iterator for MessageReader<R> {
+    type Item = Result<Message, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let next = match self.format {
+            StreamFormat::LengthPrefixed => self.next_record(),
+            _ => self.next_line(),
+        };
+        if next.is_none() {
+            self.finished = true;
+        }
+
+        next
+    }
+}
+
+/// writes messages in a [`StreamFormat`], the container header is written on creation
+pub struct MessageWriter<W: Write> {
+    writer: W,
+    format: StreamFormat,
+}
+
+impl<W: Write> MessageWriter<W> {
+    pub fn new(mut writer: W, format: StreamFormat) -> io::Result<Self> {
+        if format == StreamFormat::LengthPrefixed {
+            writer.write_all(CONTAINER_HEADER)?;
+        }
+
+        Ok(MessageWriter { writer, format })
+    }
+
+    pub fn write(&mut self, message: &Message) -> io::Result<()> {
+        match self.format {
+            StreamFormat::JsonLines => {
+                serde_json::to_writer(&mut self.writer, message)?;
+                self.writer.write_all(b"\n")
+            }
+            _ => self.write_encoded(&message.encode()),
+        }
+    }
+
+    /// writes an encoded message, as hexadecimal or with its bit length
+    pub fn write_encoded(&mut self, message: &EncodedMessage) -> io::Result<()> {
+        match self.format {
+            StreamFormat::LengthPrefixed => {
+                let bit_length = u32::try_from(message.bit_length())
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+                self.writer.write_all(&bit_length.to_be_bytes())?;
+                self.writer.write_all(message.as_bytes())
+            }
+            StreamFormat::HexLines => writeln!(self.writer, "{}", message.as_hex()),
+            StreamFormat::JsonLines => {
+                let message = message
+                    .clone()
+                    .decode()
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+                self.write(&message)
+            }
+        }
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
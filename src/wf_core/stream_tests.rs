@@ -0,0 +1,193 @@
+use super::{
+    message::Message,
+    stream::{MessageReader, MessageWriter, StreamError, StreamFormat, CONTAINER_HEADER},
+};
+use crate::error::WhiteflagError;
+use wf_codec::CodecError;
+
+const AUTH_MESSAGE: &str = "5746313020800000000000000000000000000000000000000000000000000000000000000000b43a3a38399d1797b7b933b0b734b9b0ba34b7b71734b73a17bbb434ba32b33630b380";
+const SIGNAL_MESSAGE: &str = "57463130a6a1f7da7067d41891592131a12a60c9053b4eb0aefe6263385da9f5b789421e1d7401009841882148a800000114c1e596006f04c050eca6420084";
+
+fn messages() -> Vec<Message> {
+    vec![
+        Message::decode_from_hexadecimal(AUTH_MESSAGE).unwrap(),
+        Message::decode_from_hexadecimal(SIGNAL_MESSAGE).unwrap(),
+        Message::compile(&[
+            "WF",
+            "1",
+            "0",
+            "0",
+            "K",
+            "0",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "11",
+            "abc",
+        ])
+        .unwrap(),
+    ]
+}
+
+fn write(format: StreamFormat, messages: &[Message]) -> Vec<u8> {
+    let mut writer = MessageWriter::new(Vec::new(), format).unwrap();
+    for message in messages {
+        writer.write(message).unwrap();
+    }
+    writer.into_inner()
+}
+
+fn serialized(data: &[u8], format: StreamFormat) -> Vec<Result<String, StreamError>> {
+    MessageReader::new(data, format)
+        .map(|m| m.map(|m| m.serialize()))
+        .collect()
+}
+
+#[test]
+fn formats_round_trip() {
+    let messages = messages();
+    let expected: Vec<String> = messages.iter().map(Message::serialize).collect();
+
+    for format in [StreamFormat::JsonLines, StreamFormat::LengthPrefixed] {
+        let data = write(format, &messages);
+        let read: Vec<String> = serialized(&data, format)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(expected, read, "{:?} should round trip", format);
+    }
+
+    /* hexadecimal lines lose the bit length, an odd number of crypto data digits gains a 0 */
+    let data = write(StreamFormat::HexLines, &messages);
+    let read = serialized(&data, StreamFormat::HexLines);
+    assert_eq!(
+        expected[..2],
+        read[..2]
+            .iter()
+            .map(|m| m.as_ref().unwrap().clone())
+            .collect::<Vec<_>>()
+    );
+    assert!(read[2].as_ref().unwrap().ends_with("11abc0"));
+}
+
+#[test]
+fn container_is_length_prefixed() {
+    let data = write(StreamFormat::LengthPrefixed, &messages()[..1]);
+
+    assert_eq!(CONTAINER_HEADER, &data[..4]);
+    assert_eq!(577u32.to_be_bytes(), data[4..8]);
+    assert_eq!(AUTH_MESSAGE, hex::encode(&data[8..]));
+}
+
+#[test]
+fn invalid_records_do_not_end_the_stream() {
+    let data = format!(
+        "{}\n\nnot hexadecimal\n{}\n5746\n{}\n",
+        AUTH_MESSAGE, SIGNAL_MESSAGE, AUTH_MESSAGE
+    );
+    let read = serialized(data.as_bytes(), StreamFormat::HexLines);
+
+    assert_eq!(5, read.len());
+    assert!(read[0].is_ok());
+    assert!(matches!(
+        read[1],
+        Err(StreamError::Record { record: 3, .. })
+    ));
+    assert!(read[2].is_ok());
+    assert!(matches!(
+        read[3],
+        Err(StreamError::Record { record: 5, .. })
+    ));
+    assert!(read[4].is_ok());
+}
+
+#[test]
+fn lines_that_are_not_utf8_are_reported() {
+    let data = [
+        AUTH_MESSAGE.as_bytes(),
+        b"\n\xff\n",
+        SIGNAL_MESSAGE.as_bytes(),
+        b"\n",
+    ]
+    .concat();
+
+    for format in [StreamFormat::HexLines, StreamFormat::JsonLines] {
+        let read: Vec<_> = MessageReader::new(data.as_slice(), format).collect();
+        assert_eq!(3, read.len());
+        assert!(matches!(
+            read[1],
+            Err(StreamError::Record {
+                record: 2,
+                error: WhiteflagError::Codec(CodecError::UTF8(_))
+            })
+        ));
+    }
+
+    let read = serialized(&data, StreamFormat::HexLines);
+    assert!(read[0].is_ok());
+    assert!(read[2].is_ok());
+}
+
+#[test]
+fn invalid_json_lines_are_reported() {
+    let mut data = write(StreamFormat::JsonLines, &messages()[..1]);
+    data.extend_from_slice(b"{\"prefix\": \"WF\"}\n");
+    data.extend(write(StreamFormat::JsonLines, &messages()[1..2]));
+
+    let read = serialized(&data, StreamFormat::JsonLines);
+    assert_eq!(3, read.len());
+    assert!(matches!(
+        read[1],
+        Err(StreamError::Record { record: 2, .. })
+    ));
+    assert!(read[2].is_ok());
+}
+
+#[test]
+fn invalid_container_records_are_reported() {
+    let mut data = write(StreamFormat::LengthPrefixed, &messages()[..1]);
+    /* 16 bits of "WF" are not a message */
+    data.extend_from_slice(&16u32.to_be_bytes());
+    data.extend_from_slice(b"WF");
+    data.extend_from_slice(&write(StreamFormat::LengthPrefixed, &messages()[1..2])[4..]);
+
+    let read = serialized(&data, StreamFormat::LengthPrefixed);
+    assert_eq!(3, read.len());
+    assert!(read[0].is_ok());
+    assert!(matches!(
+        read[1],
+        Err(StreamError::Record { record: 2, .. })
+    ));
+    assert!(read[2].is_ok());
+}
+
+#[test]
+fn truncated_container_ends_the_stream() {
+    let data = write(StreamFormat::LengthPrefixed, &messages()[..2]);
+
+    let read = serialized(&data[..data.len() - 3], StreamFormat::LengthPrefixed);
+    assert_eq!(2, read.len());
+    assert!(read[0].is_ok());
+    assert!(matches!(
+        read[1],
+        Err(StreamError::Truncated {
+            record: 2,
+            missing: 3
+        })
+    ));
+
+    let read = serialized(&data[..6], StreamFormat::LengthPrefixed);
+    assert!(matches!(
+        read[..],
+        [Err(StreamError::Truncated {
+            record: 1,
+            missing: 2
+        })]
+    ));
+}
+
+#[test]
+fn container_needs_header() {
+    let read = serialized(AUTH_MESSAGE.as_bytes(), StreamFormat::LengthPrefixed);
+    assert!(matches!(read[..], [Err(StreamError::InvalidHeader)]));
+
+    assert!(serialized(&[], StreamFormat::LengthPrefixed).is_empty());
+}
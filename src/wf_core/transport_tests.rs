@@ -0,0 +1,161 @@
+use super::{
+    duress::{receive, ReceivedMessage},
+    message::Message,
+};
+use wf_account::{account::WfAccount, whiteflag_account::WhiteflagAccount};
+use wf_auth::{WhiteflagAuthSignature, WhiteflagSignaturePayload, WhiteflagSigningKey};
+use wf_crypto::ecdh_keypair::WhiteflagECDHKeyPair;
+use wf_transport::{
+    Eip1559Transaction, EthereumAccount, EthereumDevNode, MockLedger, SignedTransaction, Transport,
+};
+
+const SIGNAL: &str = "WF101Q13efb4e0cfa83122b242634254c1920a769d615dfcc4c670bb53eb6f12843c3ae802013-08-31T04:29:15ZP01D00H00M22+31.79658-033.826028799321000010022003";
+const FREE_TEXT: &str =
+    "WF100F00000000000000000000000000000000000000000000000000000000000000000hello bob";
+const AUTHENTICATION: &str = "WF100A000000000000000000000000000000000000000000000000000000000000000001https://organisation.int/whiteflag";
+
+#[test]
+fn send_and_receive_a_message() {
+    let alice = hex::decode("007a0baf6f84f0fa7402ea972686e56d50b707c9b67b108866").unwrap();
+    let mut ledger = MockLedger::in_memory();
+    let mut subscription_ledger = ledger.clone();
+    let mut subscription = subscription_ledger.subscribe().unwrap();
+
+    let message = Message::deserialize(FREE_TEXT).unwrap();
+    let hash = ledger.send(&alice, &message.encode().into()).unwrap();
+
+    let incoming = subscription.poll().unwrap().remove(0);
+    assert_eq!(hash, incoming.transaction_hash);
+    assert_eq!(alice, incoming.originator);
+
+    /* the zero bits padding the last byte on the blockchain are not part of the message */
+    let received = Message::decode_from_buffer(incoming.message).unwrap();
+    assert_eq!(FREE_TEXT, received.serialize());
+    assert!(matches!(
+        receive(received, &mut |_: &Message| {}),
+        ReceivedMessage::Message(_)
+    ));
+}
+
+#[test]
+fn send_and_receive_an_authentication_message() {
+    let alice = hex::decode("007a0baf6f84f0fa7402ea972686e56d50b707c9b67b108866").unwrap();
+    let key = WhiteflagSigningKey::eddsa_from_bytes(&[1; 32]).unwrap();
+    let mut ledger = MockLedger::in_memory();
+
+    /* alice publishes her signature at the url of the authentication message she sends */
+    let payload = WhiteflagSignaturePayload::new(
+        hex::encode(&alice),
+        "Whiteflag Test Organisation",
+        "https://organisation.int/whiteflag",
+    );
+    let signature = WhiteflagAuthSignature::sign(&payload, &key).unwrap();
+    let message = Message::deserialize(AUTHENTICATION).unwrap();
+    ledger.send(&alice, &message.encode().into()).unwrap();
+
+    /* bob verifies the signature against the address the message was sent from */
+    let incoming = ledger.messages_from(0).unwrap().remove(0);
+    let mut originator = WhiteflagAccount::new(false);
+    originator.set_address(incoming.originator);
+    let mut received = Message::decode_from_buffer(incoming.message).unwrap();
+    assert_eq!(AUTHENTICATION, received.serialize());
+    received.set_originator(originator);
+
+    let verified = received
+        .verify_authentication_signature(&signature, &key.verifying_key())
+        .unwrap();
+    assert_eq!(payload, verified);
+
+    /* a signature for another address does not authenticate alice */
+    let other = WhiteflagSignaturePayload::new(
+        "00112233",
+        "Whiteflag Test Organisation",
+        "https://organisation.int/whiteflag",
+    );
+    let other = WhiteflagAuthSignature::sign(&other, &key).unwrap();
+    assert!(received
+        .verify_authentication_signature(&other, &key.verifying_key())
+        .is_err());
+}
+
+#[test]
+fn send_receive_and_decrypt_a_message() {
+    let alice =
+        hex::decode("b77b1cdb02efe1acccf0e277021cb303117bd83c689ea8a64fc549229dba").unwrap();
+    let alice_keypair = WhiteflagECDHKeyPair::default();
+    let bob_keypair = WhiteflagECDHKeyPair::default();
+    let iv = wf_crypto::generate_iv();
+    let mut ledger = MockLedger::in_memory();
+
+    /* alice encrypts for bob and sends the message */
+    let mut originator = WhiteflagAccount::new(true);
+    originator.set_address(alice.clone());
+    originator.set_ecdh_keypair(alice_keypair.clone()).unwrap();
+    let mut recipient = WhiteflagAccount::new(false);
+    recipient
+        .set_ecdh_public_key(*bob_keypair.as_ref())
+        .unwrap();
+
+    let mut message = Message::deserialize(SIGNAL).unwrap();
+    message.set_originator(originator);
+    message.set_recipient(recipient);
+    ledger.send(&alice, &message.encrypt(&iv).unwrap()).unwrap();
+
+    /* bob reads it and knows alice by the address it was sent from */
+    let incoming = ledger.clone().messages_from(0).unwrap().remove(0);
+    assert_eq!(alice, incoming.originator);
+
+    let mut originator = WhiteflagAccount::new(false);
+    originator.set_address(incoming.originator);
+    originator
+        .set_ecdh_public_key(*alice_keypair.as_ref())
+        .unwrap();
+    let mut recipient = WhiteflagAccount::new(true);
+    recipient.set_ecdh_keypair(bob_keypair).unwrap();
+
+    let decrypted = Message::decrypt(incoming.message, originator, recipient, &iv).unwrap();
+    assert_eq!(SIGNAL, decrypted.serialize());
+}
+
+#[test]
+fn send_and_receive_a_message_on_ethereum() {
+    let secret =
+        hex::decode("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318").unwrap();
+    let mut node = EthereumDevNode::new(1337);
+    let alice = node.add_account(EthereumAccount::from_secret_key(&secret).unwrap());
+
+    let message = Message::deserialize(SIGNAL).unwrap();
+    let hash = node.send(&alice, &message.encode().into()).unwrap();
+
+    let incoming = node.messages_from(0).unwrap().remove(0);
+    assert_eq!(hash, incoming.transaction_hash);
+    assert_eq!(alice.to_vec(), incoming.originator);
+    assert_eq!(
+        SIGNAL,
+        Message::decode_from_buffer(incoming.message)
+            .unwrap()
+            .serialize()
+    );
+
+    /* the same message signed offline and sent as a raw transaction */
+    let account = EthereumAccount::from_secret_key(&secret).unwrap();
+    let transaction = Eip1559Transaction::whiteflag(
+        node.chain_id(),
+        node.nonce(&alice).unwrap(),
+        alice,
+        &message.encode().into(),
+    );
+    let raw = account.sign(transaction).unwrap().encode();
+    node.send_raw_transaction(&raw).unwrap();
+
+    let extracted = SignedTransaction::decode(&raw)
+        .unwrap()
+        .transaction
+        .whiteflag_message()
+        .unwrap();
+    assert_eq!(
+        SIGNAL,
+        Message::decode_from_buffer(extracted).unwrap().serialize()
+    );
+    assert_eq!(2, node.messages_from(0).unwrap().len());
+}
@@ -1,6 +1,7 @@
 use std::ops::Div;
 use wf_buffer::WhiteflagBuffer;
 use wf_codec::CodecError;
+use wf_common::constants::BYTE;
 use wf_field::{FieldDefinition, FieldDefinitionParser, FieldValue};
 use wf_parser::Parser;
 use wf_validation::Validation;
@@ -33,7 +34,17 @@ pub struct FieldValuesParser<'a, T: FieldValue> {
 
 impl<T: FieldValue> FieldDefinitionParser for FieldValuesParser<'_, T> {
     fn parse(&mut self, definition: &FieldDefinition) -> Result<String, CodecError> {
-        let value = self.data[self.index].as_ref();
+        let value = match self.data.get(self.index) {
+            Some(value) => value.as_ref(),
+            None => {
+                return Err(CodecError::Validation {
+                    error: format!(
+                        "missing value for {}",
+                        definition.get_name().unwrap_or("field")
+                    ),
+                })
+            }
+        };
 
         if let Err(e) = definition.validate(value) {
             return Err(CodecError::Validation {
@@ -60,9 +71,13 @@ impl FieldDefinitionParser for EncodedMessageParser {
     fn parse(&mut self, definition: &FieldDefinition) -> Result<String, CodecError> {
         let value = self
             .buffer
-            .extract_message_value(definition, self.bit_cursor);
-        self.bit_cursor += definition.bit_length();
-        value
+            .extract_message_value(definition, self.bit_cursor)?;
+        /* fields without a fixed length are as long as the value read */
+        self.bit_cursor += match definition.bit_length() {
+            0 => definition.bytes.encoding.convert_to_bit_length(value.len()),
+            bit_length => bit_length,
+        };
+        Ok(value)
     }
 
     fn remaining(&self) -> usize {
@@ -83,10 +98,34 @@ pub fn builder_from_serialized(message: &str) -> Result<Parser, wf_field::Error>
     Parser::parse(parser)
 }
 
+impl EncodedMessageParser {
+    /// the bits after the last field may only pad the last byte, and must be zero
+    fn check_padding(&self) -> Result<(), CodecError> {
+        let mut reader = self.buffer.reader();
+        reader.seek(self.bit_cursor);
+
+        let mut padding = Vec::new();
+        let bits = reader.read_bits(reader.remaining(), &mut padding);
+        if bits >= BYTE || padding.iter().any(|&b| b != 0) {
+            return Err(CodecError::InvalidPadding { bits });
+        }
+
+        Ok(())
+    }
+}
+
+/// decodes a buffer with the bit length it carries
+///
+/// the last field takes every whole character that fits, so a buffer decoded from bytes rather than
+/// an [`EncodedMessage`](super::EncodedMessage) reads padding of four or more bits as a trailing `0`
+/// of a hexadecimal or decimal field
 pub fn builder_from_encoded(message: WhiteflagBuffer) -> Result<Parser, wf_field::Error> {
-    let parser = EncodedMessageParser {
+    let mut parser = EncodedMessageParser {
         buffer: message,
         bit_cursor: 0,
     };
-    Parser::parse(parser)
+    let parsed = Parser::parse(&mut parser)?;
+
+    parser.check_padding()?;
+    Ok(parsed)
 }
@@ -79,7 +79,11 @@ fn name_map(name: &str) -> Result<usize, String> {
         "objectOrientation" => 23,
         /* request */
         "objectTypeQuant" => 24,
-        _ => return Err(format!("missing index for field: {}", &name)),
+        /* numbered request pairs follow the sign fields */
+        _ => match super::serialize::request_pair(name) {
+            Some((n, quant)) => 22 + 2 * n + usize::from(quant),
+            None => return Err(format!("missing index for field: {}", &name)),
+        },
     };
 
     Ok(index)
@@ -1,5 +1,6 @@
 use crate::wf_core::message::Message;
-use serde::ser::{Serialize, SerializeStruct, Serializer};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::borrow::Cow;
 
 impl Serialize for Message {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -9,18 +10,18 @@ impl Serialize for Message {
         let fields = self.get_fields();
         let length = fields.len();
 
-        let mut state = serializer.serialize_struct("BasicMessage", length)?;
+        let mut state = serializer.serialize_map(Some(length))?;
 
         for f in fields {
             let json_name = name_map(f.get_name()).map_err(serde::ser::Error::custom)?;
-            state.serialize_field(json_name, f.get())?;
+            state.serialize_entry(&json_name, f.get())?;
         }
 
         state.end()
     }
 }
 
-fn name_map(name: &str) -> Result<&'static str, String> {
+fn name_map(name: &str) -> Result<Cow<'static, str>, String> {
     let json_name = match name {
         /* headers */
         "Prefix" => "prefix",
@@ -55,8 +56,28 @@ fn name_map(name: &str) -> Result<&'static str, String> {
         "ObjectOrientation" => "objectOrientation",
         /* request */
         "ObjectTypeQuant" => "objectTypeQuant",
-        _ => return Err(format!("missing support for field name: {}", name)),
+        /* numbered request pairs, ObjectType1 and ObjectType1Quant */
+        _ => match request_pair(name) {
+            Some(_) => return Ok(Cow::Owned(format!("o{}", &name[1..]))),
+            None => return Err(format!("missing support for field name: {}", name)),
+        },
     };
 
-    Ok(json_name)
+    Ok(Cow::Borrowed(json_name))
+}
+
+/// the number of a request pair field and whether it is the quantity
+pub(super) fn request_pair(name: &str) -> Option<(usize, bool)> {
+    let pair = name
+        .strip_prefix("ObjectType")
+        .or_else(|| name.strip_prefix("objectType"))?;
+    let (number, quant) = match pair.strip_suffix("Quant") {
+        Some(number) => (number, true),
+        None => (pair, false),
+    };
+
+    match number.parse() {
+        Ok(n) if n > 0 && !number.starts_with(['+', '0']) => Some((n, quant)),
+        _ => None,
+    }
 }
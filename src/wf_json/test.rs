@@ -1,4 +1,6 @@
-use crate::{decode_from_hex, wf_json::deserialize::WhiteflagFieldValues};
+use crate::{
+    decode_from_hex, wf_core::message::Message, wf_json::deserialize::WhiteflagFieldValues,
+};
 use serde_json::json;
 
 #[test]
@@ -64,6 +66,37 @@ fn test_serialize_auth_message_from_hex() {
     test_json(&json, &decode_from_hex(hex).unwrap());
 }
 
+#[test]
+fn request_pairs_are_numbered() {
+    let json = json!({
+        "prefix": "WF",
+        "version": "1",
+        "encryptionIndicator": "0",
+        "duressIndicator": "0",
+        "messageCode": "Q",
+        "referenceIndicator": "0",
+        "referencedMessage": "0000000000000000000000000000000000000000000000000000000000000000",
+        "subjectCode": "80",
+        "dateTime": "2013-08-31T04:29:15Z",
+        "duration": "P01D00H00M",
+        "objectType": "22",
+        "objectLatitude": "+31.79658",
+        "objectLongitude": "-033.82602",
+        "objectSizeDim1": "8799",
+        "objectSizeDim2": "3210",
+        "objectOrientation": "000",
+        "objectType1": "10",
+        "objectType1Quant": "02",
+        "objectType2": "20",
+        "objectType2Quant": "03"
+    })
+    .to_string();
+
+    let message = Message::deserialize_from_json(&json).unwrap();
+    assert!(message.serialize().ends_with("00010022003"));
+    test_json(&json, &serde_json::to_string(&message).unwrap());
+}
+
 fn test_json(actual: &str, expected: &str) {
     let a = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(actual).unwrap();
     let e = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(expected).unwrap();
@@ -38,7 +38,7 @@ impl WfAccount for WhiteflagAccount {
         self.address = Some(address);
     }
 
-    fn get_address(&mut self) -> Option<&Vec<u8>> {
+    fn get_address(&self) -> Option<&Vec<u8>> {
         if self.address.is_none() {
             None
         } else {
@@ -46,7 +46,7 @@ impl WfAccount for WhiteflagAccount {
         }
     }
 
-    fn get_auth_url(&mut self) -> Option<&Vec<u8>> {
+    fn get_auth_url(&self) -> Option<&Vec<u8>> {
         self.auth_url.as_ref()
     }
 
@@ -54,7 +54,7 @@ impl WfAccount for WhiteflagAccount {
         self.auth_url = Some(url);
     }
 
-    fn get_auth_token(&mut self) -> Option<&WhiteflagAuthToken> {
+    fn get_auth_token(&self) -> Option<&WhiteflagAuthToken> {
         self.auth_token.as_ref()
     }
 
@@ -62,7 +62,7 @@ impl WfAccount for WhiteflagAccount {
         self.auth_token = Some(token)
     }
 
-    fn get_shared_key(&mut self) -> Option<&WhiteflagEncryptionKey> {
+    fn get_shared_key(&self) -> Option<&WhiteflagEncryptionKey> {
         self.shared_key.as_ref()
     }
 
@@ -70,7 +70,7 @@ impl WfAccount for WhiteflagAccount {
         self.shared_key = Some(key);
     }
 
-    fn get_ecdh_keypair(&mut self) -> Option<&WhiteflagECDHKeyPair> {
+    fn get_ecdh_keypair(&self) -> Option<&WhiteflagECDHKeyPair> {
         self.ecdh_keypair.as_ref()
     }
 
@@ -87,7 +87,7 @@ impl WfAccount for WhiteflagAccount {
         }
     }
 
-    fn get_ecdh_public_key(&mut self) -> Option<&PublicKey> {
+    fn get_ecdh_public_key(&self) -> Option<&PublicKey> {
         self.ecdh_public_key.as_ref()
     }
 
@@ -1,6 +1,16 @@
 #[cfg(test)]
 mod test;
 
+#[cfg(test)]
+mod signature_tests;
+
+mod signature;
+
+pub use signature::{
+    SignatureAlgorithm, SignatureError, SignatureResult, WhiteflagAuthSignature,
+    WhiteflagSignaturePayload, WhiteflagSigningKey, WhiteflagVerifyingKey,
+};
+
 use wf_crypto::{hkdf, CryptoResult};
 
 /// Whiteflag specifies two methods for authentication
@@ -0,0 +1,301 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ed25519_dalek::{Signer, Verifier};
+use serde::{Deserialize, Serialize};
+
+pub type SignatureResult<T> = Result<T, SignatureError>;
+
+#[derive(thiserror::Error, Debug)]
+pub enum SignatureError {
+    #[error("issue decoding base64url string")]
+    Base64Decode(#[from] base64::DecodeError),
+    #[error("issue (de)serializing json")]
+    Json(#[from] serde_json::Error),
+    #[error("invalid key")]
+    InvalidKey,
+    #[error("jws is not in compact serialization")]
+    MalformedCompact,
+    #[error("signature algorithm {0} is not supported")]
+    UnsupportedAlgorithm(String),
+    #[error("signature algorithm {signed} does not match the {key} verification key")]
+    AlgorithmMismatch {
+        signed: SignatureAlgorithm,
+        key: SignatureAlgorithm,
+    },
+    #[error("signature does not verify")]
+    InvalidSignature,
+    #[error("signed {0} does not match the authentication message")]
+    PayloadMismatch(&'static str),
+}
+
+/// JWS algorithms for Whiteflag authentication signatures
+/// (5.1.2.1 https://standard.whiteflagprotocol.org/v1/)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignatureAlgorithm {
+    /// ECDSA using P-256 and SHA-256
+    ES256,
+    /// Ed25519
+    EdDSA,
+}
+
+impl std::fmt::Display for SignatureAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignatureAlgorithm::ES256 => write!(f, "ES256"),
+            SignatureAlgorithm::EdDSA => write!(f, "EdDSA"),
+        }
+    }
+}
+
+/// the statement signed by the originator
+/// - `addr` the blockchain address of the originator
+/// - `orgname` the name of the originator
+/// - `url` the internet resource the signature is published at, which is the verification data of the A1 message
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WhiteflagSignaturePayload {
+    pub addr: String,
+    pub orgname: String,
+    pub url: String,
+}
+
+impl WhiteflagSignaturePayload {
+    pub fn new<A: Into<String>, O: Into<String>, U: Into<String>>(
+        addr: A,
+        orgname: O,
+        url: U,
+    ) -> Self {
+        WhiteflagSignaturePayload {
+            addr: addr.into(),
+            orgname: orgname.into(),
+            url: url.into(),
+        }
+    }
+}
+
+pub enum WhiteflagSigningKey {
+    ES256(p256::ecdsa::SigningKey),
+    EdDSA(ed25519_dalek::SigningKey),
+}
+
+impl WhiteflagSigningKey {
+    /// P-256 private key as 32 big endian bytes
+    pub fn es256_from_bytes(secret: &[u8]) -> SignatureResult<Self> {
+        let key =
+            p256::ecdsa::SigningKey::from_slice(secret).map_err(|_| SignatureError::InvalidKey)?;
+        Ok(WhiteflagSigningKey::ES256(key))
+    }
+
+    /// Ed25519 private key as 32 bytes
+    pub fn eddsa_from_bytes(secret: &[u8]) -> SignatureResult<Self> {
+        let secret: [u8; 32] = secret.try_into().map_err(|_| SignatureError::InvalidKey)?;
+        Ok(WhiteflagSigningKey::EdDSA(
+            ed25519_dalek::SigningKey::from_bytes(&secret),
+        ))
+    }
+
+    pub fn algorithm(&self) -> SignatureAlgorithm {
+        match self {
+            WhiteflagSigningKey::ES256(_) => SignatureAlgorithm::ES256,
+            WhiteflagSigningKey::EdDSA(_) => SignatureAlgorithm::EdDSA,
+        }
+    }
+
+    pub fn verifying_key(&self) -> WhiteflagVerifyingKey {
+        match self {
+            WhiteflagSigningKey::ES256(key) => WhiteflagVerifyingKey::ES256(*key.verifying_key()),
+            WhiteflagSigningKey::EdDSA(key) => WhiteflagVerifyingKey::EdDSA(key.verifying_key()),
+        }
+    }
+
+    fn sign(&self, message: &[u8]) -> Vec<u8> {
+        match self {
+            WhiteflagSigningKey::ES256(key) => {
+                let signature: p256::ecdsa::Signature = key.sign(message);
+                signature.to_bytes().to_vec()
+            }
+            WhiteflagSigningKey::EdDSA(key) => key.sign(message).to_bytes().to_vec(),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum WhiteflagVerifyingKey {
+    ES256(p256::ecdsa::VerifyingKey),
+    EdDSA(ed25519_dalek::VerifyingKey),
+}
+
+impl WhiteflagVerifyingKey {
+    /// P-256 public key as a SEC1 encoded point, compressed or uncompressed
+    pub fn es256_from_bytes(public_key: &[u8]) -> SignatureResult<Self> {
+        let key = p256::ecdsa::VerifyingKey::from_sec1_bytes(public_key)
+            .map_err(|_| SignatureError::InvalidKey)?;
+        Ok(WhiteflagVerifyingKey::ES256(key))
+    }
+
+    /// Ed25519 public key as 32 bytes
+    pub fn eddsa_from_bytes(public_key: &[u8]) -> SignatureResult<Self> {
+        let public_key: [u8; 32] = public_key
+            .try_into()
+            .map_err(|_| SignatureError::InvalidKey)?;
+        let key = ed25519_dalek::VerifyingKey::from_bytes(&public_key)
+            .map_err(|_| SignatureError::InvalidKey)?;
+        Ok(WhiteflagVerifyingKey::EdDSA(key))
+    }
+
+    pub fn algorithm(&self) -> SignatureAlgorithm {
+        match self {
+            WhiteflagVerifyingKey::ES256(_) => SignatureAlgorithm::ES256,
+            WhiteflagVerifyingKey::EdDSA(_) => SignatureAlgorithm::EdDSA,
+        }
+    }
+
+    fn verify(&self, message: &[u8], signature: &[u8]) -> SignatureResult<()> {
+        let verified = match self {
+            WhiteflagVerifyingKey::ES256(key) => p256::ecdsa::Signature::from_slice(signature)
+                .map(|signature| key.verify(message, &signature).is_ok()),
+            WhiteflagVerifyingKey::EdDSA(key) => ed25519_dalek::Signature::from_slice(signature)
+                .map(|signature| key.verify(message, &signature).is_ok()),
+        };
+
+        match verified {
+            Ok(true) => Ok(()),
+            _ => Err(SignatureError::InvalidSignature),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProtectedHeader {
+    alg: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    typ: Option<String>,
+}
+
+/// Whiteflag authentication signature, a JWS over a [`WhiteflagSignaturePayload`]
+///
+/// the signature is published by the originator at the url in the verification data of an A1
+/// authentication message, in JWS JSON serialization or JWS compact serialization
+///
+/// Whiteflag Specification 5.1.2 Authentication Signature
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WhiteflagAuthSignature {
+    protected: String,
+    payload: String,
+    signature: String,
+}
+
+impl WhiteflagAuthSignature {
+    pub fn sign(
+        payload: &WhiteflagSignaturePayload,
+        key: &WhiteflagSigningKey,
+    ) -> SignatureResult<Self> {
+        let header = ProtectedHeader {
+            alg: key.algorithm().to_string(),
+            typ: Some("JWT".to_string()),
+        };
+
+        let protected = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&header)?);
+        let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(payload)?);
+        let signature = key.sign(signing_input(&protected, &payload).as_bytes());
+
+        Ok(WhiteflagAuthSignature {
+            protected,
+            payload,
+            signature: URL_SAFE_NO_PAD.encode(signature),
+        })
+    }
+
+    /// the signature algorithm from the protected header
+    pub fn algorithm(&self) -> SignatureResult<SignatureAlgorithm> {
+        let header: ProtectedHeader =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(&self.protected)?)?;
+
+        match header.alg.as_str() {
+            "ES256" => Ok(SignatureAlgorithm::ES256),
+            "EdDSA" => Ok(SignatureAlgorithm::EdDSA),
+            _ => Err(SignatureError::UnsupportedAlgorithm(header.alg)),
+        }
+    }
+
+    /// the signed payload, without verifying the signature
+    pub fn payload(&self) -> SignatureResult<WhiteflagSignaturePayload> {
+        Ok(serde_json::from_slice(
+            &URL_SAFE_NO_PAD.decode(&self.payload)?,
+        )?)
+    }
+
+    /// verifies the signature with the originator's public key and returns the signed payload
+    pub fn verify(
+        &self,
+        key: &WhiteflagVerifyingKey,
+    ) -> SignatureResult<WhiteflagSignaturePayload> {
+        let signed = self.algorithm()?;
+        if signed != key.algorithm() {
+            return Err(SignatureError::AlgorithmMismatch {
+                signed,
+                key: key.algorithm(),
+            });
+        }
+
+        let signature = URL_SAFE_NO_PAD.decode(&self.signature)?;
+        key.verify(
+            signing_input(&self.protected, &self.payload).as_bytes(),
+            &signature,
+        )?;
+
+        self.payload()
+    }
+
+    /// verifies the signature and checks that it authenticates the originator of an A1 message
+    ///
+    /// the signed `url` must equal the verification data of the message and the signed `addr` must
+    /// equal the blockchain address of the originator
+    pub fn verify_authentication(
+        &self,
+        key: &WhiteflagVerifyingKey,
+        verification_data: &str,
+        originator_address: &str,
+    ) -> SignatureResult<WhiteflagSignaturePayload> {
+        let payload = self.verify(key)?;
+
+        if payload.url != verification_data {
+            return Err(SignatureError::PayloadMismatch("url"));
+        }
+
+        if !payload.addr.eq_ignore_ascii_case(originator_address) {
+            return Err(SignatureError::PayloadMismatch("addr"));
+        }
+
+        Ok(payload)
+    }
+
+    /// JWS JSON serialization
+    pub fn to_json(&self) -> SignatureResult<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn from_json(json: &str) -> SignatureResult<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// JWS compact serialization
+    pub fn to_compact(&self) -> String {
+        format!("{}.{}.{}", self.protected, self.payload, self.signature)
+    }
+
+    pub fn from_compact(compact: &str) -> SignatureResult<Self> {
+        let mut parts = compact.trim().split('.');
+
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(protected), Some(payload), Some(signature), None) => Ok(WhiteflagAuthSignature {
+                protected: protected.to_string(),
+                payload: payload.to_string(),
+                signature: signature.to_string(),
+            }),
+            _ => Err(SignatureError::MalformedCompact),
+        }
+    }
+}
+
+fn signing_input(protected: &str, payload: &str) -> String {
+    format!("{}.{}", protected, payload)
+}
@@ -0,0 +1,103 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use wf_buffer::WhiteflagBuffer;
+use wf_common::common::{append_bits, extract_bits};
+use wf_field::{definitions, Field, FieldDefinition};
+
+/// the fields of the mission signal test vector
+fn fields() -> Vec<Field> {
+    let header = [
+        "WF",
+        "1",
+        "0",
+        "1",
+        "M",
+        "4",
+        "3efb4e0cfa83122b242634254c1920a769d615dfcc4c670bb53eb6f12843c3ae",
+    ];
+    let body = [
+        "80",
+        "2013-08-31T04:29:15Z",
+        "P00D00H00M",
+        "22",
+        "+30.79658",
+        "-037.82602",
+        "8765",
+        "3210",
+        "042",
+    ];
+
+    definitions::header::DEFINITIONS
+        .iter()
+        .zip(header)
+        .chain(definitions::sign::DEFINITIONS.iter().zip(body))
+        .map(|(definition, value)| Field::new(definition.clone(), value.to_string()))
+        .collect()
+}
+
+fn encode(c: &mut Criterion) {
+    let fields = fields();
+    let mut group = c.benchmark_group("encode");
+
+    group.bench_function("append_bits", |b| {
+        b.iter(|| {
+            let (mut data, mut bit_length) = (Vec::new(), 0);
+            for field in black_box(&fields) {
+                (data, bit_length) =
+                    append_bits(&data, bit_length, &field.encode(), field.bit_length());
+            }
+            data
+        })
+    });
+
+    group.bench_function("bit_writer", |b| {
+        b.iter(|| {
+            let mut buffer = WhiteflagBuffer::default();
+            buffer.encode(black_box(&fields));
+            buffer
+        })
+    });
+
+    group.finish();
+}
+
+fn decode(c: &mut Criterion) {
+    let fields = fields();
+    let definitions: Vec<FieldDefinition> = fields.iter().map(|f| f.definition.clone()).collect();
+    let mut buffer = WhiteflagBuffer::default();
+    buffer.encode(&fields);
+    let (data, bit_length): (Vec<u8>, usize) = buffer.extract_bits_from(0).into();
+
+    let mut group = c.benchmark_group("decode");
+
+    group.bench_function("extract_bits", |b| {
+        b.iter(|| {
+            let mut cursor = 0;
+            black_box(&definitions)
+                .iter()
+                .map(|definition| {
+                    let length = definition.bit_length();
+                    let value = definition
+                        .decode(&extract_bits(&data, bit_length, cursor, length))
+                        .unwrap();
+                    cursor += length;
+                    value
+                })
+                .collect::<Vec<String>>()
+        })
+    });
+
+    group.bench_function("bit_reader", |b| {
+        b.iter(|| {
+            let mut reader = buffer.reader();
+            black_box(&definitions)
+                .iter()
+                .map(|definition| reader.read_value(definition).unwrap())
+                .collect::<Vec<String>>()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, encode, decode);
+criterion_main!(benches);
@@ -0,0 +1,248 @@
+use crate::WhiteflagBuffer;
+use alloc::{string::String, vec::Vec};
+use wf_codec::{encoding::EncodingKind, CodecError};
+use wf_common::{
+    common::byte_length,
+    constants::{BYTE, QUADBIT},
+};
+use wf_field::{Field, FieldDefinition};
+
+/// Appends bits to the end of a [`WhiteflagBuffer`] in place
+///
+/// unlike [`wf_common::common::append_bits`], the existing data is never copied or shifted,
+/// only the appended bits are shifted into position
+pub struct BitWriter<'a> {
+    buffer: &'a mut WhiteflagBuffer,
+}
+
+impl<'a> BitWriter<'a> {
+    pub fn new(buffer: &'a mut WhiteflagBuffer) -> Self {
+        /* make sure unused bits in the last byte are cleared, so appended bits can be or-ed in */
+        let used_bytes = byte_length(buffer.bit_length);
+        buffer.data.resize(used_bytes, 0);
+        clear_unused_bits(&mut buffer.data, buffer.bit_length);
+
+        BitWriter { buffer }
+    }
+
+    /// appends the first `bit_count` bits of `bytes`
+    pub fn write_bits(&mut self, bytes: &[u8], bit_count: usize) {
+        let bit_count = bit_count.min(bytes.len() * BYTE);
+        if bit_count == 0 {
+            return;
+        }
+
+        let shift = self.buffer.bit_length % BYTE;
+        let bytes = &bytes[..byte_length(bit_count)];
+        let data = &mut self.buffer.data;
+
+        if shift == 0 {
+            data.extend_from_slice(bytes);
+        } else {
+            data.reserve(bytes.len());
+            for &byte in bytes {
+                if let Some(last) = data.last_mut() {
+                    *last |= byte >> shift;
+                }
+                data.push(byte << (BYTE - shift));
+            }
+        }
+
+        self.buffer.bit_length += bit_count;
+        data.truncate(byte_length(self.buffer.bit_length));
+        clear_unused_bits(data, self.buffer.bit_length);
+    }
+
+    /// encodes the field and appends it
+    ///
+    /// the value is encoded straight into the buffer, the same as appending [`Field::encode`]
+    pub fn write_field(&mut self, field: &Field) {
+        let start = self.position();
+        let value = field.get();
+
+        match field.definition.bytes.encoding.kind {
+            EncodingKind::UTF8 => self.write_bits(value.as_bytes(), field.bit_length()),
+            EncodingKind::BIN => value
+                .chars()
+                .for_each(|c| self.push_bits(u8::from(c == '1'), 1)),
+            EncodingKind::DEC | EncodingKind::HEX => self.write_digits(value.chars()),
+            EncodingKind::DATETIME | EncodingKind::DURATION => {
+                self.write_digits(value.chars().filter(is_digit))
+            }
+            EncodingKind::LAT | EncodingKind::LONG => {
+                match value.chars().next() {
+                    Some('+') => self.push_bits(1, 1),
+                    Some('-') => self.push_bits(0, 1),
+                    _ => (),
+                }
+                self.write_digits(value.chars().filter(is_digit))
+            }
+        }
+
+        /* the encoded value is padded to whole bytes, and cut to the bit length of the field */
+        let padding = (BYTE - (self.position() - start) % BYTE) % BYTE;
+        if padding > 0 {
+            self.push_bits(0, padding);
+        }
+        self.truncate(start + field.bit_length());
+    }
+
+    /// the number of bits in the buffer
+    pub fn position(&self) -> usize {
+        self.buffer.bit_length
+    }
+
+    /// appends hexadecimal digits as 4 bits each
+    fn write_digits(&mut self, digits: impl Iterator<Item = char>) {
+        for digit in digits {
+            self.push_bits(digit.to_digit(16).unwrap_or(0) as u8, QUADBIT);
+        }
+    }
+
+    /// appends the lowest `bit_count` bits of `bits`, from 1 to 8
+    fn push_bits(&mut self, bits: u8, bit_count: usize) {
+        let shift = self.buffer.bit_length % BYTE;
+        let bits = bits << (BYTE - bit_count);
+        let data = &mut self.buffer.data;
+
+        match data.last_mut() {
+            Some(last) if shift != 0 => {
+                *last |= bits >> shift;
+                if shift + bit_count > BYTE {
+                    data.push(bits << (BYTE - shift));
+                }
+            }
+            _ => data.push(bits),
+        }
+        self.buffer.bit_length += bit_count;
+    }
+
+    /// drops the bits after `bit_length`
+    fn truncate(&mut self, bit_length: usize) {
+        if bit_length < self.buffer.bit_length {
+            self.buffer.bit_length = bit_length;
+            self.buffer.data.truncate(byte_length(bit_length));
+            clear_unused_bits(&mut self.buffer.data, bit_length);
+        }
+    }
+}
+
+/// the characters of a date, time, duration or coordinate that are encoded, as
+/// [`wf_common::common::remove_all_invalid_hex_characters`] leaves them
+fn is_digit(c: &char) -> bool {
+    !matches!(c, '-' | '+' | ':' | '.' | 'A'..='Z')
+}
+
+/// Reads bits from a [`WhiteflagBuffer`] sequentially without copying the buffer
+///
+/// field values are decoded from a scratch buffer that is reused for every field
+pub struct BitReader<'a> {
+    data: &'a [u8],
+    bit_length: usize,
+    cursor: usize,
+    scratch: Vec<u8>,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(buffer: &'a WhiteflagBuffer) -> Self {
+        BitReader {
+            data: &buffer.data,
+            bit_length: buffer.bit_length,
+            cursor: 0,
+            scratch: Vec::new(),
+        }
+    }
+
+    /// the bit position of the next read
+    pub fn position(&self) -> usize {
+        self.cursor
+    }
+
+    /// the number of bits left to read
+    pub fn remaining(&self) -> usize {
+        self.bit_length.saturating_sub(self.cursor)
+    }
+
+    /// moves the cursor to the given bit position
+    pub fn seek(&mut self, bit: usize) {
+        self.cursor = bit.min(self.bit_length);
+    }
+
+    /// reads up to `bit_count` bits into `out`, which is cleared first
+    ///
+    /// returns the number of bits read, which is less than `bit_count` at the end of the buffer
+    pub fn read_bits(&mut self, bit_count: usize, out: &mut Vec<u8>) -> usize {
+        let bit_count = bit_count.min(self.remaining());
+        read_bits_at(self.data, self.cursor, bit_count, out);
+        self.cursor += bit_count;
+        bit_count
+    }
+
+    /// reads and decodes the value of the next field
+    ///
+    /// fields without a fixed length take all remaining bits that fit their encoding, fields with a
+    /// fixed length fail if the buffer ends before the field does
+    pub fn read_value(&mut self, definition: &FieldDefinition) -> Result<String, CodecError> {
+        let field_bit_length = definition.bit_length();
+        let remaining = self.remaining();
+        let bit_length = if field_bit_length >= 1 {
+            if field_bit_length > remaining {
+                return Err(CodecError::EndOfBuffer {
+                    expected: field_bit_length,
+                    remaining,
+                });
+            }
+            field_bit_length
+        } else {
+            remaining - remaining % definition.bytes.encoding.bit_length
+        };
+
+        let mut scratch = core::mem::take(&mut self.scratch);
+        self.read_bits(bit_length, &mut scratch);
+        /* decode with the bits read, as fields without a fixed length have no bit length of their own */
+        let value = definition.bytes.encoding.decode(&scratch, bit_length);
+        self.scratch = scratch;
+
+        value
+    }
+
+    /// reads and decodes the next field
+    pub fn read_field(&mut self, definition: &FieldDefinition) -> Result<Field, CodecError> {
+        let value = self.read_value(definition)?;
+        Ok(Field::new(definition.clone(), value))
+    }
+}
+
+/// copies `bit_count` bits starting at `start_bit` into `out`, aligned to the first bit of `out`
+fn read_bits_at(data: &[u8], start_bit: usize, bit_count: usize, out: &mut Vec<u8>) {
+    out.clear();
+    if bit_count == 0 {
+        return;
+    }
+
+    let start_byte = start_bit / BYTE;
+    let shift = start_bit % BYTE;
+    let length = byte_length(bit_count);
+
+    if shift == 0 {
+        out.extend_from_slice(&data[start_byte..start_byte + length]);
+    } else {
+        out.extend((start_byte..start_byte + length).map(|i| {
+            let next = data.get(i + 1).map_or(0, |next| next >> (BYTE - shift));
+            (data[i] << shift) | next
+        }));
+    }
+
+    clear_unused_bits(out, bit_count);
+}
+
+fn clear_unused_bits(data: &mut [u8], bit_length: usize) {
+    let used_bits = bit_length % BYTE;
+    if used_bits == 0 {
+        return;
+    }
+
+    if let Some(last) = data.last_mut() {
+        *last &= 0xFF << (BYTE - used_bits);
+    }
+}
@@ -0,0 +1,161 @@
+use super::WhiteflagBuffer;
+use wf_codec::CodecError;
+use wf_common::common::{append_bits, crop_bits, extract_bits};
+use wf_field::{definitions, Field};
+
+#[test]
+fn write_bits_matches_append_bits() {
+    let bytes: Vec<u8> = vec![0xE6, 0x38, 0x87, 0x6E, 0x7F];
+
+    for first in 0..=16 {
+        for second in 0..=40 {
+            let mut cropped = bytes.clone();
+            crop_bits(&mut cropped, first);
+            let (expected, expected_length) = append_bits(&cropped, first, &bytes, second);
+
+            let mut buffer = WhiteflagBuffer::new(bytes.clone(), first);
+            buffer.writer().write_bits(&bytes, second);
+
+            assert_eq!(expected_length, buffer.bit_length());
+            assert_eq!(
+                hex::encode(&expected[..]),
+                buffer.as_hex(),
+                "appending {} bits to {} bits",
+                second,
+                first
+            );
+        }
+    }
+}
+
+#[test]
+fn read_bits_matches_extract_bits() {
+    let bytes: Vec<u8> = vec![0xE6, 0x38, 0x87, 0x6E, 0x7F];
+    let buffer = WhiteflagBuffer::from(bytes.clone());
+    let mut out = Vec::new();
+
+    for start in 0..40 {
+        for length in 1..=(40 - start) {
+            let mut reader = buffer.reader();
+            reader.seek(start);
+
+            assert_eq!(length, reader.read_bits(length, &mut out));
+            assert_eq!(
+                extract_bits(&bytes, 40, start, length),
+                out,
+                "reading {} bits from bit {}",
+                length,
+                start
+            );
+            assert_eq!(start + length, reader.position());
+        }
+    }
+}
+
+#[test]
+fn read_bits_stops_at_end_of_buffer() {
+    let buffer = WhiteflagBuffer::new(vec![0xFF, 0xFF], 12);
+    let mut reader = buffer.reader();
+    let mut out = Vec::new();
+
+    reader.seek(8);
+    assert_eq!(4, reader.read_bits(8, &mut out));
+    assert_eq!(vec![0xF0], out);
+    assert_eq!(0, reader.remaining());
+}
+
+#[test]
+fn fields_round_trip() {
+    let fields = vec![
+        Field::new(definitions::header::PREFIX, "WF".to_string()),
+        Field::new(definitions::header::VERSION, "1".to_string()),
+        Field::new(definitions::header::ENCRYPTION_INDICATOR, "0".to_string()),
+        Field::new(definitions::header::DURESS_INDICATOR, "1".to_string()),
+        Field::new(definitions::header::MESSAGE_CODE, "M".to_string()),
+    ];
+
+    let mut buffer = WhiteflagBuffer::default();
+    let mut writer = buffer.writer();
+    fields.iter().for_each(|f| writer.write_field(f));
+    assert_eq!(41, writer.position());
+
+    let mut reader = buffer.reader();
+    for field in &fields {
+        let decoded = reader.read_field(&field.definition).unwrap();
+        assert_eq!(field.get(), decoded.get());
+    }
+    assert_eq!(0, reader.remaining());
+}
+
+#[test]
+fn variable_length_hex_field_takes_remaining_bits() {
+    let data = "d426bbe111221675e333f30ef608b1aa6e60a47080dd33cb49e96395894ef42f";
+    let field = Field::new(definitions::crypto::CRYPTO_DATA, data.to_string());
+
+    let mut buffer = WhiteflagBuffer::default();
+    buffer.writer().write_field(&field);
+
+    let decoded = buffer.reader().read_field(&field.definition).unwrap();
+    assert_eq!(data, decoded.get());
+}
+
+#[test]
+fn fixed_length_field_fails_at_end_of_buffer() {
+    let buffer = WhiteflagBuffer::new(vec![0x57], 8);
+    let mut reader = buffer.reader();
+
+    assert_eq!(
+        Err(CodecError::EndOfBuffer {
+            expected: 16,
+            remaining: 8
+        }),
+        reader.read_value(&definitions::header::PREFIX)
+    );
+}
+
+#[test]
+fn write_field_matches_encode() {
+    let fields = vec![
+        Field::new(definitions::header::PREFIX, "WF".to_string()),
+        Field::new(definitions::header::ENCRYPTION_INDICATOR, "2".to_string()),
+        Field::new(definitions::header::DURESS_INDICATOR, "1".to_string()),
+        Field::new(
+            definitions::header::REFERENCED_MESSAGE,
+            "3efb4e0cfa83122b242634254c1920a769d615dfcc4c670bb53eb6f12843c3ae".to_string(),
+        ),
+        Field::new(
+            definitions::sign::DATE_TIME,
+            "2013-08-31T04:29:15Z".to_string(),
+        ),
+        Field::new(definitions::sign::DURATION, "P01D00H00M".to_string()),
+        Field::new(definitions::sign::OBJECT_LATITUDE, "+30.79658".to_string()),
+        Field::new(
+            definitions::sign::OBJECT_LONGITUDE,
+            "-037.82602".to_string(),
+        ),
+        Field::new(definitions::sign::OBJECT_SIZE_DIM_1, "8765".to_string()),
+        Field::new(definitions::crypto::CRYPTO_DATA, "d426b".to_string()),
+        Field::new(definitions::freetext::TEXT, "hello bob".to_string()),
+    ];
+
+    for start in 0..8 {
+        for field in &fields {
+            let mut expected = WhiteflagBuffer::new(vec![0xFF], start);
+            expected
+                .writer()
+                .write_bits(&field.encode(), field.bit_length());
+
+            let mut buffer = WhiteflagBuffer::new(vec![0xFF], start);
+            buffer.writer().write_field(field);
+
+            assert_eq!(expected.bit_length(), buffer.bit_length());
+            assert_eq!(
+                expected.as_hex(),
+                buffer.as_hex(),
+                "{} at bit {}",
+                field.get(),
+                start
+            );
+        }
+    }
+}
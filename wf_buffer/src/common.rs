@@ -1,5 +1,5 @@
-use crate::WhiteflagBuffer;
-use wf_common::common::{append_bits, crop_bits, extract_bits};
+use crate::{BitReader, BitWriter, WhiteflagBuffer};
+use wf_common::common::{crop_bits, extract_bits};
 
 impl WhiteflagBuffer {
     pub fn extract_bits(&self, start: usize, end: usize) -> WhiteflagBuffer {
@@ -16,17 +16,20 @@ impl WhiteflagBuffer {
         )
     }
 
-    pub fn append(&mut self, mut buffer: WhiteflagBuffer, bits: Option<usize>) {
+    pub fn append(&mut self, buffer: WhiteflagBuffer, bits: Option<usize>) {
         let bit_length_to_extract = bits.unwrap_or(buffer.bit_length);
-        let (buffer, length) = append_bits(
-            &self.data,
-            self.bit_length,
-            buffer.to_byte_array(),
-            bit_length_to_extract,
-        );
+        self.writer()
+            .write_bits(&buffer.data, bit_length_to_extract);
+    }
+
+    /// a [`BitWriter`] that appends to the end of this buffer in place
+    pub fn writer(&mut self) -> BitWriter<'_> {
+        BitWriter::new(self)
+    }
 
-        self.data = buffer;
-        self.bit_length = length;
+    /// a [`BitReader`] that reads this buffer from the first bit
+    pub fn reader(&self) -> BitReader<'_> {
+        BitReader::new(self)
     }
 
     pub fn crop(&mut self) {
@@ -1,4 +1,5 @@
 use crate::WhiteflagBuffer;
+use alloc::{string::String, vec::Vec};
 use hex::FromHexError;
 use wf_common::{common::remove_hexadecimal_prefix, constants::BYTE};
 use wf_field::Field;
@@ -1,6 +1,6 @@
 use super::WhiteflagBuffer;
+use alloc::{string::String, vec::Vec};
 use wf_codec::CodecError;
-use wf_common::common::extract_bits;
 use wf_field::{Field, FieldDefinition};
 
 impl WhiteflagBuffer {
@@ -18,20 +18,15 @@ impl WhiteflagBuffer {
             return Err(CodecError::EmptyFieldDefinition());
         }
 
-        let mut bit_cursor = start_bit;
+        let mut reader = self.reader();
+        reader.seek(start_bit);
 
         let fields = field_defs
             .iter()
-            .map(|f| {
-                // I'm so sorry - I need to get back to this line and do something useful with the result here that doesn't panic.
-                let field = self.extract_message_field(f, bit_cursor).unwrap();
-                bit_cursor += field.bit_length();
+            .map(|f| reader.read_field(f))
+            .collect::<Result<Vec<Field>, CodecError>>()?;
 
-                field
-            })
-            .collect();
-
-        Ok((bit_cursor, fields))
+        Ok((reader.position(), fields))
     }
 
     pub fn extract_message_field(
@@ -48,18 +43,8 @@ impl WhiteflagBuffer {
         definition: &FieldDefinition,
         start_bit: usize,
     ) -> Result<String, CodecError> {
-        let field_bit_length = definition.bit_length();
-        let bit_length = if field_bit_length >= 1 {
-            field_bit_length
-        } else {
-            let mut bit_length = self.bit_length - start_bit;
-            bit_length -= bit_length % definition.bytes.encoding.bit_length;
-            bit_length
-        };
-
-        let field_buffer: Vec<u8> =
-            extract_bits(&self.data, self.bit_length, start_bit, bit_length);
-
-        definition.decode(&field_buffer)
+        let mut reader = self.reader();
+        reader.seek(start_bit);
+        reader.read_value(definition)
     }
 }
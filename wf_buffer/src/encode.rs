@@ -8,10 +8,11 @@ impl WhiteflagBuffer {
     ///
     /// * `fields` - array of fields to append and encode into the buffer
     pub fn encode(&mut self, fields: &[Field]) {
-        fields.iter().for_each(|f| self.append_field(f));
+        let mut writer = self.writer();
+        fields.iter().for_each(|f| writer.write_field(f));
     }
 
     pub fn append_field(&mut self, field: &Field) {
-        self.append(field.into(), None);
+        self.writer().write_field(field);
     }
 }
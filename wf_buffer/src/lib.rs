@@ -1,18 +1,34 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
 use wf_codec::CodecError;
 use wf_field::FieldDefinition;
 
+pub use bits::{BitReader, BitWriter};
+pub use transport::{TransportEncoding, TransportError};
+
 #[cfg(test)]
 mod tests;
 
 #[cfg(test)]
 mod test_field;
 
+#[cfg(test)]
+mod bits_tests;
+
+#[cfg(test)]
+mod transport_tests;
+
+mod bits;
 mod common;
 mod conversions;
 mod decode;
 mod encode;
+mod transport;
 
-#[derive(Default)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct WhiteflagBuffer {
     data: Vec<u8>,
     bit_length: usize,
@@ -0,0 +1,231 @@
+use crate::WhiteflagBuffer;
+use alloc::{string::String, vec::Vec};
+use base64::{
+    alphabet,
+    engine::{general_purpose, DecodePaddingMode, GeneralPurpose},
+    Engine,
+};
+use core::{fmt, str::FromStr};
+use hex::FromHexError;
+
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    general_purpose::PAD.with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+const BASE64_URL: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::URL_SAFE,
+    general_purpose::NO_PAD.with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// RFC 9285, the alphanumeric mode character set of QR codes
+const BASE45_ALPHABET: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+const ZBASE32_ALPHABET: &[u8; 32] = b"ybndrfg8ejkmcpqxot1uwisza345h769";
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum TransportError {
+    #[error("invalid hexadecimal: {0}")]
+    Hexadecimal(FromHexError),
+    #[error("invalid base64: {0}")]
+    Base64(base64::DecodeError),
+    #[error("invalid {encoding} character {character:?}")]
+    InvalidCharacter {
+        encoding: TransportEncoding,
+        character: char,
+    },
+    #[error("invalid {0} length")]
+    InvalidLength(TransportEncoding),
+    #[error("unknown transport encoding {0}, expected hex, base64, base64url, base45 or zbase32")]
+    UnknownEncoding(String),
+}
+
+impl From<FromHexError> for TransportError {
+    fn from(error: FromHexError) -> Self {
+        TransportError::Hexadecimal(error)
+    }
+}
+
+impl From<base64::DecodeError> for TransportError {
+    fn from(error: base64::DecodeError) -> Self {
+        TransportError::Base64(error)
+    }
+}
+
+/// textual encodings of an encoded message for transport, e.g. printed as a QR code
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TransportEncoding {
+    #[default]
+    Hex,
+    /// RFC 4648 base64 with padding
+    Base64,
+    /// RFC 4648 url and filename safe base64 without padding
+    Base64Url,
+    /// RFC 9285, fits the alphanumeric mode of QR codes
+    Base45,
+    /// human oriented base32 without padding
+    ZBase32,
+}
+
+impl fmt::Display for TransportEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TransportEncoding::Hex => "hex",
+            TransportEncoding::Base64 => "base64",
+            TransportEncoding::Base64Url => "base64url",
+            TransportEncoding::Base45 => "base45",
+            TransportEncoding::ZBase32 => "zbase32",
+        })
+    }
+}
+
+impl FromStr for TransportEncoding {
+    type Err = TransportError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "hex" => Ok(TransportEncoding::Hex),
+            "base64" => Ok(TransportEncoding::Base64),
+            "base64url" => Ok(TransportEncoding::Base64Url),
+            "base45" => Ok(TransportEncoding::Base45),
+            "zbase32" | "z-base-32" => Ok(TransportEncoding::ZBase32),
+            _ => Err(TransportError::UnknownEncoding(s.into())),
+        }
+    }
+}
+
+impl TransportEncoding {
+    pub fn encode(&self, data: &[u8]) -> String {
+        match self {
+            TransportEncoding::Hex => hex::encode(data),
+            TransportEncoding::Base64 => BASE64.encode(data),
+            TransportEncoding::Base64Url => BASE64_URL.encode(data),
+            TransportEncoding::Base45 => encode_base45(data),
+            TransportEncoding::ZBase32 => encode_zbase32(data),
+        }
+    }
+
+    pub fn decode<T: AsRef<str>>(&self, value: T) -> Result<Vec<u8>, TransportError> {
+        let value = value.as_ref().trim();
+        match self {
+            TransportEncoding::Hex => Ok(crate::conversions::decode_hex(value)?),
+            TransportEncoding::Base64 => Ok(BASE64.decode(value)?),
+            TransportEncoding::Base64Url => Ok(BASE64_URL.decode(value)?),
+            TransportEncoding::Base45 => decode_base45(value),
+            TransportEncoding::ZBase32 => decode_zbase32(value),
+        }
+    }
+}
+
+impl WhiteflagBuffer {
+    /// the buffer in the given transport encoding, see [`WhiteflagBuffer::as_hex`]
+    pub fn as_encoding(&self, encoding: TransportEncoding) -> String {
+        encoding.encode(&self.data)
+    }
+
+    /// decodes a buffer from the given transport encoding
+    pub fn decode_from_encoding<T: AsRef<str>>(
+        value: T,
+        encoding: TransportEncoding,
+    ) -> Result<WhiteflagBuffer, TransportError> {
+        Ok(encoding.decode(value)?.into())
+    }
+}
+
+/// every two bytes become three characters, a trailing byte becomes two
+fn encode_base45(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(2) * 3);
+    for chunk in data.chunks(2) {
+        let (mut value, digits) = match chunk {
+            [a, b] => ((*a as usize) << 8 | *b as usize, 3),
+            [a] => (*a as usize, 2),
+            _ => unreachable!(),
+        };
+
+        for _ in 0..digits {
+            encoded.push(BASE45_ALPHABET[value % 45] as char);
+            value /= 45;
+        }
+    }
+
+    encoded
+}
+
+fn decode_base45(value: &str) -> Result<Vec<u8>, TransportError> {
+    let digits = value
+        .chars()
+        .map(|c| {
+            BASE45_ALPHABET.iter().position(|&a| a as char == c).ok_or(
+                TransportError::InvalidCharacter {
+                    encoding: TransportEncoding::Base45,
+                    character: c,
+                },
+            )
+        })
+        .collect::<Result<Vec<usize>, _>>()?;
+
+    let mut data = Vec::with_capacity(digits.len() / 3 * 2 + 1);
+    for chunk in digits.chunks(3) {
+        let value = chunk
+            .iter()
+            .rev()
+            .fold(0, |value, digit| value * 45 + digit);
+        match chunk.len() {
+            3 if value <= 0xFFFF => data.extend_from_slice(&[(value >> 8) as u8, value as u8]),
+            2 if value <= 0xFF => data.push(value as u8),
+            _ => return Err(TransportError::InvalidLength(TransportEncoding::Base45)),
+        }
+    }
+
+    Ok(data)
+}
+
+/// five bits per character, most significant bit first, the last character is padded with zeros
+fn encode_zbase32(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity((data.len() * 8).div_ceil(5));
+    let mut bits: u16 = 0;
+    let mut bit_count = 0;
+
+    for &byte in data {
+        bits = (bits << 8) | byte as u16;
+        bit_count += 8;
+        while bit_count >= 5 {
+            bit_count -= 5;
+            encoded.push(ZBASE32_ALPHABET[((bits >> bit_count) & 0x1F) as usize] as char);
+        }
+    }
+
+    if bit_count > 0 {
+        encoded.push(ZBASE32_ALPHABET[((bits << (5 - bit_count)) & 0x1F) as usize] as char);
+    }
+
+    encoded
+}
+
+fn decode_zbase32(value: &str) -> Result<Vec<u8>, TransportError> {
+    let mut data = Vec::with_capacity(value.len() * 5 / 8);
+    let mut bits: u16 = 0;
+    let mut bit_count = 0;
+
+    for c in value.chars() {
+        let digit = ZBASE32_ALPHABET
+            .iter()
+            .position(|&a| a as char == c.to_ascii_lowercase())
+            .ok_or(TransportError::InvalidCharacter {
+                encoding: TransportEncoding::ZBase32,
+                character: c,
+            })?;
+
+        bits = (bits << 5) | digit as u16;
+        bit_count += 5;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            data.push((bits >> bit_count) as u8);
+        }
+    }
+
+    /* the padding of the last character must fit in less than a byte and be zero */
+    if bit_count >= 5 || bits & ((1 << bit_count) - 1) != 0 {
+        return Err(TransportError::InvalidLength(TransportEncoding::ZBase32));
+    }
+
+    Ok(data)
+}
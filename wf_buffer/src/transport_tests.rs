@@ -0,0 +1,99 @@
+use super::{TransportEncoding, TransportError, WhiteflagBuffer};
+
+const AUTHENTICATION_HEX: &str = "5746313020800000000000000000000000000000000000000000000000000000000000000000b43a3a38399d1797b7b933b0b734b9b0ba34b7b71734b73a17bbb434ba32b33630b380";
+
+const ENCODINGS: [TransportEncoding; 5] = [
+    TransportEncoding::Hex,
+    TransportEncoding::Base64,
+    TransportEncoding::Base64Url,
+    TransportEncoding::Base45,
+    TransportEncoding::ZBase32,
+];
+
+#[test]
+fn base45_rfc_vectors() {
+    /* RFC 9285 4.3 and 4.4 */
+    let base45 = TransportEncoding::Base45;
+    assert_eq!("BB8", base45.encode(b"AB"));
+    assert_eq!("%69 VD92EX0", base45.encode(b"Hello!!"));
+    assert_eq!("UJCLQE7W581", base45.encode(b"base-45"));
+    assert_eq!(b"ietf!".to_vec(), base45.decode("QED8WEX0").unwrap());
+}
+
+#[test]
+fn base45_rejects_invalid_input() {
+    let base45 = TransportEncoding::Base45;
+    assert_eq!(
+        Err(TransportError::InvalidCharacter {
+            encoding: base45,
+            character: 'a'
+        }),
+        base45.decode("aB8")
+    );
+    /* a single trailing character and values over two bytes cannot be decoded */
+    assert_eq!(
+        Err(TransportError::InvalidLength(base45)),
+        base45.decode("BB8B")
+    );
+    assert_eq!(
+        Err(TransportError::InvalidLength(base45)),
+        base45.decode("GGW")
+    );
+}
+
+#[test]
+fn zbase32_vectors() {
+    let zbase32 = TransportEncoding::ZBase32;
+    assert_eq!("9h", zbase32.encode(&[0xFF]));
+    assert_eq!("pb1sa5dx", zbase32.encode(b"hello"));
+    assert_eq!(b"hello".to_vec(), zbase32.decode("PB1SA5DX").unwrap());
+    assert_eq!(
+        Err(TransportError::InvalidLength(zbase32)),
+        zbase32.decode("9n")
+    );
+    assert!(matches!(
+        zbase32.decode("0"),
+        Err(TransportError::InvalidCharacter { character: '0', .. })
+    ));
+}
+
+#[test]
+fn message_in_all_encodings() {
+    let buffer = WhiteflagBuffer::decode_from_hexadecimal(AUTHENTICATION_HEX).unwrap();
+
+    assert_eq!(
+        "V0YxMCCAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAC0Ojo4OZ0Xl7e5M7C3NLmwujS3txc0tzoXu7Q0ujKzNjCzgA==",
+        buffer.as_encoding(TransportEncoding::Base64)
+    );
+    assert_eq!(
+        "k7ddncbyoyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyybpb48ehdu8ez16551c7osh4mucf4g155qf3wsh7bxq7wg17dfc3sgn3ay",
+        buffer.as_encoding(TransportEncoding::ZBase32)
+    );
+
+    for encoding in ENCODINGS {
+        let encoded = buffer.as_encoding(encoding);
+        let decoded = WhiteflagBuffer::decode_from_encoding(&encoded, encoding).unwrap();
+        assert_eq!(AUTHENTICATION_HEX, decoded.as_hex(), "{}", encoding);
+        assert_eq!(encoding, encoding.to_string().parse().unwrap());
+    }
+}
+
+#[test]
+fn base64_padding_is_optional() {
+    let base64 = TransportEncoding::Base64;
+    assert_eq!(b"ab".to_vec(), base64.decode("YWI=").unwrap());
+    assert_eq!(b"ab".to_vec(), base64.decode("YWI").unwrap());
+    assert_eq!(
+        b"\xfb\xff".to_vec(),
+        TransportEncoding::Base64Url.decode("-_8").unwrap()
+    );
+    assert!(base64.decode("-_8").is_err());
+}
+
+#[test]
+fn unknown_encoding() {
+    assert_eq!(
+        Err(TransportError::UnknownEncoding("base58".to_string())),
+        "base58".parse::<TransportEncoding>()
+    );
+}
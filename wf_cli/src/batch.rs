@@ -0,0 +1,148 @@
+use crate::{error::WhiteflagCLIError, format_table, inspect, WhiteflagCLIResult};
+use fennel_whiteflag::Message;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt, fs,
+    io::{self, BufRead, Read, Write},
+    path::Path,
+    str::FromStr,
+};
+use wf_buffer::{TransportEncoding, WhiteflagBuffer};
+
+/// how messages are printed
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Json,
+    Hex,
+    Base64,
+    /// the fields of the encoded message, see [`inspect`]
+    Table,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "hex" => Ok(OutputFormat::Hex),
+            "base64" => Ok(OutputFormat::Base64),
+            "table" => Ok(OutputFormat::Table),
+            _ => Err(format!(
+                "unknown output format {}, expected json, hex, base64 or table",
+                s
+            )),
+        }
+    }
+}
+
+/// a message in the output format, or encoded in the transport encoding without one
+pub fn format_message(
+    message: &Message,
+    output: Option<OutputFormat>,
+    encoding: TransportEncoding,
+) -> WhiteflagCLIResult<String> {
+    let buffer: WhiteflagBuffer = message.encode().into();
+    Ok(match output {
+        Some(OutputFormat::Json) => serde_json::to_string(message)?,
+        Some(OutputFormat::Hex) => buffer.as_encoding(TransportEncoding::Hex),
+        Some(OutputFormat::Base64) => buffer.as_encoding(TransportEncoding::Base64),
+        Some(OutputFormat::Table) => format_table(&inspect(&buffer)),
+        None => buffer.as_encoding(encoding),
+    })
+}
+
+/// the whole input: the value given, or a file, or stdin if the value is `-` or left out
+pub fn read_input(value: Option<String>, file: Option<&Path>) -> io::Result<String> {
+    match (value, file) {
+        (Some(value), _) if value != "-" => Ok(value),
+        (_, Some(file)) => fs::read_to_string(file),
+        _ => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            Ok(input)
+        }
+    }
+}
+
+/// the records of a batch and the ones that failed, by line
+#[derive(Debug, Default)]
+pub struct BatchReport {
+    pub records: usize,
+    pub failures: Vec<(usize, WhiteflagCLIError)>,
+}
+
+impl BatchReport {
+    /// the exit code of the first failure, or 0
+    pub fn exit_code(&self) -> u8 {
+        self.failures
+            .first()
+            .map_or(0, |(_, error)| error.exit_code())
+    }
+}
+
+impl fmt::Display for BatchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (line, error) in &self.failures {
+            writeln!(f, "line {}: {}", line, error.describe())?;
+        }
+        write!(
+            f,
+            "{} records, {} succeeded, {} failed",
+            self.records,
+            self.records - self.failures.len(),
+            self.failures.len()
+        )
+    }
+}
+
+/// processes one record per line, writing the result of each; blank lines are skipped
+///
+/// the first failing record, or a line that is not UTF-8, ends the batch with
+/// [`WhiteflagCLIError::Record`], unless `continue_on_error` is set and it is added to the report
+/// instead. failing to read the input or write the output always ends the batch
+pub fn run_batch<R, W, F>(
+    mut input: R,
+    mut output: W,
+    continue_on_error: bool,
+    mut process: F,
+) -> WhiteflagCLIResult<BatchReport>
+where
+    R: BufRead,
+    W: Write,
+    F: FnMut(&str) -> WhiteflagCLIResult<String>,
+{
+    let mut report = BatchReport::default();
+    let mut bytes = Vec::new();
+    let mut line = 0;
+    loop {
+        bytes.clear();
+        if input.read_until(b'\n', &mut bytes)? == 0 {
+            break;
+        }
+        line += 1;
+
+        let result = match std::str::from_utf8(&bytes) {
+            Ok(record) if record.trim().is_empty() => continue,
+            Ok(record) => process(record.trim()),
+            Err(e) => Err(WhiteflagCLIError::Utf8(e)),
+        };
+
+        report.records += 1;
+        match result {
+            Ok(result) => writeln!(output, "{}", result)?,
+            Err(WhiteflagCLIError::Io(e)) => return Err(e.into()),
+            Err(error) if continue_on_error => report.failures.push((line, error)),
+            Err(error) => {
+                return Err(WhiteflagCLIError::Record {
+                    line,
+                    error: Box::new(error),
+                })
+            }
+        }
+    }
+
+    output.flush()?;
+    Ok(report)
+}
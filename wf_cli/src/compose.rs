@@ -0,0 +1,416 @@
+use crate::{error::WhiteflagCLIError, WhiteflagCLIResult};
+use fennel_whiteflag::{Message, WhiteflagMessage};
+use serde_json::{Map, Value};
+use std::{
+    collections::HashMap,
+    io::{BufRead, ErrorKind, Write},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use wf_field::{
+    definitions::{header, request},
+    FieldDefinition, MessageCodeType,
+};
+use wf_validation::{Validation, ValidationError};
+
+/// the message codes that can be composed, with their message types
+pub const MESSAGE_CODES: &[(char, &str)] = &[
+    ('A', "Authentication"),
+    ('K', "Cryptographic Support"),
+    ('T', "Test"),
+    ('R', "Resource"),
+    ('F', "Free Text"),
+    ('P', "Protective Sign"),
+    ('E', "Emergency Signal"),
+    ('D', "Danger and Disaster Sign"),
+    ('S', "Status Signal"),
+    ('I', "Infrastructure Sign"),
+    ('M', "Mission Signal"),
+    ('Q', "Request Signal"),
+];
+
+/// the subject codes of the sign and signal message codes, from 4.3.1.2 of the specification
+pub const SUBJECT_CODES: &[(char, &[(&str, &str)])] = &[
+    (
+        'P',
+        &[
+            ("10", "Medical: red cross, red crescent or red crystal"),
+            ("11", "Civil defence"),
+            ("12", "Cultural property"),
+            ("13", "Works and installations containing dangerous forces"),
+            ("20", "Parlementaire: white flag of truce"),
+            ("30", "Prisoner of war camp or internment camp"),
+            ("40", "Hospital, safety or neutralised zone"),
+            ("50", "Demilitarised zone"),
+            ("80", "Other protective sign"),
+        ],
+    ),
+    (
+        'E',
+        &[
+            ("10", "Distress, immediate assistance required"),
+            ("11", "Medical emergency"),
+            ("20", "Evacuation required"),
+            ("30", "Persons in danger or missing"),
+            ("80", "Other emergency"),
+        ],
+    ),
+    (
+        'D',
+        &[
+            ("10", "Mines or unexploded ordnance"),
+            (
+                "20",
+                "Chemical, biological, radiological or nuclear contamination",
+            ),
+            ("30", "Ongoing hostilities"),
+            ("40", "Natural disaster"),
+            ("41", "Fire"),
+            ("42", "Flood"),
+            ("80", "Other danger"),
+        ],
+    ),
+    (
+        'S',
+        &[
+            ("10", "Safe"),
+            ("20", "Injured or sick"),
+            ("30", "Missing or unaccounted for"),
+            ("40", "Detained"),
+            ("80", "Other status"),
+        ],
+    ),
+    (
+        'I',
+        &[
+            ("10", "Hospital or medical facility"),
+            ("20", "Shelter"),
+            ("30", "Drinking water"),
+            ("40", "Food distribution"),
+            ("50", "Power supply"),
+            ("80", "Other infrastructure"),
+        ],
+    ),
+    (
+        'M',
+        &[
+            ("10", "Humanitarian convoy"),
+            ("20", "Medical evacuation"),
+            ("30", "Search and rescue"),
+            ("40", "Negotiation or mediation"),
+            ("80", "Other mission"),
+        ],
+    ),
+    (
+        'Q',
+        &[
+            ("10", "Medical assistance"),
+            ("20", "Food"),
+            ("30", "Drinking water"),
+            ("40", "Shelter"),
+            ("50", "Evacuation"),
+            ("80", "Other request"),
+        ],
+    ),
+];
+
+/// builds a message from field values given as options or answered at prompts
+///
+/// fields are named by their json names, fields without a value take the value of the template of
+/// [`WhiteflagMessage::new`], except the date and time which defaults to now
+pub struct Composer {
+    code: char,
+    template: Map<String, Value>,
+    values: HashMap<String, String>,
+    requests: Vec<(String, String)>,
+}
+
+impl Composer {
+    pub fn new(code: &str) -> WhiteflagCLIResult<Self> {
+        let mut chars = code.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if MESSAGE_CODES.iter().any(|(m, _)| *m == c) => c,
+            _ => return Err(WhiteflagCLIError::UnknownMessageCode(code.to_string())),
+        };
+
+        let mut template: Map<String, Value> =
+            serde_json::from_str(&WhiteflagMessage::new(code.to_string())?.as_json())?;
+        if template.contains_key("dateTime") {
+            template.insert("dateTime".into(), now().into());
+        }
+
+        Ok(Composer {
+            code,
+            template,
+            values: HashMap::new(),
+            requests: Vec::new(),
+        })
+    }
+
+    /// the definitions of the body fields, request pairs excluded
+    pub fn fields(&self) -> &'static [FieldDefinition] {
+        MessageCodeType::from_code(self.code)
+            .definitions()
+            .unwrap_or_default()
+    }
+
+    /// sets the value of a body field after validating it
+    pub fn set(&mut self, name: &str, value: &str) -> WhiteflagCLIResult<()> {
+        let definition = self
+            .fields()
+            .iter()
+            .find(|d| json_name(d) == name)
+            .ok_or_else(|| WhiteflagCLIError::UnknownField(name.to_string()))?;
+
+        validate(definition, value)?;
+        self.values.insert(name.to_string(), value.to_string());
+        Ok(())
+    }
+
+    /// adds a pair of an object type and its quantity to a request signal
+    pub fn add_request(&mut self, object_type: &str, quantity: &str) -> WhiteflagCLIResult<()> {
+        if self.code != 'Q' {
+            return Err(WhiteflagCLIError::UnknownField(json_name(
+                &request::OBJECT_TYPE_QUANT,
+            )));
+        }
+
+        validate(&request::OBJECT_TYPE, object_type)?;
+        validate(&request::OBJECT_TYPE_QUANT, quantity)?;
+        self.requests
+            .push((object_type.to_string(), quantity.to_string()));
+        Ok(())
+    }
+
+    /// adds a request pair given as `<object type>:<quantity>`
+    pub fn add_request_pair(&mut self, pair: &str) -> WhiteflagCLIResult<()> {
+        match pair.split_once(':') {
+            Some((object_type, quantity)) => self.add_request(object_type, quantity),
+            None => Err(WhiteflagCLIError::InvalidField {
+                field: "request".into(),
+                reason: format!("expected <object type>:<quantity>, not {:?}", pair),
+            }),
+        }
+    }
+
+    /// asks for each field without a value, and for request pairs until an empty answer
+    ///
+    /// an empty answer takes the default, an invalid one is asked again
+    pub fn prompt<R: BufRead, W: Write>(
+        &mut self,
+        mut input: R,
+        mut output: W,
+    ) -> WhiteflagCLIResult<()> {
+        let section = MESSAGE_CODES
+            .iter()
+            .find(|(code, _)| *code == self.code)
+            .map(|(_, name)| *name)
+            .unwrap_or_default();
+        writeln!(output, "composing a {} message", section)?;
+
+        for definition in self.fields() {
+            let name = json_name(definition);
+            if self.values.contains_key(&name) {
+                continue;
+            }
+
+            if let Some(help) = help(&name, self.code) {
+                writeln!(output, "{}", help)?;
+            }
+            if name == "subjectCode" {
+                for (code, meaning) in subject_codes(self.code) {
+                    writeln!(output, "  {}  {}", code, meaning)?;
+                }
+            }
+            let default = self.default(&name);
+            loop {
+                let answer = ask(&mut input, &mut output, &name, Some(&default))?;
+                let value = if answer.is_empty() { &default } else { &answer };
+                match self.set(&name, value) {
+                    Ok(()) => break,
+                    Err(e) => writeln!(output, "{}", e)?,
+                }
+            }
+        }
+
+        if self.code == 'Q' {
+            writeln!(
+                output,
+                "request pairs of an object type and quantity, leave the object type empty to finish"
+            )?;
+            loop {
+                let object_type = ask(&mut input, &mut output, "objectType", None)?;
+                if object_type.is_empty() {
+                    break;
+                }
+                let quantity = ask(&mut input, &mut output, "objectTypeQuant", None)?;
+                if let Err(e) = self.add_request(&object_type, &quantity) {
+                    writeln!(output, "{}", e)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// the message of the header of the template, the field values and the request pairs
+    pub fn compose(&self) -> WhiteflagCLIResult<Message> {
+        let mut values = Vec::new();
+        for definition in header::DEFINITIONS {
+            values.push(self.default(&json_name(definition)));
+        }
+        for definition in self.fields() {
+            let name = json_name(definition);
+            values.push(
+                self.values
+                    .get(&name)
+                    .cloned()
+                    .unwrap_or_else(|| self.default(&name)),
+            );
+        }
+        for (object_type, quantity) in &self.requests {
+            values.push(object_type.clone());
+            values.push(quantity.clone());
+        }
+
+        Ok(Message::compile(&values)?)
+    }
+
+    fn default(&self, name: &str) -> String {
+        self.template
+            .get(name)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    }
+}
+
+/// asks for a message code from the menu of [`MESSAGE_CODES`] until a valid one is given
+pub fn prompt_code<R: BufRead, W: Write>(mut input: R, mut output: W) -> WhiteflagCLIResult<char> {
+    for (code, name) in MESSAGE_CODES {
+        writeln!(output, "  {}  {}", code, name)?;
+    }
+
+    loop {
+        let answer = ask(&mut input, &mut output, "messageCode", None)?.to_uppercase();
+        match MESSAGE_CODES
+            .iter()
+            .find(|(code, _)| answer == code.to_string())
+        {
+            Some((code, _)) => return Ok(*code),
+            None => writeln!(output, "choose one of the message codes above")?,
+        }
+    }
+}
+
+/// the subject codes of a sign or signal message code, none for other message codes
+pub fn subject_codes(code: char) -> &'static [(&'static str, &'static str)] {
+    SUBJECT_CODES
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, codes)| *codes)
+        .unwrap_or_default()
+}
+
+/// the camel case name a field has in json messages
+pub(crate) fn json_name(definition: &FieldDefinition) -> String {
+    let name = definition.get_name().unwrap_or_default();
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+pub(crate) fn validate(definition: &FieldDefinition, value: &str) -> WhiteflagCLIResult<()> {
+    definition
+        .validate(value)
+        .map_err(|e| WhiteflagCLIError::InvalidField {
+            field: json_name(definition),
+            reason: match e {
+                ValidationError::InvalidLength {
+                    expected_length, ..
+                } => format!("expected {} characters", expected_length),
+                ValidationError::InvalidCharset => "contains invalid characters".to_string(),
+            },
+        })
+}
+
+/// a trimmed answer, failing at the end of the input
+fn ask<R: BufRead, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    name: &str,
+    default: Option<&str>,
+) -> WhiteflagCLIResult<String> {
+    match default {
+        Some(default) => write!(output, "{} [{}]: ", name, default)?,
+        None => write!(output, "{}: ", name)?,
+    }
+    output.flush()?;
+
+    let mut answer = String::new();
+    if input.read_line(&mut answer)? == 0 {
+        return Err(std::io::Error::new(ErrorKind::UnexpectedEof, "no answer").into());
+    }
+    Ok(answer.trim().to_string())
+}
+
+/// what a field holds, from 4.3 Message Types
+fn help(name: &str, code: char) -> Option<String> {
+    let help = match name {
+        "verificationMethod" => "1 for an internet resource, 2 for a shared token",
+        "verificationData" => "the url of the internet resource or the shared token",
+        "cryptoDataType" => "the type of the cryptographic data, two hexadecimal digits",
+        "cryptoData" => "the cryptographic data, hexadecimal",
+        "text" => "the free text",
+        "resourceMethod" => "1 for an internet resource",
+        "resourceData" => "the url of the internet resource",
+        "pseudoMessageCode" => "the message code of the message being tested",
+        "subjectCode" => {
+            return Some(format!(
+                "the sign/signal type, two hexadecimal digits from 4.3.1.2.{} of the specification",
+                "PEDSIMQ".find(code)? + 1
+            ))
+        }
+        "dateTime" => "when the sign/signal is valid, e.g. 2013-08-31T04:29:15Z",
+        "duration" => "how long the sign/signal will be valid, e.g. P01D12H00M",
+        "objectType" => "the type of object the sign/signal refers to, two hexadecimal digits",
+        "objectLatitude" => "the latitude of the object in decimal degrees, e.g. +30.79658",
+        "objectLongitude" => "the longitude of the object in decimal degrees, e.g. -037.82602",
+        "objectSizeDim1" => "the size of the first dimension of the object in meters, 4 digits",
+        "objectSizeDim2" => "the size of the second dimension of the object in meters, 4 digits",
+        "objectOrientation" => "the orientation of the object in degrees, 3 digits",
+        _ => return None,
+    };
+    Some(help.to_string())
+}
+
+/// the current time in the format of the date time field
+fn now() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let (days, time) = (seconds / 86400, seconds % 86400);
+
+    /* civil date from days since 1970-01-01, http://howardhinnant.github.io/date_algorithms.html */
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z % 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
@@ -0,0 +1,125 @@
+use crate::{
+    error::WhiteflagCLIError, identity::config_dir, IdentityStore, OutputFormat, WhiteflagCLIResult,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    env, fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+use wf_transport::MockLedger;
+
+/// the name of the config file in the config directory
+pub const CONFIG_FILE: &str = "config.toml";
+
+/// the name of the file of the mock ledger in the keystore
+pub const LEDGER_FILE: &str = "ledger.jsonl";
+
+/// the environment variables overriding the settings of the config file, with the setting
+pub const CONFIG_ENV: &[(&str, &str)] = &[
+    ("WF_ORIGINATOR", "originator"),
+    ("WF_KEYSTORE", "keystore"),
+    ("WF_OUTPUT", "output"),
+    ("WF_BLOCKCHAIN", "blockchain"),
+];
+
+/// the settings of `wf`, from the config file and then the environment
+///
+/// options given on the command line take precedence over both
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    /// the identity messages are sent as when no other is given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub originator: Option<String>,
+    /// the directory of identities, contacts, keys and the session
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keystore: Option<PathBuf>,
+    /// how messages are printed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<OutputFormat>,
+    /// the blockchain messages are sent on and read from, `mock` for the mock ledger
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blockchain: Option<String>,
+}
+
+impl Config {
+    /// the config file in the config directory with the environment applied, see [`Config::path`]
+    pub fn load() -> WhiteflagCLIResult<Self> {
+        let config = match Self::path() {
+            Some(path) => Self::read(&path)?,
+            None => Config::default(),
+        };
+        config.with_env(|name| env::var(name).ok())
+    }
+
+    /// `config.toml` in `$WF_CONFIG_DIR`, or in the `whiteflag` directory of the user's config directory
+    pub fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join(CONFIG_FILE))
+    }
+
+    /// reads a config file, a missing one has no settings
+    pub fn read(path: &Path) -> WhiteflagCLIResult<Self> {
+        match fs::read_to_string(path) {
+            Ok(toml) => toml::from_str(&toml).map_err(|e| invalid(&path.display().to_string(), e)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn parse(toml: &str) -> WhiteflagCLIResult<Self> {
+        toml::from_str(toml).map_err(|e| invalid(CONFIG_FILE, e))
+    }
+
+    /// overrides the settings with the environment variables of [`CONFIG_ENV`] that are set
+    pub fn with_env<F: Fn(&str) -> Option<String>>(mut self, var: F) -> WhiteflagCLIResult<Self> {
+        for (name, setting) in CONFIG_ENV {
+            let value = match var(name) {
+                Some(value) if !value.is_empty() => value,
+                _ => continue,
+            };
+            match *setting {
+                "originator" => self.originator = Some(value),
+                "keystore" => self.keystore = Some(value.into()),
+                "output" => self.output = Some(value.parse().map_err(|e| invalid(name, e))?),
+                "blockchain" => self.blockchain = Some(value),
+                _ => {}
+            }
+        }
+        Ok(self)
+    }
+
+    /// the store in the keystore directory, or the default store
+    pub fn store(&self) -> WhiteflagCLIResult<IdentityStore> {
+        match &self.keystore {
+            Some(dir) => Ok(IdentityStore::new(dir)),
+            None => IdentityStore::open_default(),
+        }
+    }
+
+    /// the transport of the blockchain, the mock ledger in the given file or [`LEDGER_FILE`] in the
+    /// keystore
+    pub fn transport(
+        &self,
+        store: &IdentityStore,
+        ledger: Option<PathBuf>,
+    ) -> WhiteflagCLIResult<MockLedger> {
+        match self.blockchain.as_deref() {
+            None | Some("mock") => Ok(MockLedger::open(
+                ledger.unwrap_or_else(|| store.dir().join(LEDGER_FILE)),
+            )),
+            Some(blockchain) => Err(WhiteflagCLIError::UnsupportedBlockchain(
+                blockchain.to_string(),
+            )),
+        }
+    }
+
+    /// the settings as they would be written in the config file
+    pub fn to_toml(&self) -> WhiteflagCLIResult<String> {
+        toml::to_string(self).map_err(|e| invalid(CONFIG_FILE, e))
+    }
+}
+
+fn invalid<S: ToString>(source: &str, reason: S) -> WhiteflagCLIError {
+    WhiteflagCLIError::InvalidConfig(source.to_string(), reason.to_string())
+}
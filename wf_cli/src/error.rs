@@ -1,15 +1,105 @@
+use std::error::Error as _;
 use thiserror::Error;
 
+/// the exit code of errors not caused by invalid input or by input and output
+pub const EXIT_FAILURE: u8 = 1;
+/// the exit code of invalid messages and other invalid input, as `EX_DATAERR` of sysexits.h
+pub const EXIT_INVALID: u8 = 65;
+/// the exit code of failing to read or write files and streams, as `EX_IOERR` of sysexits.h
+pub const EXIT_IO: u8 = 74;
+/// the exit code of an invalid config file or environment variable, as `EX_CONFIG` of sysexits.h
+pub const EXIT_CONFIG: u8 = 78;
+
 #[derive(Error, Debug)]
 pub enum WhiteflagCLIError {
-    #[error("must authenticate using `wf auth`")]
+    #[error("must authenticate using `wf auth login <identity>`")]
     AuthenticationRequired,
+    #[error("unknown {0} {1}, see `wf {0} list`")]
+    Unknown(&'static str, String),
+    #[error("{0} {1} already exists")]
+    Exists(&'static str, String),
+    #[error("invalid identity: {0}")]
+    InvalidIdentity(String),
+    #[error("invalid key: {0}")]
+    InvalidKey(String),
+    #[error("encryption method {0} is not supported, expected 1 or 2")]
+    UnsupportedEncryptionMethod(String),
+    #[error("already logged in as {0}, use `wf auth logout` first")]
+    SessionActive(String),
+    #[error("no authentication message to discontinue, send it with `wf send` or give its transaction hash with --referenced-message")]
+    MissingReference,
+    #[error("unknown message code {0}")]
+    UnknownMessageCode(String),
+    #[error("the message has no field {0}")]
+    UnknownField(String),
+    #[error("invalid {field}: {reason}")]
+    InvalidField { field: String, reason: String },
+    #[error("no transport for blockchain {0}, only the mock ledger is available")]
+    UnsupportedBlockchain(String),
+    #[error("invalid setting in {0}: {1}")]
+    InvalidConfig(String, String),
+    #[error("line {line} failed")]
+    Record {
+        line: usize,
+        #[source]
+        error: Box<WhiteflagCLIError>,
+    },
+    #[error("cannot read or write a file or stream")]
+    Io(#[from] std::io::Error),
+    #[error("the input is not UTF-8")]
+    Utf8(#[source] std::str::Utf8Error),
+    #[error("invalid hexadecimal")]
+    Hex(#[from] hex::FromHexError),
     #[error("whiteflag error")]
     WFError(#[from] fennel_whiteflag::WhiteflagError),
     #[error("wf_field::error")]
     WFFieldError(#[from] wf_field::Error),
+    #[error(transparent)]
+    TransportError(#[from] wf_buffer::TransportError),
+    #[error(transparent)]
+    BlockchainError(#[from] wf_transport::BlockchainError),
     #[error("issue serializing struct")]
     SerdeJsonError(#[from] serde_json::error::Error),
     #[error(transparent)]
     Other(#[from] Box<dyn std::error::Error>),
 }
+
+impl WhiteflagCLIError {
+    /// distinguishes invalid input from failing input and output
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            WhiteflagCLIError::Io(_)
+            | WhiteflagCLIError::BlockchainError(wf_transport::BlockchainError::Io(_)) => EXIT_IO,
+            WhiteflagCLIError::Record { error, .. } => error.exit_code(),
+            WhiteflagCLIError::InvalidConfig(..) | WhiteflagCLIError::UnsupportedBlockchain(_) => {
+                EXIT_CONFIG
+            }
+            WhiteflagCLIError::UnknownMessageCode(_)
+            | WhiteflagCLIError::BlockchainError(
+                wf_transport::BlockchainError::MessageTooLarge { .. }
+                | wf_transport::BlockchainError::InvalidTransaction(_)
+                | wf_transport::BlockchainError::InvalidBlock { .. },
+            )
+            | WhiteflagCLIError::UnknownField(_)
+            | WhiteflagCLIError::InvalidField { .. }
+            | WhiteflagCLIError::Hex(_)
+            | WhiteflagCLIError::Utf8(_)
+            | WhiteflagCLIError::WFError(_)
+            | WhiteflagCLIError::WFFieldError(_)
+            | WhiteflagCLIError::TransportError(_)
+            | WhiteflagCLIError::SerdeJsonError(_) => EXIT_INVALID,
+            _ => EXIT_FAILURE,
+        }
+    }
+
+    /// the error with the errors that caused it
+    pub fn describe(&self) -> String {
+        let mut description = self.to_string();
+        let mut source = self.source();
+        while let Some(error) = source {
+            description = format!("{}: {}", description, error);
+            source = error.source();
+        }
+        description
+    }
+}
@@ -0,0 +1,433 @@
+use crate::{error::WhiteflagCLIError, WhiteflagCLIResult};
+use fennel_whiteflag::WhiteflagMessage;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{
+    env, fs,
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+use wf_account::{account::WfAccount, whiteflag_account::WhiteflagAccount};
+use wf_auth::WhiteflagAuthToken;
+use wf_crypto::ecdh_keypair::WhiteflagECDHKeyPair;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+/// overrides the directory identities and the session are kept in
+pub const CONFIG_DIR_ENV: &str = "WF_CONFIG_DIR";
+
+/// a kind of entry in the store, kept as json files in a directory of their own
+#[derive(Clone, Copy)]
+pub(crate) struct Kind {
+    pub(crate) dir: &'static str,
+    pub(crate) name: &'static str,
+}
+
+const IDENTITY: Kind = Kind {
+    dir: "identities",
+    name: "identity",
+};
+const SESSION: &str = "session";
+const NO_REFERENCE: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+/// 4.2.1.7 Reference Indicator Field
+const DISCONTINUE: &str = "4";
+
+/// how an identity authenticates itself, Whiteflag Specification 5.1.2
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Authentication {
+    /// method 1, a url of an internet resource identifying the originator
+    Url(String),
+    /// method 2, a hexadecimal shared token
+    Token(String),
+}
+
+impl Authentication {
+    /// the authentication from the mutually exclusive `--auth-url` and `--auth-token` options
+    pub fn from_options(url: Option<String>, token: Option<String>) -> WhiteflagCLIResult<Self> {
+        match (url, token) {
+            (Some(url), None) => Ok(Authentication::Url(url)),
+            (None, Some(token)) => {
+                hex::decode(&token).map_err(|e| invalid(format!("auth token: {}", e)))?;
+                Ok(Authentication::Token(token))
+            }
+            _ => Err(invalid("expected either an auth url or an auth token")),
+        }
+    }
+
+    /// the verification method and data of the authentication message for the given address
+    fn verification(&self, address: &[u8]) -> WhiteflagCLIResult<(&'static str, String)> {
+        match self {
+            Authentication::Url(url) => Ok(("1", url.clone())),
+            Authentication::Token(token) => {
+                let token = WhiteflagAuthToken::new(hex::decode(token)?);
+                let data = token
+                    .get_verification_data(address)
+                    .map_err(|e| WhiteflagCLIError::Other(Box::new(e)))?;
+                Ok(("2", hex::encode(data)))
+            }
+        }
+    }
+}
+
+/// an account of our own, with its blockchain address and ECDH key pair
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Identity {
+    name: String,
+    address: String,
+    ecdh_secret: String,
+    authentication: Authentication,
+}
+
+impl Identity {
+    /// a new identity with a random ECDH key pair
+    pub fn create<A: AsRef<str>>(
+        name: &str,
+        address: A,
+        authentication: Authentication,
+    ) -> WhiteflagCLIResult<Self> {
+        let secret = StaticSecret::random_from_rng(rand_core::OsRng);
+        Self::import(
+            name,
+            address,
+            hex::encode(secret.as_bytes()),
+            authentication,
+        )
+    }
+
+    /// an identity with an existing hexadecimal ECDH secret
+    pub fn import<A: AsRef<str>, S: AsRef<str>>(
+        name: &str,
+        address: A,
+        ecdh_secret: S,
+        authentication: Authentication,
+    ) -> WhiteflagCLIResult<Self> {
+        check_name(name)?;
+        let address = address.as_ref().trim_start_matches("0x").to_lowercase();
+        hex::decode(&address).map_err(|e| invalid(format!("address: {}", e)))?;
+
+        let identity = Identity {
+            name: name.to_string(),
+            address,
+            ecdh_secret: ecdh_secret.as_ref().to_lowercase(),
+            authentication,
+        };
+        identity.secret()?;
+        Ok(identity)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// the hexadecimal blockchain address
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    pub fn authentication(&self) -> &Authentication {
+        &self.authentication
+    }
+
+    pub fn ecdh_public_key(&self) -> WhiteflagCLIResult<PublicKey> {
+        Ok(PublicKey::from(&self.secret()?))
+    }
+
+    /// the account to encrypt and decrypt messages as this identity
+    pub fn account(&self) -> WhiteflagCLIResult<WhiteflagAccount> {
+        let mut account = WhiteflagAccount::new(true);
+        account.set_address(hex::decode(&self.address)?);
+        account
+            .set_ecdh_keypair(WhiteflagECDHKeyPair::from_secret(self.secret()?))
+            .map_err(|e| WhiteflagCLIError::Other(Box::new(e)))?;
+
+        match &self.authentication {
+            Authentication::Url(url) => account.set_auth_url(url.as_bytes().to_vec()),
+            Authentication::Token(token) => {
+                account.set_auth_token(WhiteflagAuthToken::new(hex::decode(token)?))
+            }
+        }
+
+        Ok(account)
+    }
+
+    /// the A1 or A2 authentication message identifying this identity as originator
+    pub fn authentication_message(&self) -> WhiteflagCLIResult<String> {
+        self.message(None)
+    }
+
+    /// the authentication message discontinuing the authentication sent in the referenced message
+    pub fn discontinue_message(&self, referenced_message: &str) -> WhiteflagCLIResult<String> {
+        if referenced_message == NO_REFERENCE {
+            return Err(WhiteflagCLIError::MissingReference);
+        }
+        self.message(Some(referenced_message))
+    }
+
+    fn message(&self, discontinues: Option<&str>) -> WhiteflagCLIResult<String> {
+        let (method, data) = self
+            .authentication
+            .verification(&hex::decode(&self.address)?)?;
+
+        let mut message: Map<String, Value> =
+            serde_json::from_str(&WhiteflagMessage::new("A".to_string())?.as_json())?;
+        message.insert("verificationMethod".into(), method.into());
+        message.insert("verificationData".into(), data.into());
+        if let Some(referenced_message) = discontinues {
+            message.insert("referenceIndicator".into(), DISCONTINUE.into());
+            message.insert("referencedMessage".into(), referenced_message.into());
+        }
+
+        let json = Value::Object(message).to_string();
+        /* fails if the url or referenced message are not valid field values */
+        fennel_whiteflag::encode_from_json(&json)?;
+        Ok(json)
+    }
+
+    fn secret(&self) -> WhiteflagCLIResult<StaticSecret> {
+        let secret: [u8; 32] = hex::decode(&self.ecdh_secret)
+            .ok()
+            .and_then(|s| s.try_into().ok())
+            .ok_or_else(|| invalid("the ECDH secret must be 32 hexadecimal bytes"))?;
+        Ok(StaticSecret::from(secret))
+    }
+}
+
+/// identities and the active session, kept as json files in a config directory
+pub struct IdentityStore {
+    dir: PathBuf,
+}
+
+impl IdentityStore {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        IdentityStore { dir: dir.into() }
+    }
+
+    /// the store in `$WF_CONFIG_DIR`, or the `whiteflag` directory of the user's config directory
+    pub fn open_default() -> WhiteflagCLIResult<Self> {
+        config_dir()
+            .map(IdentityStore::new)
+            .ok_or_else(|| invalid(format!("no config directory, set {}", CONFIG_DIR_ENV)))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// saves a new identity, failing if one with the same name exists
+    pub fn add(&self, identity: &Identity) -> WhiteflagCLIResult<()> {
+        self.save(IDENTITY, &identity.name, identity)
+    }
+
+    pub fn get(&self, name: &str) -> WhiteflagCLIResult<Identity> {
+        self.load(IDENTITY, name)
+    }
+
+    /// the names of all identities, sorted
+    pub fn list(&self) -> WhiteflagCLIResult<Vec<String>> {
+        self.names(IDENTITY)
+    }
+
+    /// removes an identity, ending its session if it is active
+    pub fn remove(&self, name: &str) -> WhiteflagCLIResult<Identity> {
+        let identity = self.get(name)?;
+        if self.session()?.as_deref() == Some(name) {
+            fs::remove_file(self.dir.join(SESSION))?;
+        }
+
+        self.delete(IDENTITY, name)?;
+        Ok(identity)
+    }
+
+    /// starts a session as the identity, failing if another session is active
+    pub fn login(&self, name: &str) -> WhiteflagCLIResult<Identity> {
+        if let Some(active) = self.session()? {
+            return Err(WhiteflagCLIError::SessionActive(active));
+        }
+
+        let identity = self.get(name)?;
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.dir.join(SESSION), name)?;
+        Ok(identity)
+    }
+
+    /// ends the session, returning the identity it was for
+    pub fn logout(&self) -> WhiteflagCLIResult<Identity> {
+        let identity = self.active()?;
+        fs::remove_file(self.dir.join(SESSION))?;
+        Ok(identity)
+    }
+
+    /// the identity of the active session
+    pub fn active(&self) -> WhiteflagCLIResult<Identity> {
+        match self.session()? {
+            Some(name) => self.get(&name),
+            None => Err(WhiteflagCLIError::AuthenticationRequired),
+        }
+    }
+
+    /// tells whether the session is active as the identity
+    pub fn is_active(&self, name: &str) -> WhiteflagCLIResult<bool> {
+        Ok(self.session()?.as_deref() == Some(name))
+    }
+
+    /// keeps the transaction hash of the authentication message of the session, the message logging
+    /// out discontinues
+    pub fn record_authentication(&self, transaction_hash: &str) -> WhiteflagCLIResult<()> {
+        let name = self
+            .session()?
+            .ok_or(WhiteflagCLIError::AuthenticationRequired)?;
+        fs::write(
+            self.dir.join(SESSION),
+            format!("{}\n{}", name, transaction_hash),
+        )?;
+        Ok(())
+    }
+
+    /// the transaction hash of the authentication message of the session, once it has been sent
+    pub fn authentication_reference(&self) -> WhiteflagCLIResult<Option<String>> {
+        Ok(self.read_session()?.and_then(|session| {
+            session
+                .lines()
+                .nth(1)
+                .map(str::trim)
+                .filter(|hash| !hash.is_empty())
+                .map(str::to_string)
+        }))
+    }
+
+    /// the name of the identity of the session, on the first line of the session file
+    fn session(&self) -> WhiteflagCLIResult<Option<String>> {
+        Ok(self.read_session()?.map(|session| {
+            session
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        }))
+    }
+
+    fn read_session(&self) -> WhiteflagCLIResult<Option<String>> {
+        match fs::read_to_string(self.dir.join(SESSION)) {
+            Ok(session) => Ok(Some(session)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// saves an entry as `<name>.json` in the directory of its kind, failing if it exists
+    pub(crate) fn save<T: Serialize>(
+        &self,
+        kind: Kind,
+        name: &str,
+        entry: &T,
+    ) -> WhiteflagCLIResult<()> {
+        check_name(name)?;
+        let path = self.path(kind, name);
+        if path.exists() {
+            return Err(WhiteflagCLIError::Exists(kind.name, name.to_string()));
+        }
+
+        fs::create_dir_all(self.dir.join(kind.dir))?;
+        write_private(&path, serde_json::to_string_pretty(entry)?.as_bytes())
+    }
+
+    pub(crate) fn load<T: DeserializeOwned>(
+        &self,
+        kind: Kind,
+        name: &str,
+    ) -> WhiteflagCLIResult<T> {
+        check_name(name)?;
+        match fs::read_to_string(self.path(kind, name)) {
+            Ok(json) => Ok(serde_json::from_str(&json)?),
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                Err(WhiteflagCLIError::Unknown(kind.name, name.to_string()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// the names of all entries of a kind, sorted
+    pub(crate) fn names(&self, kind: Kind) -> WhiteflagCLIResult<Vec<String>> {
+        let entries = match fs::read_dir(self.dir.join(kind.dir)) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut names = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == "json") {
+                if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                    names.push(name.to_string());
+                }
+            }
+        }
+
+        names.sort();
+        Ok(names)
+    }
+
+    pub(crate) fn delete(&self, kind: Kind, name: &str) -> WhiteflagCLIResult<()> {
+        check_name(name)?;
+        match fs::remove_file(self.path(kind, name)) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                Err(WhiteflagCLIError::Unknown(kind.name, name.to_string()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn path(&self, kind: Kind, name: &str) -> PathBuf {
+        self.dir.join(kind.dir).join(format!("{}.json", name))
+    }
+}
+
+pub(crate) fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os(CONFIG_DIR_ENV) {
+        return Some(dir.into());
+    }
+
+    let base = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+    };
+    base.map(|base| base.join("whiteflag"))
+}
+
+/// identity files hold secrets, so only the owner may read them
+fn write_private(path: &Path, contents: &[u8]) -> WhiteflagCLIResult<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    options.open(path)?.write_all(contents)?;
+    Ok(())
+}
+
+/// names are used as file names
+pub(crate) fn check_name(name: &str) -> WhiteflagCLIResult<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(invalid(format!(
+            "names may only contain letters, digits, '-' and '_', not {:?}",
+            name
+        )))
+    }
+}
+
+fn invalid<T: Into<String>>(reason: T) -> WhiteflagCLIError {
+    WhiteflagCLIError::InvalidIdentity(reason.into())
+}
@@ -0,0 +1,236 @@
+use crate::compose::{json_name, validate};
+use std::fmt;
+use wf_buffer::WhiteflagBuffer;
+use wf_field::{
+    definitions::{header, request},
+    FieldDefinition, MessageCodeType,
+};
+
+/// a field of an encoded message, where it sits and what it decodes to
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InspectedField {
+    pub name: String,
+    pub encoding: String,
+    pub bit_start: usize,
+    pub bit_end: usize,
+    /// the bits of the field as `0` and `1` characters
+    pub bits: String,
+    /// the decoded value, or why decoding or validating it failed
+    pub value: Result<String, String>,
+}
+
+/// the fields of an encoded message in the order they are encoded
+///
+/// decoding stops at the first field that cannot be read, the bits after the last field are listed as
+/// padding, which fails unless it is less than a byte of zero bits. an encrypted body is listed as a
+/// whole, as its fields are only known after decryption
+pub fn inspect(buffer: &WhiteflagBuffer) -> Vec<InspectedField> {
+    let mut inspector = Inspector {
+        buffer,
+        cursor: 0,
+        fields: Vec::new(),
+    };
+    inspector.inspect();
+    inspector.fields
+}
+
+/// the inspected fields as a table, failed fields are marked with `!`
+pub fn format_table(fields: &[InspectedField]) -> String {
+    let rows: Vec<[String; 6]> = fields
+        .iter()
+        .map(|f| {
+            let (mark, value) = match &f.value {
+                Ok(value) => (" ", value.clone()),
+                Err(e) => ("!", e.clone()),
+            };
+            [
+                format!("{}{}", mark, f.name),
+                f.encoding.clone(),
+                f.bit_start.to_string(),
+                f.bit_end.to_string(),
+                value,
+                f.bits.clone(),
+            ]
+        })
+        .collect();
+
+    let header = [" field", "encoding", "start", "end", "value", "bits"].map(String::from);
+    let mut widths = [0; 6];
+    for row in rows.iter().chain([&header]) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut table = String::new();
+    for row in [&header].into_iter().chain(&rows) {
+        let line = Row(row, &widths).to_string();
+        table.push_str(line.trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+struct Row<'a>(&'a [String; 6], &'a [usize; 6]);
+
+impl fmt::Display for Row<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [name, encoding, start, end, value, bits] = self.0;
+        let [w_name, w_encoding, w_start, w_end, w_value, _] = self.1;
+        write!(
+            f,
+            "{:w_name$}  {:w_encoding$}  {:>w_start$}  {:>w_end$}  {:w_value$}  {}",
+            name, encoding, start, end, value, bits,
+        )
+    }
+}
+
+struct Inspector<'a> {
+    buffer: &'a WhiteflagBuffer,
+    /// the end of the last field, where request pairs and padding start
+    cursor: usize,
+    fields: Vec<InspectedField>,
+}
+
+impl Inspector<'_> {
+    fn inspect(&mut self) {
+        for definition in header::DEFINITIONS {
+            if self.read_at(definition).is_none() {
+                return;
+            }
+        }
+
+        let value = |name: &str| {
+            self.fields
+                .iter()
+                .find(|f| f.name == name)
+                .and_then(|f| f.value.clone().ok())
+                .unwrap_or_default()
+        };
+        let encrypted = value("encryptionIndicator") != "0";
+        let code = value("messageCode").chars().next().unwrap_or_default();
+
+        if encrypted {
+            let end = self.buffer.bit_length();
+            self.push("encrypted body", "", self.cursor, end, Ok(String::new()));
+            return;
+        }
+
+        let definitions = match MessageCodeType::from_code(code).definitions() {
+            Ok(definitions) => definitions,
+            Err(e) => {
+                let end = self.buffer.bit_length();
+                self.push("body", "", self.cursor, end, Err(e.to_string()));
+                return;
+            }
+        };
+        for definition in definitions {
+            if self.read_at(definition).is_none() {
+                return;
+            }
+        }
+
+        /* 4.3.1.9 Object Request Fields, pairs while whole pairs remain */
+        let pair_length =
+            request::OBJECT_TYPE.bit_length() + request::OBJECT_TYPE_QUANT.bit_length();
+        let mut pair = 1;
+        while code == 'Q' && self.buffer.bit_length() - self.cursor >= pair_length {
+            let object_type = format!("objectType{}", pair);
+            let quantity = format!("objectType{}Quant", pair);
+            if self
+                .read(&request::OBJECT_TYPE, object_type, self.cursor)
+                .is_none()
+                || self
+                    .read(&request::OBJECT_TYPE_QUANT, quantity, self.cursor)
+                    .is_none()
+            {
+                return;
+            }
+            pair += 1;
+        }
+
+        self.padding();
+    }
+
+    /// reads a header or body field at the position of its definition, a field without a fixed length
+    /// ends where its value does
+    fn read_at(&mut self, definition: &FieldDefinition) -> Option<()> {
+        self.read(
+            definition,
+            json_name(definition),
+            definition.positions.bit_start,
+        )
+    }
+
+    /// reads a field from a bit, `None` if it cannot be decoded and the fields after it are unknown
+    fn read(&mut self, definition: &FieldDefinition, name: String, start: usize) -> Option<()> {
+        let mut reader = self.buffer.reader();
+        reader.seek(start);
+        let read = reader.read_value(definition);
+        /* a field that runs past the end is shown up to the end */
+        let end = match &read {
+            Ok(_) if definition.bit_length() > 0 => definition.positions.bit_end,
+            Ok(_) => reader.position(),
+            Err(_) => self.buffer.bit_length(),
+        };
+        let decoded = read.is_ok();
+
+        let encoding = format!("{:?}", definition.bytes.encoding.kind);
+        let value =
+            read.map_err(|e| e.to_string())
+                .and_then(|value| match validate(definition, &value) {
+                    Ok(()) => Ok(value),
+                    Err(e) => Err(format!("{}: {:?}", e, value)),
+                });
+
+        self.push(&name, &encoding, start, end, value);
+        decoded.then_some(())
+    }
+
+    fn padding(&mut self) {
+        let end = self.buffer.bit_length();
+        let bits = end - self.cursor;
+        let zero = self.bits(self.cursor, end).chars().all(|b| b == '0');
+        let value = if bits < 8 && zero {
+            Ok(String::new())
+        } else {
+            Err(format!(
+                "{} bits that are not zero padding of the last byte",
+                bits
+            ))
+        };
+
+        if bits > 0 {
+            self.push("padding", "", self.cursor, end, value);
+        }
+    }
+
+    fn push(
+        &mut self,
+        name: &str,
+        encoding: &str,
+        start: usize,
+        end: usize,
+        value: Result<String, String>,
+    ) {
+        self.fields.push(InspectedField {
+            name: name.to_string(),
+            encoding: encoding.to_string(),
+            bit_start: start,
+            bit_end: end,
+            bits: self.bits(start, end),
+            value,
+        });
+        self.cursor = end;
+    }
+
+    fn bits(&self, start: usize, end: usize) -> String {
+        let bytes: &[u8] = self.buffer.as_ref();
+        (start..end)
+            .map(|i| match bytes[i / 8] >> (7 - i % 8) & 1 {
+                0 => '0',
+                _ => '1',
+            })
+            .collect()
+    }
+}
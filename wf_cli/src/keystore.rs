@@ -0,0 +1,213 @@
+use crate::{
+    error::WhiteflagCLIError,
+    identity::{check_name, Kind},
+    IdentityStore, WhiteflagCLIResult,
+};
+use serde::{Deserialize, Serialize};
+use wf_account::{account::WfAccount, whiteflag_account::WhiteflagAccount};
+use wf_crypto::wf_encryption_key::WhiteflagEncryptionKey;
+use x25519_dalek::PublicKey;
+
+const CONTACT: Kind = Kind {
+    dir: "contacts",
+    name: "contact",
+};
+const KEY: Kind = Kind {
+    dir: "keys",
+    name: "key",
+};
+
+/// the stored keys to encrypt or decrypt a message with, by name
+#[derive(Default)]
+pub struct CryptKeys<'a> {
+    /// our identity, the active session if left out
+    pub identity: Option<&'a str>,
+    /// the recipient of an encrypted message or the originator of a received one
+    pub contact: Option<&'a str>,
+    /// the pre-shared key of encryption method 2
+    pub key: Option<&'a str>,
+}
+
+impl CryptKeys<'_> {
+    /// the account of our identity
+    pub(crate) fn own_account(
+        &self,
+        store: &IdentityStore,
+    ) -> WhiteflagCLIResult<WhiteflagAccount> {
+        match self.identity {
+            Some(name) => store.get(name)?.account(),
+            None => store.active()?.account(),
+        }
+    }
+
+    /// the account of the contact holding the pre-shared key, a contact is needed for method 1 only
+    pub(crate) fn other_account(
+        &self,
+        store: &IdentityStore,
+        method: &str,
+    ) -> WhiteflagCLIResult<WhiteflagAccount> {
+        let mut account = match (self.contact, method) {
+            (Some(name), _) => store.contact(name)?.account()?,
+            (None, "1") => return Err(invalid("encryption method 1 needs a contact")),
+            (None, _) => WhiteflagAccount::new(false),
+        };
+
+        match (self.key, method) {
+            (Some(name), _) => account.set_shared_key(store.key(name)?.encryption_key()),
+            (None, "2") => return Err(invalid("encryption method 2 needs a pre-shared key")),
+            _ => {}
+        }
+        Ok(account)
+    }
+}
+
+/// another party messages are encrypted for or received from
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Contact {
+    name: String,
+    address: String,
+    /// needed for encryption method 1
+    ecdh_public_key: Option<String>,
+}
+
+impl Contact {
+    pub fn new<A: AsRef<str>>(
+        name: &str,
+        address: A,
+        ecdh_public_key: Option<&str>,
+    ) -> WhiteflagCLIResult<Self> {
+        check_name(name)?;
+        let contact = Contact {
+            name: name.to_string(),
+            address: hex_value("address", address.as_ref())?,
+            ecdh_public_key: ecdh_public_key
+                .map(|key| hex_value("ECDH public key", key))
+                .transpose()?,
+        };
+        contact.public_key()?;
+        Ok(contact)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// the hexadecimal blockchain address
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// the account of the contact, with its ECDH public key if it is known
+    pub fn account(&self) -> WhiteflagCLIResult<WhiteflagAccount> {
+        let mut account = WhiteflagAccount::new(false);
+        account.set_address(hex::decode(&self.address)?);
+        if let Some(public_key) = self.public_key()? {
+            account
+                .set_ecdh_public_key(public_key)
+                .map_err(|e| WhiteflagCLIError::Other(Box::new(e)))?;
+        }
+        Ok(account)
+    }
+
+    fn public_key(&self) -> WhiteflagCLIResult<Option<PublicKey>> {
+        self.ecdh_public_key
+            .as_ref()
+            .map(|key| {
+                let key: [u8; 32] = hex::decode(key)
+                    .ok()
+                    .and_then(|k| k.try_into().ok())
+                    .ok_or_else(|| invalid("the ECDH public key must be 32 hexadecimal bytes"))?;
+                Ok(PublicKey::from(key))
+            })
+            .transpose()
+    }
+}
+
+/// a raw key shared with other parties for encryption method 2
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PreSharedKey {
+    name: String,
+    key: String,
+}
+
+impl PreSharedKey {
+    pub fn new<K: AsRef<str>>(name: &str, key: K) -> WhiteflagCLIResult<Self> {
+        check_name(name)?;
+        let key = hex_value("pre-shared key", key.as_ref())?;
+        if key.is_empty() {
+            return Err(invalid("the pre-shared key is empty"));
+        }
+
+        Ok(PreSharedKey {
+            name: name.to_string(),
+            key,
+        })
+    }
+
+    /// a new random key of 32 bytes
+    pub fn generate(name: &str) -> WhiteflagCLIResult<Self> {
+        let mut key = [0; 32];
+        rand_core::RngCore::fill_bytes(&mut rand_core::OsRng, &mut key);
+        Self::new(name, hex::encode(key))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// the hexadecimal raw key, to share with the other parties
+    pub fn as_hex(&self) -> &str {
+        &self.key
+    }
+
+    pub fn encryption_key(&self) -> WhiteflagEncryptionKey {
+        WhiteflagEncryptionKey::from_preshared_key(&self.key)
+    }
+}
+
+impl IdentityStore {
+    pub fn add_contact(&self, contact: &Contact) -> WhiteflagCLIResult<()> {
+        self.save(CONTACT, &contact.name, contact)
+    }
+
+    pub fn contact(&self, name: &str) -> WhiteflagCLIResult<Contact> {
+        self.load(CONTACT, name)
+    }
+
+    pub fn contacts(&self) -> WhiteflagCLIResult<Vec<String>> {
+        self.names(CONTACT)
+    }
+
+    pub fn remove_contact(&self, name: &str) -> WhiteflagCLIResult<()> {
+        self.delete(CONTACT, name)
+    }
+
+    pub fn add_key(&self, key: &PreSharedKey) -> WhiteflagCLIResult<()> {
+        self.save(KEY, &key.name, key)
+    }
+
+    pub fn key(&self, name: &str) -> WhiteflagCLIResult<PreSharedKey> {
+        self.load(KEY, name)
+    }
+
+    pub fn keys(&self) -> WhiteflagCLIResult<Vec<String>> {
+        self.names(KEY)
+    }
+
+    pub fn remove_key(&self, name: &str) -> WhiteflagCLIResult<()> {
+        self.delete(KEY, name)
+    }
+}
+
+/// a lower case hexadecimal value, without a `0x` prefix
+fn hex_value(what: &str, value: &str) -> WhiteflagCLIResult<String> {
+    let value = value.trim_start_matches("0x").to_lowercase();
+    hex::decode(&value).map_err(|e| invalid(format!("{}: {}", what, e)))?;
+    Ok(value)
+}
+
+fn invalid<T: Into<String>>(reason: T) -> WhiteflagCLIError {
+    WhiteflagCLIError::InvalidKey(reason.into())
+}
@@ -1,11 +1,31 @@
 #[cfg(test)]
 mod test;
 
-mod auth;
+mod batch;
+mod compose;
+mod config;
 mod error;
+mod identity;
+mod inspect;
+mod keystore;
+mod man;
+mod validate;
 
-use crate::auth::UserAuthenticationState;
+pub use batch::{format_message, read_input, run_batch, BatchReport, OutputFormat};
+pub use compose::{prompt_code, subject_codes, Composer, MESSAGE_CODES, SUBJECT_CODES};
+pub use config::{Config, CONFIG_ENV, CONFIG_FILE, LEDGER_FILE};
+pub use error::{WhiteflagCLIError, EXIT_CONFIG, EXIT_FAILURE, EXIT_INVALID, EXIT_IO};
+use fennel_whiteflag::Message;
 pub use fennel_whiteflag::WhiteflagMessage;
+pub use identity::{Authentication, Identity, IdentityStore, CONFIG_DIR_ENV};
+pub use inspect::{format_table, inspect, InspectedField};
+pub use keystore::{Contact, CryptKeys, PreSharedKey};
+pub use man::man_pages;
+use serde_json::{Map, Value};
+pub use validate::{validate_message, Problem, Rule, ValidationReport};
+pub use wf_buffer::TransportEncoding;
+use wf_buffer::{BufferReader, WhiteflagBuffer};
+pub use wf_transport::{IncomingMessage, MockLedger, Network, Transport};
 
 pub struct WhiteflagCLICommands;
 pub type WhiteflagCLIResult<T> = Result<T, error::WhiteflagCLIError>;
@@ -19,31 +39,323 @@ impl WhiteflagCLICommands {
         Ok(fennel_whiteflag::decode_from_hex(hex)?)
     }
 
-    pub fn auth(logout: bool) -> WhiteflagCLIResult<String> {
-        Ok(if logout {
-            UserAuthenticationState::logout()
-        } else {
-            UserAuthenticationState::login()
-        }
-        .to_string())
+    /// converts a hexadecimal encoded message into the given transport encoding
+    pub fn to_encoding<T: AsRef<str>>(
+        hex: T,
+        encoding: TransportEncoding,
+    ) -> WhiteflagCLIResult<String> {
+        let buffer = WhiteflagBuffer::decode_from_encoding(hex, TransportEncoding::Hex)?;
+        Ok(buffer.as_encoding(encoding))
+    }
+
+    /// converts a message in the given transport encoding back into hexadecimal
+    pub fn from_encoding<T: AsRef<str>>(
+        value: T,
+        encoding: TransportEncoding,
+    ) -> WhiteflagCLIResult<String> {
+        let buffer = WhiteflagBuffer::decode_from_encoding(value, encoding)?;
+        Ok(buffer.as_encoding(TransportEncoding::Hex))
+    }
+
+    /// creates an identity with a new ECDH key pair, or imports one with the given secret
+    pub fn add_identity(
+        store: &IdentityStore,
+        name: &str,
+        address: &str,
+        ecdh_secret: Option<&str>,
+        authentication: Authentication,
+    ) -> WhiteflagCLIResult<String> {
+        let identity = match ecdh_secret {
+            Some(secret) => Identity::import(name, address, secret, authentication)?,
+            None => Identity::create(name, address, authentication)?,
+        };
+
+        store.add(&identity)?;
+        Self::show_identity(&identity)
     }
 
-    pub fn message(code: String) -> WhiteflagCLIResult<WhiteflagMessage> {
-        if !UserAuthenticationState::is_authenticated() {
-            Err(error::WhiteflagCLIError::AuthenticationRequired)
-        } else {
-            Ok(WhiteflagMessage::new(code)?)
+    /// the public details of an identity as json, leaving out its secrets
+    pub fn show_identity(identity: &Identity) -> WhiteflagCLIResult<String> {
+        let mut details = serde_json::json!({
+            "name": identity.name(),
+            "address": identity.address(),
+            "ecdhPublicKey": hex::encode(identity.ecdh_public_key()?.as_bytes()),
+        });
+        match identity.authentication() {
+            Authentication::Url(url) => details["authUrl"] = url.as_str().into(),
+            Authentication::Token(_) => details["authToken"] = true.into(),
         }
+
+        Ok(details.to_string())
+    }
+
+    /// starts a session as the identity and returns its authentication message
+    pub fn login(store: &IdentityStore, name: &str) -> WhiteflagCLIResult<String> {
+        store.login(name)?.authentication_message()
+    }
+
+    /// ends the session and returns the message discontinuing its authentication, the referenced
+    /// message; the session stays active if there is no message to discontinue
+    ///
+    /// the referenced message defaults to the authentication message sent with [`Self::send`]
+    /// during the session
+    pub fn logout(
+        store: &IdentityStore,
+        referenced_message: Option<&str>,
+    ) -> WhiteflagCLIResult<String> {
+        let referenced_message = match referenced_message {
+            Some(hash) => hash.to_string(),
+            None => store
+                .authentication_reference()?
+                .ok_or(WhiteflagCLIError::MissingReference)?,
+        };
+
+        let message = store.active()?.discontinue_message(&referenced_message)?;
+        store.logout()?;
+        Ok(message)
+    }
+
+    pub fn message(store: &IdentityStore, code: String) -> WhiteflagCLIResult<WhiteflagMessage> {
+        store.active()?;
+        Ok(WhiteflagMessage::new(code)?)
     }
 
     pub fn message_with_reference(
+        store: &IdentityStore,
         code: String,
         reference_code: String,
     ) -> WhiteflagCLIResult<WhiteflagMessage> {
-        if !UserAuthenticationState::is_authenticated() {
-            Err(error::WhiteflagCLIError::AuthenticationRequired)
-        } else {
-            Ok(WhiteflagMessage::new_with_reference(code, reference_code)?)
+        store.active()?;
+        Ok(WhiteflagMessage::new_with_reference(code, reference_code)?)
+    }
+
+    /// the json and the hexadecimal encoding of a composed message
+    pub fn compose(
+        store: &IdentityStore,
+        composer: &Composer,
+    ) -> WhiteflagCLIResult<(String, String)> {
+        store.active()?;
+        let message = composer.compose()?;
+        Ok((serde_json::to_string(&message)?, message.encode().as_hex()))
+    }
+
+    /// the fields of a message in the given transport encoding, see [`inspect`]
+    pub fn inspect<T: AsRef<str>>(
+        value: T,
+        encoding: TransportEncoding,
+    ) -> WhiteflagCLIResult<Vec<InspectedField>> {
+        let buffer = WhiteflagBuffer::decode_from_encoding(value.as_ref().trim(), encoding)?;
+        Ok(inspect(&buffer))
+    }
+
+    /// encrypts a json or hexadecimal message with encryption method 1 or 2
+    ///
+    /// returns the encrypted message in the given transport encoding and the hexadecimal
+    /// initialisation vector as json
+    pub fn encrypt(
+        store: &IdentityStore,
+        message: &str,
+        method: &str,
+        keys: &CryptKeys,
+        encoding: TransportEncoding,
+    ) -> WhiteflagCLIResult<String> {
+        if method != "1" && method != "2" {
+            return Err(error::WhiteflagCLIError::UnsupportedEncryptionMethod(
+                method.to_string(),
+            ));
+        }
+
+        let json = match message.trim_start().starts_with('{') {
+            true => message.to_string(),
+            false => Self::decode(message.trim())?,
+        };
+        let mut fields: Map<String, Value> = serde_json::from_str(&json)?;
+        fields.insert("encryptionIndicator".into(), method.into());
+
+        let mut message = Message::deserialize_from_json(Value::Object(fields).to_string())?;
+        message.set_originator(keys.own_account(store)?);
+        message.set_recipient(keys.other_account(store, method)?);
+
+        let iv = wf_crypto::generate_iv();
+        let encrypted = message.encrypt(&iv)?;
+        Ok(serde_json::json!({
+            "message": encrypted.as_encoding(encoding),
+            "iv": hex::encode(iv),
+        })
+        .to_string())
+    }
+
+    /// sends a json or encoded message from the address of an identity, the active session if left
+    /// out, returning the transaction hash
+    ///
+    /// the hash of the authentication message of the session is kept, for logging out
+    pub fn send<T: Transport>(
+        store: &IdentityStore,
+        transport: &mut T,
+        message: &str,
+        identity: Option<&str>,
+        encoding: TransportEncoding,
+    ) -> WhiteflagCLIResult<String> {
+        let identity = match identity {
+            Some(name) => store.get(name)?,
+            None => store.active()?,
+        };
+
+        let buffer = message_buffer(message, encoding)?;
+        let hash = transport.send(&hex::decode(identity.address())?, &buffer)?;
+
+        if store.is_active(identity.name())?
+            && buffer == message_buffer(&identity.authentication_message()?, encoding)?
+        {
+            store.record_authentication(&hash)?;
+        }
+        Ok(hash)
+    }
+
+    /// the `OP_RETURN` script of a bitcoin output embedding a json or encoded message, in hexadecimal
+    pub fn op_return(message: &str, encoding: TransportEncoding) -> WhiteflagCLIResult<String> {
+        let script = wf_transport::op_return_script(&message_buffer(message, encoding)?)?;
+        Ok(hex::encode(script.as_bytes()))
+    }
+
+    /// the messages in a file of raw bitcoin transactions or blocks as json, decoded where they can be
+    ///
+    /// a text file holds a hexadecimal transaction or block on every line, a binary one a single
+    /// transaction or block, or the blocks of a `blk*.dat` file
+    pub fn scan(
+        file: &[u8],
+        blocks: bool,
+        network: Network,
+        encoding: TransportEncoding,
+    ) -> WhiteflagCLIResult<Vec<String>> {
+        let scan = |raw: &[u8]| match blocks {
+            true => wf_transport::block_messages(raw, network),
+            false => wf_transport::transaction_messages(raw, network),
+        };
+
+        let found = match hex_lines(file) {
+            _ if blocks && wf_transport::is_block_file(file, network) => {
+                wf_transport::block_file_messages(file, network)?
+            }
+            Some(lines) => {
+                let mut found = Vec::new();
+                for line in lines {
+                    found.extend(scan(&hex::decode(line)?)?);
+                }
+                found
+            }
+            None => scan(file)?,
+        };
+
+        let mut scanned = Vec::new();
+        for found in found {
+            let mut details = serde_json::json!({
+                "transactionHash": found.transaction_hash,
+                "output": found.output,
+                "originator": found.originator.map(|address| address.to_string()),
+                "message": found.message.as_encoding(encoding),
+            });
+            if let Some(block_hash) = found.block_hash {
+                details["blockHash"] = block_hash.into();
+                details["timestamp"] = found.timestamp.into();
+            }
+            match Message::decode_from_buffer(found.message) {
+                Ok(message) => details["decoded"] = serde_json::to_value(&message)?,
+                Err(e) => details["error"] = e.to_string().into(),
+            }
+            scanned.push(details.to_string());
+        }
+        Ok(scanned)
+    }
+
+    /// the messages from a block on as json, with the contact they are from if it is known
+    ///
+    /// messages are given as received, in the transport encoding and without decoding or decrypting
+    /// them; decode them with [`Self::decode`] and decrypt encrypted ones with [`Self::decrypt`]
+    pub fn receive<T: Transport>(
+        store: &IdentityStore,
+        transport: &mut T,
+        block: u64,
+        encoding: TransportEncoding,
+    ) -> WhiteflagCLIResult<Vec<String>> {
+        let mut contacts = Vec::new();
+        for name in store.contacts()? {
+            let contact = store.contact(&name)?;
+            contacts.push((hex::decode(contact.address())?, name));
+        }
+
+        let mut received = Vec::new();
+        for incoming in transport.messages_from(block)? {
+            let mut details = serde_json::json!({
+                "transactionHash": incoming.transaction_hash,
+                "block": incoming.block,
+                "timestamp": incoming.timestamp,
+                "originator": hex::encode(&incoming.originator),
+                "message": incoming.message.as_encoding(encoding),
+            });
+            if let Some((_, name)) = contacts.iter().find(|(a, _)| *a == incoming.originator) {
+                details["contact"] = name.as_str().into();
+            }
+            received.push(details.to_string());
         }
+        Ok(received)
+    }
+
+    /// decrypts a message received from a contact as json
+    pub fn decrypt(
+        store: &IdentityStore,
+        message: &str,
+        iv: &str,
+        keys: &CryptKeys,
+        encoding: TransportEncoding,
+    ) -> WhiteflagCLIResult<String> {
+        if keys.contact.is_none() {
+            return Err(error::WhiteflagCLIError::InvalidKey(
+                "decrypting needs the contact that sent the message".into(),
+            ));
+        }
+
+        let buffer = WhiteflagBuffer::decode_from_encoding(message.trim(), encoding)?;
+        let method = wf_field::definitions::header::ENCRYPTION_INDICATOR
+            .read(&buffer)
+            .unwrap_or_default();
+
+        let message = Message::decrypt(
+            buffer,
+            keys.other_account(store, &method)?,
+            keys.own_account(store)?,
+            &hex::decode(iv)?,
+        )?;
+        Ok(serde_json::to_string(&message)?)
+    }
+}
+
+/// a json message encoded, or an encoded message
+fn message_buffer(
+    message: &str,
+    encoding: TransportEncoding,
+) -> WhiteflagCLIResult<WhiteflagBuffer> {
+    let message = message.trim();
+    Ok(match message.starts_with('{') {
+        true => Message::deserialize_from_json(message)?.encode().into(),
+        false => WhiteflagBuffer::decode_from_encoding(message, encoding)?,
+    })
+}
+
+/// the non-empty lines of a text file of hexadecimal values
+fn hex_lines(file: &[u8]) -> Option<Vec<&str>> {
+    let text = std::str::from_utf8(file).ok()?;
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    match !lines.is_empty()
+        && lines
+            .iter()
+            .all(|l| l.bytes().all(|b| b.is_ascii_hexdigit()))
+    {
+        true => Some(lines),
+        false => None,
     }
 }
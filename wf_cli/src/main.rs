@@ -1,57 +1,717 @@
-mod auth;
-#[cfg(test)]
-mod test;
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use std::{
+    error::Error,
+    fs,
+    io::{self, BufRead},
+    path::PathBuf,
+    process::ExitCode,
+};
+use wf_cli::{
+    format_message, read_input, run_batch, Authentication, Composer, Config, Contact, CryptKeys,
+    IdentityStore, Network, OutputFormat, PreSharedKey, TransportEncoding, WhiteflagCLICommands,
+    WhiteflagCLIError, WhiteflagCLIResult, WhiteflagMessage, EXIT_FAILURE, EXIT_INVALID, EXIT_IO,
+};
 
-use clap::{AppSettings, Parser, Subcommand};
-use std::error::Error;
-use wf_cli::WhiteflagCLICommands;
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(code) => ExitCode::from(code),
+        Err(e) => {
+            let code = if let Some(e) = e.downcast_ref::<WhiteflagCLIError>() {
+                eprintln!("Error: {}", e.describe());
+                e.exit_code()
+            } else {
+                eprintln!("Error: {}", e);
+                match e.downcast_ref::<io::Error>() {
+                    Some(_) => EXIT_IO,
+                    None => EXIT_FAILURE,
+                }
+            };
+            ExitCode::from(code)
+        }
+    }
+}
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let args = Args::parse();
+/// runs the command, returning the exit code
+fn run(args: Args) -> Result<u8, Box<dyn Error>> {
+    let config = match args.command {
+        Commands::Completions { .. } | Commands::Man { .. } => Config::default(),
+        _ => Config::load()?,
+    };
+    let encoding = args.encoding;
+    let output = args.output.or(config.output);
 
     let result = match args.command {
-        Commands::Encode { json } => WhiteflagCLICommands::encode(json)?,
-        Commands::Decode { hex } => WhiteflagCLICommands::decode(hex)?,
-        Commands::Auth { logout } => WhiteflagCLICommands::auth(logout)?,
+        Commands::Encode { input, batch } => {
+            let encode = |json: &str| {
+                let message = fennel_whiteflag::Message::deserialize_from_json(json)?;
+                format_message(&message, output, encoding)
+            };
+            return process(input, batch, encode);
+        }
+        Commands::Decode { input, batch } => {
+            let decode = |value: &str| {
+                let hex = WhiteflagCLICommands::from_encoding(value, encoding)?;
+                let message = fennel_whiteflag::Message::decode_from_hexadecimal(hex)?;
+                format_message(&message, output.or(Some(OutputFormat::Json)), encoding)
+            };
+            return process(input, batch, decode);
+        }
+        Commands::Identity(command) => {
+            let store = config.store()?;
+            identity(&store, command)?
+        }
+        Commands::Contact(command) => {
+            let store = config.store()?;
+            match command {
+                ContactCommands::Add {
+                    name,
+                    address,
+                    ecdh_public_key,
+                } => {
+                    let contact = Contact::new(&name, address, ecdh_public_key.as_deref())?;
+                    store.add_contact(&contact)?;
+                    name
+                }
+                ContactCommands::List => store.contacts()?.join("\n"),
+                ContactCommands::Remove { name } => {
+                    store.remove_contact(&name)?;
+                    name
+                }
+            }
+        }
+        Commands::Key(command) => {
+            let store = config.store()?;
+            match command {
+                KeyCommands::Add { name, key } => {
+                    store.add_key(&PreSharedKey::new(&name, key)?)?;
+                    name
+                }
+                KeyCommands::Generate { name } => {
+                    let key = PreSharedKey::generate(&name)?;
+                    store.add_key(&key)?;
+                    key.as_hex().to_string()
+                }
+                KeyCommands::List => store.keys()?.join("\n"),
+                KeyCommands::Remove { name } => {
+                    store.remove_key(&name)?;
+                    name
+                }
+            }
+        }
+        Commands::Encrypt {
+            input,
+            method,
+            to,
+            key,
+            r#as,
+        } => {
+            let store = config.store()?;
+            let keys = CryptKeys {
+                identity: r#as.as_deref().or(config.originator.as_deref()),
+                contact: to.as_deref(),
+                key: key.as_deref(),
+            };
+            let message = read_input(input.message, input.file.as_deref())?;
+            WhiteflagCLICommands::encrypt(&store, &message, &method, &keys, encoding)?
+        }
+        Commands::Decrypt {
+            input,
+            iv,
+            from,
+            key,
+            r#as,
+        } => {
+            let store = config.store()?;
+            let keys = CryptKeys {
+                identity: r#as.as_deref().or(config.originator.as_deref()),
+                contact: from.as_deref(),
+                key: key.as_deref(),
+            };
+            let message = read_input(input.message, input.file.as_deref())?;
+            WhiteflagCLICommands::decrypt(&store, &message, &iv, &keys, encoding)?
+        }
+        Commands::Auth(command) => {
+            let store = config.store()?;
+            match command {
+                AuthCommands::Login { name } => {
+                    let name = name
+                        .or(config.originator)
+                        .ok_or("an identity is required unless an originator is configured")?;
+                    WhiteflagCLICommands::login(&store, &name)?
+                }
+                AuthCommands::Logout { referenced_message } => {
+                    WhiteflagCLICommands::logout(&store, referenced_message.as_deref())?
+                }
+                AuthCommands::Status => store.active()?.name().to_string(),
+            }
+        }
         Commands::Message { code } => {
-            let hex = WhiteflagCLICommands::message(code)?.as_hex()?;
-            hex
+            let store = config.store()?;
+            template(
+                WhiteflagCLICommands::message(&store, code)?,
+                output,
+                encoding,
+            )?
+        }
+        Commands::Compose(options) => {
+            let store = config.store()?;
+            let (json, hex) = WhiteflagCLICommands::compose(&store, &compose(options)?)?;
+            match output {
+                Some(_) => {
+                    let message = fennel_whiteflag::Message::deserialize_from_json(json)?;
+                    format_message(&message, output, encoding)?
+                }
+                None => format!(
+                    "{}\n{}",
+                    json,
+                    WhiteflagCLICommands::to_encoding(hex, encoding)?
+                ),
+            }
+        }
+        Commands::Inspect { input } => {
+            let message = read_input(input.message, input.file.as_deref())?;
+            let fields = WhiteflagCLICommands::inspect(message, encoding)?;
+            print!("{}", wf_cli::format_table(&fields));
+            if fields.iter().any(|f| f.value.is_err()) {
+                eprintln!("Error: the message has fields that cannot be decoded or are invalid");
+                return Ok(EXIT_INVALID);
+            }
+            return Ok(0);
+        }
+        Commands::Validate { input, json } => {
+            let message = read_input(input.message, input.file.as_deref())?;
+            let report = wf_cli::validate_message(&message, encoding);
+            match json {
+                true => println!("{}", report.to_json()),
+                false => println!("{}", report),
+            }
+            return Ok(if report.valid { 0 } else { EXIT_INVALID });
+        }
+        Commands::Send {
+            input,
+            r#as,
+            ledger,
+        } => {
+            let store = config.store()?;
+            let mut transport = config.transport(&store, ledger.ledger)?;
+            let message = read_input(input.message, input.file.as_deref())?;
+            let identity = r#as.as_deref().or(config.originator.as_deref());
+            WhiteflagCLICommands::send(&store, &mut transport, &message, identity, encoding)?
+        }
+        Commands::Receive { from_block, ledger } => {
+            let store = config.store()?;
+            let mut transport = config.transport(&store, ledger.ledger)?;
+            WhiteflagCLICommands::receive(&store, &mut transport, from_block, encoding)?.join("\n")
+        }
+        Commands::Bitcoin(BitcoinCommands::Script { input }) => {
+            let message = read_input(input.message, input.file.as_deref())?;
+            WhiteflagCLICommands::op_return(&message, encoding)?
+        }
+        Commands::Bitcoin(BitcoinCommands::Scan {
+            file,
+            blocks,
+            network,
+        }) => {
+            let file = fs::read(file)?;
+            WhiteflagCLICommands::scan(&file, blocks, network, encoding)?.join("\n")
+        }
+        Commands::Config => {
+            let path = Config::path().map(|p| p.display().to_string());
+            format!(
+                "# {}\n{}",
+                path.as_deref().unwrap_or("no config file"),
+                config.to_toml()?.trim_end()
+            )
+        }
+        Commands::Completions { shell } => {
+            clap_complete::generate(shell, &mut Args::command(), "wf", &mut io::stdout());
+            return Ok(0);
+        }
+        Commands::Man { dir } => {
+            let pages = wf_cli::man_pages(&Args::command());
+            match dir {
+                Some(dir) => {
+                    fs::create_dir_all(&dir)?;
+                    for (name, page) in &pages {
+                        fs::write(dir.join(name), page)?;
+                    }
+                    return Ok(0);
+                }
+                None => pages
+                    .into_iter()
+                    .next()
+                    .map(|(_, page)| page)
+                    .unwrap_or_default(),
+            }
         }
         Commands::MessageWithReferenceCode {
             code,
             reference_code,
-        } => WhiteflagCLICommands::message_with_reference(code, reference_code)?.as_hex()?,
+        } => {
+            let store = config.store()?;
+            let message =
+                WhiteflagCLICommands::message_with_reference(&store, code, reference_code)?;
+            template(message, output, encoding)?
+        }
     };
 
     println!("{}", result);
-    Ok(())
+    Ok(0)
+}
+
+/// processes the whole input as one message, or each line of it with `--batch`
+fn process<F>(input: InputArgs, batch: BatchArgs, mut process: F) -> Result<u8, Box<dyn Error>>
+where
+    F: FnMut(&str) -> WhiteflagCLIResult<String>,
+{
+    if !batch.batch {
+        println!(
+            "{}",
+            process(read_input(input.message, input.file.as_deref())?.trim())?
+        );
+        return Ok(0);
+    }
+
+    let reader: Box<dyn BufRead> = match (input.message.as_deref(), input.file) {
+        (Some(message), _) if message != "-" => Box::new(io::Cursor::new(message.to_string())),
+        (_, Some(file)) => Box::new(io::BufReader::new(std::fs::File::open(file)?)),
+        _ => Box::new(io::stdin().lock()),
+    };
+    let report = run_batch(
+        reader,
+        io::stdout().lock(),
+        batch.continue_on_error,
+        process,
+    )?;
+    eprintln!("{}", report);
+    Ok(report.exit_code())
+}
+
+fn template(
+    message: WhiteflagMessage,
+    output: Option<OutputFormat>,
+    encoding: TransportEncoding,
+) -> WhiteflagCLIResult<String> {
+    let message = fennel_whiteflag::Message::deserialize_from_json(message.as_json())?;
+    format_message(&message, output, encoding)
+}
+
+fn identity(store: &IdentityStore, command: IdentityCommands) -> Result<String, Box<dyn Error>> {
+    Ok(match command {
+        IdentityCommands::Create {
+            name,
+            address,
+            auth_url,
+            auth_token,
+        } => {
+            let authentication = Authentication::from_options(auth_url, auth_token)?;
+            WhiteflagCLICommands::add_identity(store, &name, &address, None, authentication)?
+        }
+        IdentityCommands::Import {
+            name,
+            address,
+            ecdh_secret,
+            auth_url,
+            auth_token,
+        } => {
+            let authentication = Authentication::from_options(auth_url, auth_token)?;
+            WhiteflagCLICommands::add_identity(
+                store,
+                &name,
+                &address,
+                Some(&ecdh_secret),
+                authentication,
+            )?
+        }
+        IdentityCommands::List => store.list()?.join("\n"),
+        IdentityCommands::Show { name } => WhiteflagCLICommands::show_identity(&store.get(&name)?)?,
+        IdentityCommands::Remove { name } => store.remove(&name)?.name().to_string(),
+    })
+}
+
+fn compose(options: ComposeOptions) -> Result<Composer, Box<dyn Error>> {
+    let code = match options.code {
+        Some(code) => code,
+        None if options.interactive => {
+            wf_cli::prompt_code(io::stdin().lock(), io::stderr())?.to_string()
+        }
+        None => return Err("a message code is required unless composing interactively".into()),
+    };
+
+    let mut composer = Composer::new(&code)?;
+    let named = [
+        ("subjectCode", options.subject_code),
+        ("dateTime", options.date_time),
+        ("duration", options.duration),
+        ("objectType", options.object_type),
+        ("objectLatitude", options.lat),
+        ("objectLongitude", options.long),
+        ("objectSizeDim1", options.size_dim1),
+        ("objectSizeDim2", options.size_dim2),
+        ("objectOrientation", options.orientation),
+        ("text", options.text),
+    ];
+    for (name, value) in named {
+        if let Some(value) = value {
+            composer.set(name, &value)?;
+        }
+    }
+    for field in &options.field {
+        let (name, value) = field
+            .split_once('=')
+            .ok_or_else(|| format!("expected <field>=<value>, not {:?}", field))?;
+        composer.set(name, value)?;
+    }
+    for pair in &options.request {
+        composer.add_request_pair(pair)?;
+    }
+
+    if options.interactive {
+        composer.prompt(io::stdin().lock(), io::stderr())?;
+    }
+    Ok(composer)
 }
 
 #[derive(Parser)]
-#[clap(name = "wf")]
+#[clap(name = "wf", version)]
 #[clap(about = "A tool for interacting with the Whiteflag Protocol", long_about = None)]
 pub struct Args {
     #[clap(subcommand)]
     pub command: Commands,
+
+    /// transport encoding of encoded messages: hex, base64, base64url, base45 or zbase32
+    #[clap(long, global = true, default_value = "hex")]
+    pub encoding: TransportEncoding,
+
+    /// prints messages as json, hex, base64 or a table of their fields instead, see `wf config`
+    #[clap(long, global = true)]
+    pub output: Option<OutputFormat>,
+}
+
+/// a message given as an argument, in a file, or on stdin if left out or `-`
+#[derive(clap::Args)]
+pub struct InputArgs {
+    #[clap(conflicts_with = "file")]
+    pub message: Option<String>,
+    #[clap(long)]
+    pub file: Option<PathBuf>,
+}
+
+#[derive(clap::Args)]
+pub struct BatchArgs {
+    /// processes one message per line, json lines to encode and encoded lines to decode
+    #[clap(long)]
+    pub batch: bool,
+    /// processes the lines after a failing one, reporting the failures when done
+    #[clap(long, requires = "batch")]
+    pub continue_on_error: bool,
+}
+
+/// the mock ledger standing in for a blockchain
+#[derive(clap::Args)]
+pub struct LedgerArgs {
+    /// the file of the mock ledger, `ledger.jsonl` in the keystore if left out
+    #[clap(long)]
+    pub ledger: Option<PathBuf>,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
-    #[clap(setting(AppSettings::ArgRequiredElseHelp))]
-    Encode { json: String },
+    /// encodes a json message
+    Encode {
+        #[clap(flatten)]
+        input: InputArgs,
+        #[clap(flatten)]
+        batch: BatchArgs,
+    },
 
-    #[clap(setting(AppSettings::ArgRequiredElseHelp))]
-    Decode { hex: String },
+    /// decodes an encoded message into json
+    Decode {
+        #[clap(flatten)]
+        input: InputArgs,
+        #[clap(flatten)]
+        batch: BatchArgs,
+    },
 
-    #[clap()]
-    Auth { logout: bool },
+    /// manages the identities messages are sent as
+    #[clap(subcommand)]
+    Identity(IdentityCommands),
+
+    /// starts or ends a session as an identity
+    #[clap(subcommand)]
+    Auth(AuthCommands),
+
+    /// manages the addresses and ECDH public keys of other parties
+    #[clap(subcommand)]
+    Contact(ContactCommands),
+
+    /// manages pre-shared keys for encryption method 2
+    #[clap(subcommand)]
+    Key(KeyCommands),
+
+    /// encrypts a json or encoded message, printing the encrypted message and its iv
+    Encrypt {
+        #[clap(flatten)]
+        input: InputArgs,
+        /// 1 for a key negotiated with the contact's ECDH public key, 2 for a pre-shared key
+        #[clap(long)]
+        method: String,
+        /// the contact the message is for, needed for method 1
+        #[clap(long)]
+        to: Option<String>,
+        /// the pre-shared key for method 2
+        #[clap(long)]
+        key: Option<String>,
+        /// the identity sending the message, the configured originator or the active session if left out
+        #[clap(long)]
+        r#as: Option<String>,
+    },
+
+    /// decrypts a message received from a contact, printing it as json
+    Decrypt {
+        #[clap(flatten)]
+        input: InputArgs,
+        /// hexadecimal initialisation vector the message was encrypted with
+        #[clap(long)]
+        iv: String,
+        /// the contact that sent the message
+        #[clap(long)]
+        from: Option<String>,
+        /// the pre-shared key for method 2
+        #[clap(long)]
+        key: Option<String>,
+        /// the identity the message is for, the configured originator or the active session if left out
+        #[clap(long)]
+        r#as: Option<String>,
+    },
 
     #[clap()]
     Message { code: String },
 
+    /// composes a message from options, or by prompting for each field with `--interactive`
+    Compose(ComposeOptions),
+
+    /// prints the position, bits and value of every field of an encoded message
+    Inspect {
+        #[clap(flatten)]
+        input: InputArgs,
+    },
+
+    /// checks a json or encoded message, printing every problem with its specification section
+    ///
+    /// which reference indicators a message code allows is not checked, only that the indicator is
+    /// defined and agrees with the referenced message
+    Validate {
+        #[clap(flatten)]
+        input: InputArgs,
+        /// prints the report as json
+        #[clap(long)]
+        json: bool,
+    },
+
     #[clap()]
     MessageWithReferenceCode {
         code: String,
         reference_code: String,
     },
+
+    /// sends a json or encoded message on the blockchain, printing the transaction hash
+    Send {
+        #[clap(flatten)]
+        input: InputArgs,
+        /// the identity sending the message, the configured originator or the active session if left out
+        #[clap(long)]
+        r#as: Option<String>,
+        #[clap(flatten)]
+        ledger: LedgerArgs,
+    },
+
+    /// prints the messages on the blockchain from a block on as json lines, encoded as they were sent
+    Receive {
+        #[clap(long, default_value = "0")]
+        from_block: u64,
+        #[clap(flatten)]
+        ledger: LedgerArgs,
+    },
+
+    /// embeds messages in bitcoin transactions and extracts them from raw transactions and blocks
+    #[clap(subcommand)]
+    Bitcoin(BitcoinCommands),
+
+    /// prints the settings of the config file with the environment variables applied
+    Config,
+
+    /// prints the completions of a shell: bash, elvish, fish, powershell or zsh
+    Completions {
+        #[clap(value_parser)]
+        shell: Shell,
+    },
+
+    /// prints the man page of wf, or writes the pages of all commands into a directory
+    Man {
+        #[clap(long)]
+        dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BitcoinCommands {
+    /// prints the OP_RETURN script of an output embedding a json or encoded message
+    Script {
+        #[clap(flatten)]
+        input: InputArgs,
+    },
+
+    /// prints the messages in a file of raw transactions or blocks as json lines, decoded
+    Scan {
+        /// hexadecimal transactions or blocks on separate lines, or a binary transaction or block
+        file: PathBuf,
+        /// the file holds blocks, which may also be a blk*.dat file of a node
+        #[clap(long)]
+        blocks: bool,
+        /// bitcoin, testnet, signet or regtest
+        #[clap(long, value_parser, default_value = "bitcoin")]
+        network: Network,
+    },
+}
+
+#[derive(clap::Args)]
+pub struct ComposeOptions {
+    /// message code, e.g. P for a protective sign; chosen from a menu with `--interactive`
+    pub code: Option<String>,
+    /// prompts for the fields that have no value, and for request pairs
+    #[clap(short, long)]
+    pub interactive: bool,
+    /// sets a field by its json name, e.g. `--field verificationData=https://organisation.int`
+    #[clap(long)]
+    pub field: Vec<String>,
+    #[clap(long)]
+    pub subject_code: Option<String>,
+    /// defaults to now
+    #[clap(long)]
+    pub date_time: Option<String>,
+    #[clap(long)]
+    pub duration: Option<String>,
+    #[clap(long)]
+    pub object_type: Option<String>,
+    /// object latitude, e.g. +30.79658
+    #[clap(long, allow_hyphen_values = true)]
+    pub lat: Option<String>,
+    /// object longitude, e.g. -037.82602
+    #[clap(long, allow_hyphen_values = true)]
+    pub long: Option<String>,
+    #[clap(long)]
+    pub size_dim1: Option<String>,
+    #[clap(long)]
+    pub size_dim2: Option<String>,
+    #[clap(long)]
+    pub orientation: Option<String>,
+    #[clap(long)]
+    pub text: Option<String>,
+    /// a request pair of a request signal as `<object type>:<quantity>`, may be repeated
+    #[clap(long)]
+    pub request: Vec<String>,
+}
+
+#[derive(Subcommand)]
+pub enum IdentityCommands {
+    /// creates an identity with a new ECDH key pair
+    Create {
+        name: String,
+        /// blockchain address, hexadecimal
+        #[clap(long)]
+        address: String,
+        /// url of the internet resource for A1 authentication
+        #[clap(long)]
+        auth_url: Option<String>,
+        /// hexadecimal shared token for A2 authentication
+        #[clap(long)]
+        auth_token: Option<String>,
+    },
+
+    /// imports an identity with an existing ECDH secret
+    Import {
+        name: String,
+        /// blockchain address, hexadecimal
+        #[clap(long)]
+        address: String,
+        /// 32 byte ECDH secret, hexadecimal
+        #[clap(long)]
+        ecdh_secret: String,
+        /// url of the internet resource for A1 authentication
+        #[clap(long)]
+        auth_url: Option<String>,
+        /// hexadecimal shared token for A2 authentication
+        #[clap(long)]
+        auth_token: Option<String>,
+    },
+
+    /// lists the names of all identities
+    List,
+
+    /// shows the address, ECDH public key and authentication of an identity
+    Show { name: String },
+
+    /// removes an identity
+    Remove { name: String },
+}
+
+#[derive(Subcommand)]
+pub enum ContactCommands {
+    /// adds a contact
+    Add {
+        name: String,
+        /// blockchain address, hexadecimal
+        #[clap(long)]
+        address: String,
+        /// 32 byte ECDH public key, hexadecimal, needed for encryption method 1
+        #[clap(long)]
+        ecdh_public_key: Option<String>,
+    },
+
+    /// lists the names of all contacts
+    List,
+
+    /// removes a contact
+    Remove { name: String },
+}
+
+#[derive(Subcommand)]
+pub enum KeyCommands {
+    /// adds an existing hexadecimal pre-shared key
+    Add {
+        name: String,
+        #[clap(long)]
+        key: String,
+    },
+
+    /// adds a random 32 byte pre-shared key and prints it to share with the other parties
+    Generate { name: String },
+
+    /// lists the names of all pre-shared keys
+    List,
+
+    /// removes a pre-shared key
+    Remove { name: String },
+}
+
+#[derive(Subcommand)]
+pub enum AuthCommands {
+    /// starts a session as the identity and prints its authentication message
+    Login {
+        /// the configured originator if left out
+        name: Option<String>,
+    },
+
+    /// ends the session and prints the message discontinuing its authentication
+    Logout {
+        /// transaction hash of the authentication message being discontinued, the one sent with
+        /// `wf send` during the session if left out
+        #[clap(long)]
+        referenced_message: Option<String>,
+    },
+
+    /// prints the name of the identity of the active session
+    Status,
 }
@@ -0,0 +1,72 @@
+use crate::config::{CONFIG_ENV, CONFIG_FILE};
+use crate::identity::CONFIG_DIR_ENV;
+use clap::Command;
+use clap_mangen::roff::{bold, italic, roman, Roff};
+use clap_mangen::Man;
+
+/// the man pages of a command and all of its subcommands, as file names and roff sources
+///
+/// subcommands get pages of their own, named after their path like `wf-identity-create.1`. the page of
+/// the command itself describes the config file and the environment variables
+pub fn man_pages(command: &Command) -> Vec<(String, String)> {
+    let mut command = command.clone().disable_help_subcommand(true);
+    command.build();
+    let source = format!(
+        "{} {}",
+        command.get_name(),
+        command.get_version().unwrap_or(env!("CARGO_PKG_VERSION"))
+    );
+
+    let mut pages = Vec::new();
+    add_pages(&command, &source, &mut pages);
+    if let Some((_, page)) = pages.first_mut() {
+        page.push_str(&environment().to_roff());
+    }
+    pages
+}
+
+fn add_pages(command: &Command, source: &str, pages: &mut Vec<(String, String)>) {
+    let name = command
+        .get_display_name()
+        .unwrap_or_else(|| command.get_name());
+    let man = Man::new(command.clone())
+        .title(name.to_uppercase())
+        .source(source);
+
+    let mut page = Vec::new();
+    man.render(&mut page).unwrap_or_default();
+    pages.push((
+        man.get_filename(),
+        String::from_utf8_lossy(&page).into_owned(),
+    ));
+
+    for subcommand in command.get_subcommands().filter(|c| !c.is_hide_set()) {
+        add_pages(subcommand, source, pages);
+    }
+}
+
+/// the environment variables and the config file of the page of the command itself
+fn environment() -> Roff {
+    let mut roff = Roff::default();
+    roff.control("SH", ["ENVIRONMENT"]);
+    roff.control("TP", []);
+    roff.text([bold(CONFIG_DIR_ENV)]);
+    roff.text([roman(
+        "the directory of the config file and of the default keystore",
+    )]);
+    for (name, setting) in CONFIG_ENV {
+        roff.control("TP", []);
+        roff.text([bold(*name)]);
+        roff.text([roman(format!(
+            "overrides the {} setting of the config file",
+            setting
+        ))]);
+    }
+    roff.control("SH", ["FILES"]);
+    roff.control("TP", []);
+    roff.text([italic(format!("~/.config/whiteflag/{}", CONFIG_FILE))]);
+    roff.text([roman(
+        "the config file, with the originator, keystore, output and blockchain settings",
+    )]);
+    roff
+}
@@ -1,12 +1,133 @@
-use crate::WhiteflagCLICommands;
+use crate::{
+    format_message, man_pages, prompt_code, run_batch, Authentication, Composer, Config, Contact,
+    CryptKeys, Identity, IdentityStore, OutputFormat, PreSharedKey, Rule, TransportEncoding,
+    WhiteflagCLICommands, WhiteflagCLIError, EXIT_INVALID, EXIT_IO,
+};
 
-use super::auth::{acquire_auth_lock, check_auth_lock, release_auth_lock};
+const ADDRESS: &str = "007a0baf6f84f0fa7402ea972686e56d50b707c9b67b108866";
+const ECDH_SECRET: &str = "a8c7ed6a8cbf6d8e1ccb87e1a2d6a0ab5cdf12a4e6c0e2aa2f0c8c4f76b5d950";
+const AUTH_URL: &str = "https://organisation.int/whiteflag";
+
+/// an empty store in a directory of its own
+fn store(name: &str) -> IdentityStore {
+    let dir = std::env::temp_dir().join(format!("wf_cli_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    IdentityStore::new(dir)
+}
+
+fn field(json: &str, name: &str) -> String {
+    let json: serde_json::Value = serde_json::from_str(json).unwrap();
+    json[name].as_str().unwrap().to_string()
+}
+
+#[test]
+fn test_identity_store() {
+    let store = store("identity_store");
+    assert!(store.list().unwrap().is_empty());
+
+    let url = Identity::create("alice", ADDRESS, Authentication::Url(AUTH_URL.into())).unwrap();
+    let token = Identity::import(
+        "bob",
+        format!("0x{}", ADDRESS),
+        ECDH_SECRET,
+        Authentication::Token("0123456789abcdef".into()),
+    )
+    .unwrap();
+    store.add(&url).unwrap();
+    store.add(&token).unwrap();
+
+    assert_eq!(vec!["alice", "bob"], store.list().unwrap());
+    assert_eq!(token, store.get("bob").unwrap());
+    assert!(store.add(&url).is_err(), "identities are not overwritten");
+    assert!(store.get("carol").is_err());
+
+    let account = store.get("bob").unwrap().account().unwrap();
+    assert_eq!(
+        wf_account::account::WfAccount::get_address(&account),
+        Some(&hex::decode(ADDRESS).unwrap())
+    );
+
+    store.remove("alice").unwrap();
+    assert_eq!(vec!["bob"], store.list().unwrap());
+}
 
 #[test]
-fn test_auth_lock_system() {
-    acquire_auth_lock();
-    assert!(check_auth_lock());
-    assert!(release_auth_lock());
+fn test_invalid_identities() {
+    let url = || Authentication::Url(AUTH_URL.into());
+    assert!(Identity::create("../alice", ADDRESS, url()).is_err());
+    assert!(Identity::create("alice", "not an address", url()).is_err());
+    assert!(Identity::import("alice", ADDRESS, "abcd", url()).is_err());
+    assert!(Authentication::from_options(None, None).is_err());
+    assert!(Authentication::from_options(Some(AUTH_URL.into()), Some("00".into())).is_err());
+    assert!(Authentication::from_options(None, Some("xyz".into())).is_err());
+}
+
+#[test]
+fn test_session() {
+    let store = store("session");
+    let identity =
+        Identity::create("alice", ADDRESS, Authentication::Url(AUTH_URL.into())).unwrap();
+    store.add(&identity).unwrap();
+
+    assert!(WhiteflagCLICommands::message(&store, "F".into()).is_err());
+    assert!(WhiteflagCLICommands::login(&store, "carol").is_err());
+
+    let login = WhiteflagCLICommands::login(&store, "alice").unwrap();
+    assert_eq!("A", field(&login, "messageCode"));
+    assert_eq!("1", field(&login, "verificationMethod"));
+    assert_eq!(AUTH_URL, field(&login, "verificationData"));
+    assert!(WhiteflagCLICommands::encode(&login).is_ok());
+
+    assert!(WhiteflagCLICommands::login(&store, "alice").is_err());
+    assert_eq!("alice", store.active().unwrap().name());
+    assert!(WhiteflagCLICommands::message(&store, "F".into()).is_ok());
+
+    /* logging out needs the authentication message to discontinue, and keeps the session without */
+    assert!(matches!(
+        WhiteflagCLICommands::logout(&store, None),
+        Err(WhiteflagCLIError::MissingReference)
+    ));
+    assert!(matches!(
+        WhiteflagCLICommands::logout(&store, Some(&"0".repeat(64))),
+        Err(WhiteflagCLIError::MissingReference)
+    ));
+    assert_eq!("alice", store.active().unwrap().name());
+
+    let referenced = "3efb4e0cfa83122b242634254c1920a769d615dfcc4c670bb53eb6f12843c3ae";
+    let logout = WhiteflagCLICommands::logout(&store, Some(referenced)).unwrap();
+    assert_eq!("4", field(&logout, "referenceIndicator"));
+    assert_eq!(referenced, field(&logout, "referencedMessage"));
+    assert_eq!(AUTH_URL, field(&logout, "verificationData"));
+
+    assert!(WhiteflagCLICommands::logout(&store, None).is_err());
+    assert!(WhiteflagCLICommands::message(&store, "F".into()).is_err());
+}
+
+#[test]
+fn test_token_authentication_message() {
+    let identity = Identity::import(
+        "bob",
+        ADDRESS,
+        ECDH_SECRET,
+        Authentication::Token("0123456789abcdef".into()),
+    )
+    .unwrap();
+    let message = identity.authentication_message().unwrap();
+
+    assert_eq!("2", field(&message, "verificationMethod"));
+    assert_eq!(64, field(&message, "verificationData").len());
+}
+
+#[test]
+fn test_removed_identity_has_no_session() {
+    let store = store("removed_identity");
+    let identity =
+        Identity::create("alice", ADDRESS, Authentication::Url(AUTH_URL.into())).unwrap();
+    store.add(&identity).unwrap();
+    store.login("alice").unwrap();
+
+    store.remove("alice").unwrap();
+    assert!(store.active().is_err());
 }
 
 #[test]
@@ -47,3 +168,708 @@ fn test_decode_message() {
     let result = WhiteflagCLICommands::decode(hex_string);
     assert!(result.is_ok());
 }
+
+#[test]
+fn test_transport_encoding_round_trip() {
+    let hex_string = "5746313120800000000000000000000000000000000000000000000000000000000000000000b43a3a38399d1797b7b933b0b734b9b0ba34b7b71734b73a17bbb434ba32b33630b380";
+    let base45 = WhiteflagCLICommands::to_encoding(hex_string, TransportEncoding::Base45).unwrap();
+    assert!(base45
+        .bytes()
+        .all(|b| b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:".contains(&b)));
+
+    let hex = WhiteflagCLICommands::from_encoding(base45, TransportEncoding::Base45).unwrap();
+    assert_eq!(hex, hex_string);
+    assert!(WhiteflagCLICommands::from_encoding("GGW", TransportEncoding::Base45).is_err());
+}
+
+#[test]
+fn test_compose_from_options() {
+    let mut composer = Composer::new("P").unwrap();
+    composer.set("subjectCode", "10").unwrap();
+    composer.set("objectLatitude", "-12.34567").unwrap();
+    assert!(composer.set("objectLatitude", "1").is_err());
+    assert!(composer.set("text", "hello").is_err());
+    assert!(composer.add_request_pair("10:02").is_err());
+
+    let json = serde_json::to_string(&composer.compose().unwrap()).unwrap();
+    assert_eq!("10", field(&json, "subjectCode"));
+    assert_eq!("-12.34567", field(&json, "objectLatitude"));
+    assert_eq!("-037.82602", field(&json, "objectLongitude"));
+    assert_ne!("2013-08-31T04:29:15Z", field(&json, "dateTime"));
+    assert!(WhiteflagCLICommands::encode(json).is_ok());
+
+    assert!(Composer::new("X").is_err());
+    assert!(Composer::new("PP").is_err());
+}
+
+#[test]
+fn test_compose_request_pairs() {
+    let mut composer = Composer::new("Q").unwrap();
+    composer.add_request_pair("10:02").unwrap();
+    composer.add_request("20", "03").unwrap();
+    assert!(composer.add_request_pair("10").is_err());
+    assert!(composer.add_request("20", "3").is_err());
+
+    let json = serde_json::to_string(&composer.compose().unwrap()).unwrap();
+    assert_eq!("10", field(&json, "objectType1"));
+    assert_eq!("03", field(&json, "objectType2Quant"));
+
+    let hex = composer.compose().unwrap().encode().as_hex();
+    assert_eq!(json, WhiteflagCLICommands::decode(hex).unwrap());
+}
+
+#[test]
+fn test_compose_prompts() {
+    /* an invalid subject code is asked again, empty answers take the defaults */
+    let answers = "q\n1\n20\n\n\n+01.00000\n\n\n\n\n10\n02\n\n";
+    let mut input = answers.as_bytes();
+    let mut output = Vec::new();
+
+    let code = prompt_code(&mut input, &mut output).unwrap();
+    assert_eq!('Q', code);
+
+    let mut composer = Composer::new(&code.to_string()).unwrap();
+    composer.set("objectType", "33").unwrap();
+    composer.prompt(&mut input, &mut output).unwrap();
+
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("invalid subjectCode"));
+    for (code, meaning) in crate::subject_codes('Q') {
+        assert!(output.contains(&format!("  {}  {}\n", code, meaning)));
+    }
+    assert!(
+        !output.contains(crate::subject_codes('P')[0].1),
+        "only the subject codes of the message code are listed"
+    );
+    for (message_code, codes) in crate::SUBJECT_CODES {
+        let mut composer = Composer::new(&message_code.to_string()).unwrap();
+        for (code, _) in *codes {
+            composer.set("subjectCode", code).unwrap();
+        }
+    }
+    assert!(
+        !output.contains("objectType [22]"),
+        "fields given are not asked"
+    );
+
+    let json = serde_json::to_string(&composer.compose().unwrap()).unwrap();
+    assert_eq!("20", field(&json, "subjectCode"));
+    assert_eq!("33", field(&json, "objectType"));
+    assert_eq!("+01.00000", field(&json, "objectLatitude"));
+    assert_eq!("8765", field(&json, "objectSizeDim1"));
+    assert_eq!("02", field(&json, "objectType1Quant"));
+
+    let mut composer = Composer::new("F").unwrap();
+    assert!(composer.prompt(&b""[..], Vec::new()).is_err());
+}
+
+#[test]
+fn test_compose_needs_session() {
+    let store = store("compose");
+    let identity =
+        Identity::create("alice", ADDRESS, Authentication::Url(AUTH_URL.into())).unwrap();
+    store.add(&identity).unwrap();
+
+    let composer = Composer::new("F").unwrap();
+    assert!(WhiteflagCLICommands::compose(&store, &composer).is_err());
+
+    store.login("alice").unwrap();
+    let (json, hex) = WhiteflagCLICommands::compose(&store, &composer).unwrap();
+    assert_eq!(json, WhiteflagCLICommands::decode(hex).unwrap());
+}
+
+#[test]
+fn test_inspect_message() {
+    let hex_string = "57463130a6a1f7da7067d41891592131a12a60c9053b4eb0aefe6263385da9f5b789421e1d7401009841882148a800000114c1e596006f04c050eca6420084";
+    let fields = WhiteflagCLICommands::inspect(hex_string, TransportEncoding::Hex).unwrap();
+
+    assert_eq!(17, fields.len());
+    assert!(fields.iter().all(|f| f.value.is_ok()));
+    assert!(fields
+        .windows(2)
+        .all(|pair| pair[0].bit_end == pair[1].bit_start));
+
+    let latitude = fields.iter().find(|f| f.name == "objectLatitude").unwrap();
+    assert_eq!("LAT", latitude.encoding);
+    assert_eq!((397, 426), (latitude.bit_start, latitude.bit_end));
+    assert_eq!(Ok("+30.79658".to_string()), latitude.value);
+    assert_eq!("10011000001111001011001011000", latitude.bits);
+
+    /* fixed fields sit where their definitions put them */
+    assert_eq!(Ok("M".to_string()), fields[4].value);
+    let definitions = wf_field::definitions::header::DEFINITIONS.iter().chain(
+        wf_field::MessageCodeType::from_code('M')
+            .definitions()
+            .unwrap(),
+    );
+    for (field, definition) in fields.iter().zip(definitions) {
+        assert_eq!(
+            (definition.positions.bit_start, definition.positions.bit_end),
+            (field.bit_start, field.bit_end),
+            "{}",
+            field.name
+        );
+    }
+
+    let table = crate::format_table(&fields);
+    assert_eq!(fields.len() + 1, table.lines().count());
+    assert!(!table.contains('!'));
+}
+
+#[test]
+fn test_inspect_failures() {
+    let auth = "5746313020800000000000000000000000000000000000000000000000000000000000000000b43a3a38399d1797b7b933b0b734b9b0ba34b7b71734b73a17bbb434ba32b33630b380";
+
+    /* a truncated header ends at the field that cannot be read */
+    let fields = WhiteflagCLICommands::inspect(&auth[..6], TransportEncoding::Hex).unwrap();
+    assert_eq!(3, fields.len());
+    assert!(fields[2].value.is_err());
+
+    /* the last field takes the whole characters of the byte added, the ones left over are not padding */
+    let fields =
+        WhiteflagCLICommands::inspect(format!("{}ff", auth), TransportEncoding::Hex).unwrap();
+    let padding = fields.last().unwrap();
+    assert_eq!("padding", padding.name);
+    assert!(padding.value.is_err());
+    assert!(crate::format_table(&fields).contains("!padding"));
+
+    /* the body of an encrypted message is not decoded */
+    let encrypted = auth.replacen("574631302", "574631312", 1);
+    let fields = WhiteflagCLICommands::inspect(encrypted, TransportEncoding::Hex).unwrap();
+    assert_eq!("encrypted body", fields.last().unwrap().name);
+}
+
+const FREE_TEXT: &str = "{\"prefix\":\"WF\",\"version\":\"1\",\"encryptionIndicator\":\"0\",\"duressIndicator\":\"0\",\"messageCode\":\"F\",\"referenceIndicator\":\"0\",\"referencedMessage\":\"0000000000000000000000000000000000000000000000000000000000000000\",\"text\":\"hello bob\"}";
+
+/// a store with the identities alice and bob, each a contact of the other, and a pre-shared key
+fn crypt_store(name: &str) -> IdentityStore {
+    let store = store(name);
+    for (name, address) in [("alice", "00aa11"), ("bob", "00bb22")] {
+        let identity =
+            Identity::create(name, address, Authentication::Url(AUTH_URL.into())).unwrap();
+        let public_key = hex::encode(identity.ecdh_public_key().unwrap().as_bytes());
+        store.add(&identity).unwrap();
+
+        let contact =
+            Contact::new(&format!("{}-contact", name), address, Some(&public_key)).unwrap();
+        store.add_contact(&contact).unwrap();
+    }
+    store
+        .add_key(&PreSharedKey::new("shared", ECDH_SECRET).unwrap())
+        .unwrap();
+    store
+}
+
+fn keys<'a>(identity: &'a str, contact: &'a str, key: Option<&'a str>) -> CryptKeys<'a> {
+    CryptKeys {
+        identity: Some(identity),
+        contact: Some(contact),
+        key,
+    }
+}
+
+#[test]
+fn test_contacts_and_keys() {
+    let store = crypt_store("contacts");
+    assert_eq!(
+        vec!["alice-contact", "bob-contact"],
+        store.contacts().unwrap()
+    );
+    assert_eq!(vec!["shared"], store.keys().unwrap());
+    assert_eq!("00bb22", store.contact("bob-contact").unwrap().address());
+    assert!(store
+        .add_key(&PreSharedKey::generate("shared").unwrap())
+        .is_err());
+
+    assert!(Contact::new("carol", "00cc", Some("abcd")).is_err());
+    assert!(Contact::new("carol", "xyz", None).is_err());
+    assert!(PreSharedKey::new("empty", "").is_err());
+    assert_eq!(64, PreSharedKey::generate("new").unwrap().as_hex().len());
+
+    store.remove_contact("alice-contact").unwrap();
+    store.remove_key("shared").unwrap();
+    assert!(store.contact("alice-contact").is_err());
+    assert!(store.remove_key("shared").is_err());
+}
+
+#[test]
+fn test_encrypt_and_decrypt() {
+    let store = crypt_store("encrypt");
+    let hex = WhiteflagCLICommands::encode(FREE_TEXT).unwrap();
+
+    for (method, key) in [("1", None), ("2", Some("shared"))] {
+        for message in [FREE_TEXT, &hex] {
+            let encrypted = WhiteflagCLICommands::encrypt(
+                &store,
+                message,
+                method,
+                &keys("alice", "bob-contact", key),
+                TransportEncoding::Hex,
+            )
+            .unwrap();
+            let encrypted_message = field(&encrypted, "message");
+            let iv = field(&encrypted, "iv");
+            assert!(!encrypted_message.contains(&hex[76..]));
+
+            let decrypted = WhiteflagCLICommands::decrypt(
+                &store,
+                &encrypted_message,
+                &iv,
+                &keys("bob", "alice-contact", key),
+                TransportEncoding::Hex,
+            )
+            .unwrap();
+            assert_eq!(method, field(&decrypted, "encryptionIndicator"));
+            assert_eq!("hello bob", field(&decrypted, "text"));
+
+            /* the key is bound to the originator's address */
+            assert!(WhiteflagCLICommands::decrypt(
+                &store,
+                &encrypted_message,
+                &iv,
+                &keys("bob", "bob-contact", key),
+                TransportEncoding::Hex,
+            )
+            .map_or(true, |json| field(&json, "text") != "hello bob"));
+        }
+    }
+}
+
+#[test]
+fn test_encryption_needs_keys() {
+    let store = crypt_store("encryption_keys");
+    let encrypt = |method: &str, keys: &CryptKeys| {
+        WhiteflagCLICommands::encrypt(&store, FREE_TEXT, method, keys, TransportEncoding::Hex)
+    };
+
+    assert!(encrypt("3", &keys("alice", "bob-contact", None)).is_err());
+    assert!(encrypt("2", &keys("alice", "bob-contact", None)).is_err());
+    assert!(
+        encrypt("1", &CryptKeys::default()).is_err(),
+        "needs a session"
+    );
+
+    let no_contact = CryptKeys {
+        identity: Some("alice"),
+        key: Some("shared"),
+        ..Default::default()
+    };
+    assert!(encrypt("1", &no_contact).is_err());
+    assert!(encrypt("2", &no_contact).is_ok());
+}
+
+fn encode_line(json: &str) -> crate::WhiteflagCLIResult<String> {
+    let message = fennel_whiteflag::Message::deserialize_from_json(json)?;
+    format_message(&message, None, TransportEncoding::Hex)
+}
+
+#[test]
+fn test_batch() {
+    let hex = WhiteflagCLICommands::encode(FREE_TEXT).unwrap();
+    let input = format!("{}\n\n{{}}\n{}\n", FREE_TEXT, FREE_TEXT);
+
+    let mut output = Vec::new();
+    let report = run_batch(input.as_bytes(), &mut output, true, encode_line).unwrap();
+    assert_eq!(
+        format!("{}\n{}\n", hex, hex),
+        String::from_utf8(output).unwrap()
+    );
+    assert_eq!(3, report.records);
+    assert_eq!(
+        vec![3],
+        report.failures.iter().map(|f| f.0).collect::<Vec<_>>()
+    );
+    assert_eq!(EXIT_INVALID, report.exit_code());
+    assert!(report
+        .to_string()
+        .ends_with("3 records, 2 succeeded, 1 failed"));
+
+    let mut output = Vec::new();
+    let error = run_batch(input.as_bytes(), &mut output, false, encode_line).unwrap_err();
+    assert!(matches!(error, WhiteflagCLIError::Record { line: 3, .. }));
+    assert_eq!(EXIT_INVALID, error.exit_code());
+    assert_eq!(format!("{}\n", hex), String::from_utf8(output).unwrap());
+
+    let report = run_batch(&b""[..], Vec::new(), false, encode_line).unwrap();
+    assert_eq!((0, 0), (report.records, report.exit_code()));
+}
+
+#[test]
+fn test_batch_lines_that_are_not_utf8_fail() {
+    let hex = WhiteflagCLICommands::encode(FREE_TEXT).unwrap();
+    let input = [FREE_TEXT.as_bytes(), b"\n\xff\xfe\n", FREE_TEXT.as_bytes()].concat();
+
+    let mut output = Vec::new();
+    let report = run_batch(input.as_slice(), &mut output, true, encode_line).unwrap();
+    assert_eq!(
+        format!("{}\n{}\n", hex, hex),
+        String::from_utf8(output).unwrap()
+    );
+    assert_eq!(3, report.records);
+    assert!(matches!(
+        report.failures.as_slice(),
+        [(2, WhiteflagCLIError::Utf8(_))]
+    ));
+    assert_eq!(EXIT_INVALID, report.exit_code());
+
+    let error = run_batch(input.as_slice(), Vec::new(), false, encode_line).unwrap_err();
+    assert!(matches!(error, WhiteflagCLIError::Record { line: 2, .. }));
+    assert_eq!(EXIT_INVALID, error.exit_code());
+}
+
+#[test]
+fn test_batch_io_errors_end_the_batch() {
+    let input = format!("{}\n{}\n", FREE_TEXT, FREE_TEXT);
+    let mut calls = 0;
+    let error = run_batch(input.as_bytes(), Vec::new(), true, |_| {
+        calls += 1;
+        Err(std::io::Error::from(std::io::ErrorKind::BrokenPipe).into())
+    })
+    .unwrap_err();
+
+    assert_eq!(1, calls);
+    assert_eq!(EXIT_IO, error.exit_code());
+}
+
+#[test]
+fn test_output_formats() {
+    let message = fennel_whiteflag::Message::deserialize_from_json(FREE_TEXT).unwrap();
+    let format = |output: &str| {
+        let output: OutputFormat = output.parse().unwrap();
+        format_message(&message, Some(output), TransportEncoding::Base45).unwrap()
+    };
+
+    let hex = WhiteflagCLICommands::encode(FREE_TEXT).unwrap();
+    assert_eq!(hex, format("hex"));
+    assert_eq!(
+        WhiteflagCLICommands::to_encoding(&hex, TransportEncoding::Base64).unwrap(),
+        format("BASE64")
+    );
+    assert_eq!("hello bob", field(&format("json"), "text"));
+    assert!(format("table").contains("hello bob"));
+    assert_eq!(
+        WhiteflagCLICommands::to_encoding(&hex, TransportEncoding::Base45).unwrap(),
+        format_message(&message, None, TransportEncoding::Base45).unwrap()
+    );
+    assert!("yaml".parse::<OutputFormat>().is_err());
+}
+
+#[test]
+fn test_validate_message() {
+    let report = crate::validate_message(FREE_TEXT, TransportEncoding::Hex);
+    assert!(report.valid);
+    assert!(report.problems.is_empty());
+
+    let hex = WhiteflagCLICommands::encode(FREE_TEXT).unwrap();
+    assert!(crate::validate_message(&hex, TransportEncoding::Hex).valid);
+
+    let json = FREE_TEXT
+        .replace("\"version\":\"1\"", "\"version\":\"2\"")
+        .replace("\"duressIndicator\":\"0\",", "")
+        .replace(
+            "\"referenceIndicator\":\"0\"",
+            "\"referenceIndicator\":\"3\"",
+        )
+        .replace("\"text\"", "\"subjectCode\":\"10\",\"text\"");
+    let report = crate::validate_message(&json, TransportEncoding::Hex);
+    assert!(!report.valid);
+
+    let problems: Vec<_> = report
+        .problems
+        .iter()
+        .map(|p| (p.field.as_str(), p.section, p.rule))
+        .collect();
+    assert_eq!(
+        vec![
+            ("duressIndicator", "4.2.1.5", Rule::Syntax),
+            ("subjectCode", "4.3.3.1", Rule::Syntax),
+            ("version", "4.2.1.3", Rule::Semantics),
+            ("referencedMessage", "4.2.1.8", Rule::Reference),
+        ],
+        problems
+    );
+
+    let report: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+    assert_eq!(false, report["valid"]);
+    assert_eq!("reference", report["problems"][3]["rule"]);
+}
+
+#[test]
+fn test_validate_values() {
+    let sign = WhiteflagCLICommands::decode(
+        "57463130a6a1f7da7067d41891592131a12a60c9053b4eb0aefe6263385da9f5b789421e1d7401009841882148a800000114c1e596006f04c050eca6420084",
+    )
+    .unwrap();
+    assert!(crate::validate_message(&sign, TransportEncoding::Hex).valid);
+
+    let invalid = |name: &str, value: &str| {
+        let mut json: serde_json::Value = serde_json::from_str(&sign).unwrap();
+        json[name] = value.into();
+        crate::validate_message(&json.to_string(), TransportEncoding::Hex).problems
+    };
+    for (name, value) in [
+        ("dateTime", "2021-02-29T12:00:00Z"),
+        ("dateTime", "2020-01-01T24:00:00Z"),
+        ("duration", "P00D00H60M"),
+        ("objectLatitude", "-90.00001"),
+        ("objectLongitude", "+180.50000"),
+        ("objectOrientation", "361"),
+        ("messageCode", "X"),
+        ("referenceIndicator", "A"),
+    ] {
+        let problems = invalid(name, value);
+        assert_eq!(1, problems.len(), "{} {}", name, value);
+        assert_eq!(
+            (name, Rule::Semantics),
+            (problems[0].field.as_str(), problems[0].rule)
+        );
+    }
+    assert!(invalid("dateTime", "2020-02-29T23:59:59Z").is_empty());
+    assert_eq!(
+        "4.2.1.7",
+        invalid("referenceIndicator", "F").first().unwrap().section
+    );
+    assert_eq!(
+        Rule::Syntax,
+        invalid("objectType", "1").first().unwrap().rule
+    );
+
+    /* encoded messages are checked as they are decoded */
+    let problems = crate::validate_message("5746", TransportEncoding::Hex).problems;
+    assert_eq!("version", problems[0].field);
+    let problems = crate::validate_message("not hex", TransportEncoding::Hex).problems;
+    assert_eq!("message", problems[0].field);
+}
+
+#[test]
+fn test_config() {
+    let config = Config::parse(
+        "originator = \"alice\"\nkeystore = \"/tmp/wf\"\noutput = \"table\"\nblockchain = \"ethereum\"\n",
+    )
+    .unwrap();
+    assert_eq!(Some("alice"), config.originator.as_deref());
+    assert_eq!(Some(OutputFormat::Table), config.output);
+    assert_eq!(
+        std::path::Path::new("/tmp/wf"),
+        config.store().unwrap().dir()
+    );
+    assert_eq!(config, Config::parse(&config.to_toml().unwrap()).unwrap());
+
+    let env = |name: &str| match name {
+        "WF_ORIGINATOR" => Some("bob".to_string()),
+        "WF_OUTPUT" => Some("HEX".to_string()),
+        "WF_BLOCKCHAIN" => Some(String::new()),
+        _ => None,
+    };
+    let config = config.with_env(env).unwrap();
+    assert_eq!(Some("bob"), config.originator.as_deref());
+    assert_eq!(Some(OutputFormat::Hex), config.output);
+    assert_eq!(Some("ethereum"), config.blockchain.as_deref());
+
+    let error = Config::default()
+        .with_env(|name| (name == "WF_OUTPUT").then(|| "xml".to_string()))
+        .unwrap_err();
+    assert!(matches!(&error, WhiteflagCLIError::InvalidConfig(source, _) if source == "WF_OUTPUT"));
+    assert_eq!(crate::EXIT_CONFIG, error.exit_code());
+    assert!(Config::parse("origin = \"alice\"").is_err());
+
+    let dir = store("config").dir().to_path_buf();
+    let path = dir.join(crate::CONFIG_FILE);
+    assert_eq!(Config::default(), Config::read(&path).unwrap());
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(&path, "output = 1").unwrap();
+    assert!(matches!(
+        Config::read(&path),
+        Err(WhiteflagCLIError::InvalidConfig(source, _)) if source == path.display().to_string()
+    ));
+}
+
+#[test]
+fn test_man_pages() {
+    let command = clap::Command::new("wf")
+        .about("a tool")
+        .arg(
+            clap::Arg::new("file")
+                .long("file")
+                .value_name("FILE")
+                .help(".tsv-files only"),
+        )
+        .subcommand(
+            clap::Command::new("identity")
+                .about("manages identities")
+                .subcommand(clap::Command::new("list").about("lists identities")),
+        );
+    let pages = man_pages(&command);
+
+    let names: Vec<_> = pages.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(vec!["wf.1", "wf-identity.1", "wf-identity-list.1"], names);
+
+    let (_, page) = &pages[0];
+    assert!(page.contains(".TH WF 1 "));
+    assert!(page.contains("\\fB\\-\\-file\\fR \\fI<FILE>\\fR\n\\&.tsv\\-files only\n"));
+    assert!(page.contains("wf\\-identity(1)"));
+    assert!(page.contains("WF_KEYSTORE"));
+    assert!(!pages[2].1.contains("ENVIRONMENT"));
+    assert!(pages[2].1.contains("\\fBwf identity list\\fR"));
+}
+
+#[test]
+fn test_send_receive_and_decrypt() {
+    let store = crypt_store("send_receive");
+    let mut ledger = crate::MockLedger::open(store.dir().join(crate::LEDGER_FILE));
+
+    /* alice sends an encrypted message to bob, then a plain one without a session */
+    let encrypted = WhiteflagCLICommands::encrypt(
+        &store,
+        FREE_TEXT,
+        "1",
+        &keys("alice", "bob-contact", None),
+        TransportEncoding::Hex,
+    )
+    .unwrap();
+    let hash = WhiteflagCLICommands::send(
+        &store,
+        &mut ledger,
+        &field(&encrypted, "message"),
+        Some("alice"),
+        TransportEncoding::Hex,
+    )
+    .unwrap();
+    assert!(matches!(
+        WhiteflagCLICommands::send(&store, &mut ledger, FREE_TEXT, None, TransportEncoding::Hex),
+        Err(WhiteflagCLIError::AuthenticationRequired)
+    ));
+    store.login("alice").unwrap();
+    WhiteflagCLICommands::send(&store, &mut ledger, FREE_TEXT, None, TransportEncoding::Hex)
+        .unwrap();
+
+    /* bob reads them, from the file in another transport */
+    let mut ledger = crate::MockLedger::open(store.dir().join(crate::LEDGER_FILE));
+    let received =
+        WhiteflagCLICommands::receive(&store, &mut ledger, 0, TransportEncoding::Base64).unwrap();
+    assert_eq!(2, received.len());
+    assert_eq!(hash, field(&received[0], "transactionHash"));
+    assert_eq!("00aa11", field(&received[0], "originator"));
+    assert_eq!("alice-contact", field(&received[0], "contact"));
+
+    let decrypted = WhiteflagCLICommands::decrypt(
+        &store,
+        &field(&received[0], "message"),
+        &field(&encrypted, "iv"),
+        &keys("bob", &field(&received[0], "contact"), None),
+        TransportEncoding::Base64,
+    )
+    .unwrap();
+    assert_eq!("hello bob", field(&decrypted, "text"));
+    assert!(crate::validate_message(&decrypted, TransportEncoding::Hex).valid);
+
+    let plain = WhiteflagCLICommands::from_encoding(
+        field(&received[1], "message"),
+        TransportEncoding::Base64,
+    )
+    .unwrap();
+    assert!(crate::validate_message(&plain, TransportEncoding::Hex).valid);
+    assert_eq!(
+        1,
+        WhiteflagCLICommands::receive(&store, &mut ledger, 1, TransportEncoding::Hex)
+            .unwrap()
+            .len()
+    );
+}
+
+#[test]
+fn test_logout_discontinues_the_sent_authentication() {
+    let store = crypt_store("logout_sent");
+    let mut ledger = crate::MockLedger::open(store.dir().join(crate::LEDGER_FILE));
+
+    let login = WhiteflagCLICommands::login(&store, "alice").unwrap();
+    WhiteflagCLICommands::send(&store, &mut ledger, FREE_TEXT, None, TransportEncoding::Hex)
+        .unwrap();
+    assert_eq!(None, store.authentication_reference().unwrap());
+
+    let hash =
+        WhiteflagCLICommands::send(&store, &mut ledger, &login, None, TransportEncoding::Hex)
+            .unwrap();
+    assert_eq!(
+        Some(hash.clone()),
+        store.authentication_reference().unwrap()
+    );
+    assert_eq!("alice", store.active().unwrap().name());
+
+    let logout = WhiteflagCLICommands::logout(&store, None).unwrap();
+    assert_eq!("4", field(&logout, "referenceIndicator"));
+    assert_eq!(hash, field(&logout, "referencedMessage"));
+    assert!(crate::validate_message(&logout, TransportEncoding::Hex).valid);
+    assert!(store.active().is_err());
+}
+
+#[test]
+fn test_transport_of_the_configured_blockchain() {
+    let store = store("transport");
+    let config = Config::default();
+    let ledger = config.transport(&store, None).unwrap();
+    assert_eq!(
+        Some(store.dir().join(crate::LEDGER_FILE).as_path()),
+        ledger.path()
+    );
+
+    let config = Config {
+        blockchain: Some("ethereum".into()),
+        ..Config::default()
+    };
+    assert!(matches!(
+        config.transport(&store, None),
+        Err(WhiteflagCLIError::UnsupportedBlockchain(_))
+    ));
+}
+
+/* a p2wpkh spend on regtest with the auth message in its first output */
+const BITCOIN_TX: &str = "020000000001018dd4f5fbd5e980fc02f35c6ce145935b11e284605bf599a13c6d415db55d07a10000000000fdffffff0200000000000000004b6a495746313020800000000000000000000000000000000000000000000000000000000000000000b43a3a38399d1797b7b933b0b734b9b0ba34b7b71734b73a17bbb434ba32b33630b380b8820100000000001600149b78039087bd663f20ace711f15be0eaf7d07005024730440220500ea079c4cf5cccf6c5a2cf266e8c0e7fac9d260acae7e3a39994807195a5830220447fc5dfed2eedb4256b8b62b204e5b622bd5e6590fa84570c9db82cecb876590121024e3b81af9c2234cad09d679ce6035ed1392347ce64ce405f5dcd36228a25de6e00000000";
+
+#[test]
+fn test_op_return_script() {
+    let script = WhiteflagCLICommands::op_return(FREE_TEXT, TransportEncoding::Hex).unwrap();
+    assert!(script.starts_with("6a"), "{}", script);
+    assert_eq!(
+        script,
+        WhiteflagCLICommands::op_return(&script[4..], TransportEncoding::Hex).unwrap()
+    );
+
+    let long_text = FREE_TEXT.replace("hello bob", &"hello bob ".repeat(8));
+    let error = WhiteflagCLICommands::op_return(&long_text, TransportEncoding::Hex).unwrap_err();
+    assert!(matches!(
+        error,
+        WhiteflagCLIError::BlockchainError(wf_transport::BlockchainError::MessageTooLarge { .. })
+    ));
+    assert_eq!(EXIT_INVALID, error.exit_code());
+}
+
+#[test]
+fn test_scan_bitcoin_transactions() {
+    let network = crate::Network::Regtest;
+    let file = format!("{}\n\n{}\n", BITCOIN_TX, BITCOIN_TX);
+    let scanned =
+        WhiteflagCLICommands::scan(file.as_bytes(), false, network, TransportEncoding::Hex)
+            .unwrap();
+    assert_eq!(2, scanned.len());
+    assert_eq!(
+        "38031463385a28a7bc868b9932afed71f4226521ef0e4ba04a6b2680fa8aa370",
+        field(&scanned[0], "transactionHash")
+    );
+    assert_eq!(
+        "bcrt1qnduq8yy8h4nr7g9vuuglzklqatmaquq94hh56s",
+        field(&scanned[0], "originator")
+    );
+    let json: serde_json::Value = serde_json::from_str(&scanned[0]).unwrap();
+    assert_eq!("A", json["decoded"]["messageCode"]);
+    assert_eq!(0, json["output"]);
+
+    /* the same transaction in binary */
+    let binary = hex::decode(BITCOIN_TX).unwrap();
+    let scanned = WhiteflagCLICommands::scan(&binary, false, network, TransportEncoding::Hex);
+    assert_eq!(1, scanned.unwrap().len());
+
+    let error = WhiteflagCLICommands::scan(&binary[1..], false, network, TransportEncoding::Hex)
+        .unwrap_err();
+    assert_eq!(EXIT_INVALID, error.exit_code());
+}
@@ -0,0 +1,400 @@
+use crate::{
+    compose::{json_name, validate, MESSAGE_CODES},
+    error::WhiteflagCLIError,
+    inspect,
+};
+use fennel_whiteflag::Message;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fmt;
+use wf_buffer::{TransportEncoding, WhiteflagBuffer};
+use wf_field::{
+    definitions::{header, request},
+    FieldDefinition, MessageCodeType,
+};
+
+const NO_REFERENCE: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// the kind of rule a problem breaks
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Rule {
+    /// the field is missing, unknown, cannot be decoded or has the wrong length or characters
+    Syntax,
+    /// the value is well formed but has no meaning, e.g. a month 13
+    Semantics,
+    /// the reference indicator and referenced message do not agree
+    Reference,
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Rule::Syntax => "syntax",
+            Rule::Semantics => "semantics",
+            Rule::Reference => "reference",
+        })
+    }
+}
+
+/// a field that breaks a rule of the section of the Whiteflag Specification defining it
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// the json name of the field, or `message` for the message as a whole
+    pub field: String,
+    pub section: &'static str,
+    pub rule: Rule,
+    pub message: String,
+}
+
+/// every problem found in a message
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub valid: bool,
+    pub problems: Vec<Problem>,
+}
+
+impl ValidationReport {
+    /// the report as json, for use by other tools
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    fn push<F: Into<String>, M: Into<String>>(&mut self, field: F, rule: Rule, message: M) {
+        let field = field.into();
+        self.problems.push(Problem {
+            section: section(&field),
+            field,
+            rule,
+            message: message.into(),
+        });
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for problem in &self.problems {
+            writeln!(
+                f,
+                "{}: {} ({}, {})",
+                problem.field, problem.message, problem.rule, problem.section
+            )?;
+        }
+        match self.problems.len() {
+            0 => write!(f, "the message is valid"),
+            1 => write!(f, "1 problem"),
+            n => write!(f, "{} problems", n),
+        }
+    }
+}
+
+/// checks a json message, or an encoded one in the transport encoding, reporting every problem
+///
+/// the fields of an encoded message after one that cannot be decoded are unknown and not reported
+/// as missing, neither are the fields of an encrypted body. which reference indicators a message
+/// code allows is not checked, only that the indicator is defined and agrees with the referenced message
+pub fn validate_message(message: &str, encoding: TransportEncoding) -> ValidationReport {
+    let message = message.trim();
+    let mut report = ValidationReport::default();
+
+    if message.starts_with('{') {
+        match serde_json::from_str::<Map<String, Value>>(message) {
+            Ok(fields) => {
+                check_fields(&fields, &mut report);
+                if report.problems.is_empty() {
+                    if let Err(e) = Message::deserialize_from_json(message).map(|m| m.encode()) {
+                        report.push("message", Rule::Syntax, e.to_string());
+                    }
+                }
+            }
+            Err(e) => report.push("message", Rule::Syntax, format!("not a json object: {}", e)),
+        }
+    } else {
+        match WhiteflagBuffer::decode_from_encoding(message, encoding) {
+            Ok(buffer) => check_encoded(&buffer, &mut report),
+            Err(e) => report.push("message", Rule::Syntax, format!("cannot be decoded: {}", e)),
+        }
+    }
+
+    report.valid = report.problems.is_empty();
+    report
+}
+
+/// the syntax of each field as it is decoded, see [`inspect`], then the rules of the decoded values
+fn check_encoded(buffer: &WhiteflagBuffer, report: &mut ValidationReport) {
+    let mut fields = Map::new();
+    for field in inspect(buffer) {
+        match (field.name.as_str(), field.value) {
+            /* an unknown message code, reported with the header */
+            ("body", Err(_)) => {}
+            (name, Err(e)) => report.push(name, Rule::Syntax, e),
+            (name, Ok(value)) => {
+                fields.insert(name.to_string(), value.into());
+            }
+        }
+    }
+    check_values(&fields, report);
+}
+
+/// the syntax of the fields of a json message, then the rules of their values
+fn check_fields(fields: &Map<String, Value>, report: &mut ValidationReport) {
+    let mut strings = Map::new();
+    for (name, value) in fields {
+        match value {
+            Value::String(_) => {
+                strings.insert(name.clone(), value.clone());
+            }
+            _ => report.push(name.as_str(), Rule::Syntax, "expected a string"),
+        }
+    }
+
+    let mut expected: Vec<String> = Vec::new();
+    for definition in header::DEFINITIONS {
+        expected.push(check_syntax(definition, None, fields, report));
+    }
+    expected.extend(check_body(fields, report));
+
+    /* the fields of an unknown message code are unknown too */
+    let code = string(fields, "messageCode");
+    if let Some(section) = body_section(code) {
+        for name in fields.keys().filter(|name| !expected.contains(name)) {
+            report.problems.push(Problem {
+                field: name.clone(),
+                section,
+                rule: Rule::Syntax,
+                message: format!("not a field of message code {}", code),
+            });
+        }
+    }
+    check_values(&strings, report);
+}
+
+/// checks the syntax of the body fields of the message code, returning their names
+fn check_body(fields: &Map<String, Value>, report: &mut ValidationReport) -> Vec<String> {
+    let code = string(fields, "messageCode").chars().next();
+    let definitions = code
+        .map(MessageCodeType::from_code)
+        .and_then(|code| code.definitions().ok())
+        .unwrap_or_default();
+
+    let mut names = Vec::new();
+    for definition in definitions {
+        names.push(check_syntax(definition, None, fields, report));
+    }
+
+    /* 4.3.1.9 Object Request Fields, numbered pairs */
+    let mut pair = 1;
+    while code == Some('Q') {
+        let object_type = format!("objectType{}", pair);
+        let quantity = format!("objectType{}Quant", pair);
+        if !fields.contains_key(&object_type) && !fields.contains_key(&quantity) {
+            break;
+        }
+        names.push(check_syntax(
+            &request::OBJECT_TYPE,
+            Some(object_type),
+            fields,
+            report,
+        ));
+        names.push(check_syntax(
+            &request::OBJECT_TYPE_QUANT,
+            Some(quantity),
+            fields,
+            report,
+        ));
+        pair += 1;
+    }
+    names
+}
+
+/// checks the length and characters of a field, returning its name
+fn check_syntax(
+    definition: &FieldDefinition,
+    name: Option<String>,
+    fields: &Map<String, Value>,
+    report: &mut ValidationReport,
+) -> String {
+    let name = name.unwrap_or_else(|| json_name(definition));
+    match fields.get(&name).and_then(Value::as_str) {
+        Some(value) => {
+            if let Err(e) = validate(definition, value) {
+                let reason = match e {
+                    WhiteflagCLIError::InvalidField { reason, .. } => reason,
+                    e => e.to_string(),
+                };
+                report.push(name.as_str(), Rule::Syntax, reason);
+            }
+        }
+        None if !fields.contains_key(&name) => report.push(name.as_str(), Rule::Syntax, "missing"),
+        None => {}
+    }
+    name
+}
+
+/// the meaning of the values and the reference rules, for the fields with a well formed value
+fn check_values(fields: &Map<String, Value>, report: &mut ValidationReport) {
+    let reported: Vec<String> = report.problems.iter().map(|p| p.field.clone()).collect();
+    for (name, value) in fields {
+        let value = value.as_str().unwrap_or_default();
+        if reported.contains(name) {
+            continue;
+        }
+        if let Some(message) = semantics(name, value) {
+            report.push(name.as_str(), Rule::Semantics, message);
+        }
+    }
+
+    /* 4.2.1.7 Reference Indicator Field */
+    let indicator = string(fields, "referenceIndicator");
+    let referenced = string(fields, "referencedMessage");
+    if report
+        .problems
+        .iter()
+        .any(|p| p.field == "referenceIndicator" || p.field == "referencedMessage")
+        || indicator.is_empty()
+        || referenced.is_empty()
+    {
+        return;
+    }
+    match (indicator, referenced == NO_REFERENCE) {
+        ("0", false) => report.push(
+            "referencedMessage",
+            Rule::Reference,
+            "an original message with reference indicator 0 cannot reference another message",
+        ),
+        (indicator, true) if indicator != "0" => report.push(
+            "referencedMessage",
+            Rule::Reference,
+            format!(
+                "reference indicator {} needs the transaction hash of the referenced message",
+                indicator
+            ),
+        ),
+        _ => {}
+    }
+}
+
+/// why a well formed value has no meaning
+fn semantics(name: &str, value: &str) -> Option<String> {
+    let known_code = |code: &str| MESSAGE_CODES.iter().any(|(c, _)| code == c.to_string());
+    let message = match name {
+        "prefix" if value != "WF" => "expected WF",
+        "version" if value != "1" => "only version 1 is defined",
+        "encryptionIndicator" if !matches!(value, "0" | "1" | "2") => {
+            "expected 0 for no encryption, or encryption method 1 or 2"
+        }
+        "referenceIndicator" if !value.chars().all(|c| c.is_ascii_digit()) => {
+            "reference indicators A to F are reserved, expected 0 to 9"
+        }
+        "messageCode" | "pseudoMessageCode" if !known_code(value) => "unknown message code",
+        "verificationMethod" if !matches!(value, "1" | "2") => {
+            "expected 1 for an internet resource or 2 for a shared token"
+        }
+        "resourceMethod" if value != "1" => "expected 1 for an internet resource",
+        "dateTime" => return date_time(value),
+        "duration" => return duration(value),
+        "objectLatitude" => return within(value, 90.0, "latitude"),
+        "objectLongitude" => return within(value, 180.0, "longitude"),
+        "objectOrientation" if value.parse::<u16>().map_or(true, |v| v > 360) => {
+            "expected at most 360 degrees"
+        }
+        _ => return None,
+    };
+    Some(message.to_string())
+}
+
+/// a date and time like 2013-08-31T04:29:15Z that exists
+fn date_time(value: &str) -> Option<String> {
+    let numbers: Vec<u32> = value
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|n| !n.is_empty())
+        .filter_map(|n| n.parse().ok())
+        .collect();
+    let (year, month, day, hour, minute, second) = match numbers[..] {
+        [year, month, day, hour, minute, second] => (year, month, day, hour, minute, second),
+        _ => return Some("expected a date and time like 2013-08-31T04:29:15Z".to_string()),
+    };
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    };
+    if !(1..=12).contains(&month) || !(1..=days).contains(&day) {
+        Some(format!(
+            "there is no date {:04}-{:02}-{:02}",
+            year, month, day
+        ))
+    } else if hour > 23 || minute > 59 || second > 59 {
+        Some(format!(
+            "there is no time {:02}:{:02}:{:02}",
+            hour, minute, second
+        ))
+    } else {
+        None
+    }
+}
+
+/// a duration like P01D12H00M with hours and minutes that do not overflow
+fn duration(value: &str) -> Option<String> {
+    let hours = value.get(4..6)?.parse::<u32>().ok()?;
+    let minutes = value.get(7..9)?.parse::<u32>().ok()?;
+    (hours > 23 || minutes > 59).then(|| "expected at most 23 hours and 59 minutes".to_string())
+}
+
+/// decimal degrees within the bound
+fn within(value: &str, bound: f64, what: &str) -> Option<String> {
+    match value.parse::<f64>() {
+        Ok(degrees) if degrees.abs() <= bound => None,
+        _ => Some(format!(
+            "expected a {} from -{} to +{} degrees",
+            what, bound, bound
+        )),
+    }
+}
+
+fn string<'a>(fields: &'a Map<String, Value>, name: &str) -> &'a str {
+    fields.get(name).and_then(Value::as_str).unwrap_or_default()
+}
+
+/// the section of the Whiteflag Specification listing the fields of a message code
+fn body_section(code: &str) -> Option<&'static str> {
+    Some(match code {
+        "A" => "4.3.4.1",
+        "K" => "4.3.5.1",
+        "T" => "4.3.6.1",
+        "R" => "4.3.2.1",
+        "F" => "4.3.3.1",
+        "P" | "E" | "D" | "S" | "I" | "M" | "Q" => "4.3.1.1",
+        _ => return None,
+    })
+}
+
+/// the section of the Whiteflag Specification defining a field
+fn section(name: &str) -> &'static str {
+    match name {
+        "prefix" => "4.2.1.2",
+        "version" => "4.2.1.3",
+        "encryptionIndicator" => "4.2.1.4",
+        "duressIndicator" => "4.2.1.5",
+        "messageCode" => "4.2.1.6",
+        "referenceIndicator" => "4.2.1.7",
+        "referencedMessage" => "4.2.1.8",
+        "verificationMethod" | "verificationData" => "4.3.4.1",
+        "cryptoDataType" | "cryptoData" => "4.3.5.1",
+        "text" => "4.3.3.1",
+        "resourceMethod" | "resourceData" => "4.3.2.1",
+        "pseudoMessageCode" => "4.3.6.1",
+        "subjectCode" => "4.3.1.2",
+        "dateTime" => "4.3.1.3",
+        "duration" => "4.3.1.4",
+        "objectType" => "4.3.1.5",
+        "objectLatitude" | "objectLongitude" => "4.3.1.6",
+        "objectSizeDim1" | "objectSizeDim2" => "4.3.1.7",
+        "objectOrientation" => "4.3.1.8",
+        name if name.starts_with("objectType") => "4.3.1.9",
+        _ => "4.1",
+    }
+}
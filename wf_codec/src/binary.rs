@@ -1,3 +1,4 @@
+use alloc::{string::String, vec, vec::Vec};
 use wf_common::constants::BYTE;
 
 /// encodes a binary string into a binary buffer
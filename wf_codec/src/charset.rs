@@ -0,0 +1,43 @@
+//! hand-written scanners for the charsets of the Whiteflag encodings
+//!
+//! these replace searching the values for the charset patterns in [`crate::encoding::charsets`]
+//! with regular expressions, and accept the same values
+
+use crate::encoding::EncodingKind;
+
+/// a digit
+const DIGIT: u8 = b'd';
+/// a sign, `+` or `-`
+const SIGN: u8 = b's';
+
+const DATETIME: &[u8] = b"dddd-dd-ddTdd:dd:ddZ";
+const DURATION: &[u8] = b"PddDddHddM";
+const LAT: &[u8] = b"sdd.ddddd";
+const LONG: &[u8] = b"sddd.ddddd";
+
+/// checks if the charset pattern of the encoding occurs in the value, as the unanchored regular
+/// expressions did
+pub fn is_valid(kind: EncodingKind, value: &str) -> bool {
+    let bytes = value.as_bytes();
+    match kind {
+        EncodingKind::BIN => bytes.iter().any(|b| matches!(b, b'0' | b'1')),
+        EncodingKind::DEC => bytes.iter().any(u8::is_ascii_digit),
+        EncodingKind::HEX => bytes.iter().any(u8::is_ascii_hexdigit),
+        EncodingKind::UTF8 => bytes.iter().any(u8::is_ascii),
+        EncodingKind::DATETIME => contains_template(bytes, DATETIME),
+        EncodingKind::DURATION => contains_template(bytes, DURATION),
+        EncodingKind::LAT => contains_template(bytes, LAT),
+        EncodingKind::LONG => contains_template(bytes, LONG),
+    }
+}
+
+/// searches a value for a fixed length template of [`DIGIT`], [`SIGN`] and literal characters
+fn contains_template(value: &[u8], template: &[u8]) -> bool {
+    value.windows(template.len()).any(|window| {
+        window.iter().zip(template).all(|(v, t)| match *t {
+            DIGIT => v.is_ascii_digit(),
+            SIGN => matches!(v, b'+' | b'-'),
+            literal => *v == literal,
+        })
+    })
+}
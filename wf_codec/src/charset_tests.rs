@@ -0,0 +1,77 @@
+use crate::{charset::is_valid, encoding::EncodingKind};
+
+#[test]
+fn valid_charsets() {
+    assert!(is_valid(EncodingKind::BIN, "0110"));
+    assert!(is_valid(EncodingKind::DEC, "0123456789"));
+    assert!(is_valid(EncodingKind::HEX, "0123456789abcdefABCDEF"));
+    assert!(is_valid(EncodingKind::UTF8, "Whiteflag test message!"));
+    assert!(is_valid(EncodingKind::DATETIME, "2013-08-31T04:29:15Z"));
+    assert!(is_valid(EncodingKind::DURATION, "P00D00H00M"));
+    assert!(is_valid(EncodingKind::LAT, "+30.79658"));
+    assert!(is_valid(EncodingKind::LONG, "-037.82602"));
+}
+
+#[test]
+fn charsets_are_searched_for() {
+    /* a single character of the charset is enough, as it was with the regular expressions */
+    assert!(is_valid(EncodingKind::DEC, "12a"));
+    assert!(is_valid(EncodingKind::HEX, "0x12"));
+    assert!(is_valid(EncodingKind::UTF8, "caf\u{e9}"));
+    assert!(is_valid(EncodingKind::LAT, "+30.796580"));
+
+    assert!(!is_valid(EncodingKind::BIN, "2345"));
+    assert!(!is_valid(EncodingKind::DEC, "abc"));
+    assert!(!is_valid(EncodingKind::HEX, "xyz"));
+    assert!(!is_valid(EncodingKind::UTF8, "\u{e9}\u{e8}"));
+    assert!(!is_valid(EncodingKind::DATETIME, "2013-08-31 04:29:15Z"));
+    assert!(!is_valid(EncodingKind::DURATION, "P00D00H00S"));
+    assert!(!is_valid(EncodingKind::LONG, "-37.826020"));
+    assert!(!is_valid(EncodingKind::HEX, ""));
+}
+
+#[test]
+fn scanners_agree_with_charset_patterns() {
+    use crate::encoding::charsets;
+    use regex::Regex;
+
+    let values = [
+        (EncodingKind::BIN, charsets::BIN, "0120"),
+        (EncodingKind::BIN, charsets::BIN, "2345"),
+        (EncodingKind::DEC, charsets::DEC, "12a"),
+        (EncodingKind::DEC, charsets::DEC, "12A4"),
+        (EncodingKind::DEC, charsets::DEC, "abc"),
+        (EncodingKind::HEX, charsets::HEX, "0x12"),
+        (EncodingKind::HEX, charsets::HEX, "xyz"),
+        (EncodingKind::HEX, charsets::HEX, ""),
+        (EncodingKind::UTF8, charsets::UTF8, "caf\u{e9}"),
+        (EncodingKind::UTF8, charsets::UTF8, "\u{e9}\u{e8}"),
+        (
+            EncodingKind::DATETIME,
+            charsets::DATETIME,
+            "2013-08-31T04:29:15Z",
+        ),
+        (
+            EncodingKind::DATETIME,
+            charsets::DATETIME,
+            "2013-08-31T04:29:15X",
+        ),
+        (
+            EncodingKind::DATETIME,
+            charsets::DATETIME,
+            "x2013-08-31T04:29:15Zx",
+        ),
+        (EncodingKind::DURATION, charsets::DURATION, "P01D23H59M"),
+        (EncodingKind::DURATION, charsets::DURATION, "P01D2HH59M"),
+        (EncodingKind::LAT, charsets::LAT, "-89.99999"),
+        (EncodingKind::LAT, charsets::LAT, "*89.99999"),
+        (EncodingKind::LAT, charsets::LAT, "+30.796580"),
+        (EncodingKind::LONG, charsets::LONG, "+179.99999"),
+        (EncodingKind::LONG, charsets::LONG, "+179,99999"),
+    ];
+
+    for (kind, charset, value) in values {
+        let regex = Regex::new(charset).unwrap();
+        assert_eq!(regex.is_match(value), is_valid(kind, value), "{}", value);
+    }
+}
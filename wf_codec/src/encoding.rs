@@ -1,7 +1,13 @@
 use crate::error::{CodecError, CodecResult};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
 
 use super::{
     binary::{decode_to_binary, encode_from_binary},
+    charset,
     hexadecimal::{decode_to_bdx, encode_from_bdx},
     latlong::encode_latlong,
 };
@@ -65,7 +71,7 @@ impl ConfiguredByteLength {
     }
 }
 
-impl std::ops::Deref for ConfiguredByteLength {
+impl core::ops::Deref for ConfiguredByteLength {
     type Target = ByteLength;
 
     fn deref(&self) -> &Self::Target {
@@ -85,7 +91,7 @@ impl Encoding {
     /// @param field the message field to be encoded
     /// @return a binary buffer with the encoded field
     /// java equivalent: WfMessageCodec.encodeField
-    pub fn encode<T: AsRef<str> + std::fmt::Display>(&self, value: T) -> Vec<u8> {
+    pub fn encode<T: AsRef<str> + core::fmt::Display>(&self, value: T) -> Vec<u8> {
         match &self.kind {
             EncodingKind::UTF8 => value.as_ref().as_bytes().to_vec(),
             EncodingKind::BIN => encode_from_binary(value),
@@ -108,7 +114,7 @@ impl Encoding {
 
         match &self.kind {
             EncodingKind::UTF8 => {
-                return Ok(std::str::from_utf8(buffer)?.to_string());
+                return Ok(core::str::from_utf8(buffer)?.to_string());
             }
             EncodingKind::BIN => {
                 return Ok(decode_to_binary(buffer, bit_length));
@@ -200,9 +206,7 @@ macro_rules! encoding {
                     _ => (),
                 };
 
-                if match self.kind {
-                    $( EncodingKind::$name => rx::$name.is_match(value), )*
-                } == false {
+                if !charset::is_valid(self.kind, value) {
                     return Err(ValidationError::InvalidCharset);
                 }
 
@@ -220,13 +224,6 @@ macro_rules! encoding {
         pub mod charsets {
             $( pub const $name: &'static str = $charset; )*
         }
-
-        pub mod rx {
-            use regex::Regex;
-            lazy_static!{
-                $( pub static ref $name: Regex = Regex::new(super::charsets::$name).unwrap(); )*
-            }
-        }
     };
 }
 
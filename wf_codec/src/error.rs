@@ -1,4 +1,5 @@
-use std::str::Utf8Error;
+use alloc::string::String;
+use core::str::Utf8Error;
 
 #[derive(thiserror::Error, Debug, PartialEq, Eq)]
 pub enum CodecError {
@@ -8,6 +9,10 @@ pub enum CodecError {
     Hexadecimal(),
     #[error("field definition vector should not be empty")]
     EmptyFieldDefinition(),
+    #[error("the buffer ends {remaining} bits into a field of {expected} bits")]
+    EndOfBuffer { expected: usize, remaining: usize },
+    #[error("the message ends with {bits} bits that are not zero padding of its last byte")]
+    InvalidPadding { bits: usize },
     #[error("validation error while converting array of strings into fields: {error:?}")]
     Validation { error: String },
 }
@@ -1,4 +1,8 @@
 use crate::error::HexDecodeError;
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
 use wf_common::constants::QUADBIT;
 
 /// encodes a hexadecimal string into a binary buffer
@@ -1,4 +1,5 @@
 use super::hexadecimal::encode_from_bdx;
+use alloc::vec::Vec;
 use wf_common::{
     common::{crop_bits, remove_all_invalid_hex_characters, shift_right},
     constants::QUADBIT,
@@ -1,7 +1,9 @@
-#[macro_use]
-extern crate lazy_static;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 pub mod binary;
+mod charset;
 pub mod encoding;
 mod error;
 pub mod hexadecimal;
@@ -11,3 +13,6 @@ pub use error::{CodecError, CodecResult};
 
 #[cfg(test)]
 mod validation_tests;
+
+#[cfg(test)]
+mod charset_tests;
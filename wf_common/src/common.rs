@@ -1,10 +1,13 @@
 use super::constants::*;
+use alloc::{string::String, vec, vec::Vec};
 
 /// removes characters from string that are invalid in hexadecimal format
 /// java equivalent: N/A
 pub fn remove_all_invalid_hex_characters<T: AsRef<str>>(data: T) -> String {
-    let re = regex::Regex::new("[-+:.A-Z]").unwrap();
-    re.replace_all(data.as_ref(), "").to_string()
+    data.as_ref()
+        .chars()
+        .filter(|c| !matches!(c, '-' | '+' | ':' | '.' | 'A'..='Z'))
+        .collect()
 }
 
 pub fn remove_hexadecimal_prefix(data: &str) -> &str {
@@ -0,0 +1,69 @@
+use crate::{CryptoError, CryptoResult};
+use aes::cipher::{KeyIvInit, StreamCipher};
+use rand_core::{OsRng, RngCore};
+use wf_buffer::WhiteflagBuffer;
+
+/// length of the initialisation vector for AES-256-CTR in bytes
+pub const IV_LENGTH: usize = 16;
+
+type Aes256Ctr128BE = ctr::Ctr128BE<aes::Aes256>;
+
+/// A cipher that encrypts and decrypts the encrypted part of Whiteflag messages
+///
+/// Implementations receive the initialisation vector for every message explicitly and must return
+/// a buffer with the same bit length as the given buffer, so that messages whose bit length is not a
+/// multiple of 8 survive encryption. Key material is held by the implementation, which allows
+/// ciphers backed by an HSM or another audited library.
+///
+/// Whiteflag Specification 5.2.4 Message Encryption
+pub trait WhiteflagCipher {
+    fn encrypt(&self, plaintext: &WhiteflagBuffer, iv: &[u8]) -> CryptoResult<WhiteflagBuffer>;
+    fn decrypt(&self, ciphertext: &WhiteflagBuffer, iv: &[u8]) -> CryptoResult<WhiteflagBuffer>;
+}
+
+/// the default [`WhiteflagCipher`], AES-256 in counter mode as used by encryption methods 1 and 2
+#[derive(Clone)]
+pub struct Aes256Ctr {
+    key: [u8; 32],
+}
+
+impl Aes256Ctr {
+    pub fn new(key: &[u8]) -> CryptoResult<Self> {
+        let key = key.try_into().map_err(|_| CryptoError::InvalidKeyLength)?;
+        Ok(Aes256Ctr { key })
+    }
+
+    /// AES-256-CTR is symmetric, encrypting and decrypting both apply the key stream
+    fn apply_keystream(
+        &self,
+        buffer: &WhiteflagBuffer,
+        iv: &[u8],
+    ) -> CryptoResult<WhiteflagBuffer> {
+        let mut cipher = Aes256Ctr128BE::new_from_slices(&self.key, iv)
+            .map_err(|_| CryptoError::InvalidIvLength)?;
+
+        let (mut data, bit_length) = buffer.extract_bits_from(0).into();
+        cipher.apply_keystream(&mut data);
+
+        let mut crypted = WhiteflagBuffer::new(data, bit_length);
+        crypted.crop();
+        Ok(crypted)
+    }
+}
+
+impl WhiteflagCipher for Aes256Ctr {
+    fn encrypt(&self, plaintext: &WhiteflagBuffer, iv: &[u8]) -> CryptoResult<WhiteflagBuffer> {
+        self.apply_keystream(plaintext, iv)
+    }
+
+    fn decrypt(&self, ciphertext: &WhiteflagBuffer, iv: &[u8]) -> CryptoResult<WhiteflagBuffer> {
+        self.apply_keystream(ciphertext, iv)
+    }
+}
+
+/// generates a random initialisation vector to encrypt a message with
+pub fn generate_iv() -> [u8; IV_LENGTH] {
+    let mut iv = [0; IV_LENGTH];
+    OsRng.fill_bytes(&mut iv);
+    iv
+}
@@ -1,36 +1,85 @@
-use crate::{ecdh_keypair::WhiteflagECDHKeyPair, wf_encryption_key::WhiteflagEncryptionKey};
-use aes_tools::FennelCipher;
+use crate::{
+    cipher::{Aes256Ctr, WhiteflagCipher},
+    ecdh_keypair::WhiteflagECDHKeyPair,
+    wf_encryption_key::WhiteflagEncryptionKey,
+    CryptoError,
+};
+use wf_buffer::WhiteflagBuffer;
 
 /// Tests Whiteflag encryption and decryption with pre-shared key and known test vector
 #[test]
 fn test_cipher_1() {
     let plaintext = "23000000000088888889111111119999999a22222222aaaaaaab33333333bbbbbbbb0983098309830983118b118b118b118b1993199319931993219b219b219b219b29a329a329a329a331ab31ab31ab31a9b1b9b1b9b1b9b1b9c1c9c1c9c1c9c1c8";
-    let key = WhiteflagEncryptionKey::from_preshared_key(
+    let ciphertext = "6d7658e7d17479677a0de95076989fcd7825b709349b143f2b17644e5cb2c8ded5c7f18d77447cf9dc2115e0c1c81d717b57fadaeedf27bfef8926448ff666d3d9a65168827c94b393974ebbe6b7f0599e184bfd1ace3569117c23ae17c5640f2f2d";
+    let mut key = WhiteflagEncryptionKey::from_preshared_key(
         "32676187ba7badda85ea63a69870a7133909f1999774abb2eed251073616a6e7",
     );
+    key.set_context(&hex::decode("007a0baf6f84f0fa7402ea972686e56d50b707c9b67b108866").unwrap());
+    let iv = hex::decode("40aa85015d24e4601448c1ba8d7bf1aa").unwrap();
 
-    let cipher = key.aes_cipher();
-    let ciphertext = cipher.encrypt(plaintext);
+    let cipher = key.aes_256_ctr_cipher();
+    let encrypted = cipher
+        .encrypt(
+            &WhiteflagBuffer::decode_from_hexadecimal(plaintext).unwrap(),
+            &iv,
+        )
+        .unwrap();
+    assert_eq!(ciphertext, encrypted.as_hex());
 
-    assert_eq!(
-        plaintext,
-        String::from_utf8_lossy(&cipher.decrypt(ciphertext))
-    );
+    let decrypted = cipher.decrypt(&encrypted, &iv).unwrap();
+    assert_eq!(plaintext, decrypted.as_hex());
 }
 
 /// Tests full Whiteflag encryption scheme and decryption with negotiated key
 #[test]
 fn test_cipher_2() {
-    let plaintext1 = "aa1bb2cc3dd4ee5ff6007008009000";
+    let plaintext1 =
+        WhiteflagBuffer::decode_from_hexadecimal("aa1bb2cc3dd4ee5ff6007008009000").unwrap();
+    let iv = crate::generate_iv();
 
     let keypair1 = WhiteflagECDHKeyPair::default();
     let keypair2 = WhiteflagECDHKeyPair::default();
 
     let cipher1 = keypair1.create_aes_cipher(keypair2.as_ref());
-    let ciphertext = cipher1.encrypt(plaintext1);
+    let ciphertext = cipher1.encrypt(&plaintext1, &iv).unwrap();
 
     let cipher2 = keypair2.create_aes_cipher(keypair1.as_ref());
-    let plaintext2 = cipher2.decrypt(ciphertext);
+    let plaintext2 = cipher2.decrypt(&ciphertext, &iv).unwrap();
+
+    assert_eq!(plaintext1.as_hex(), plaintext2.as_hex());
+}
+
+#[test]
+fn cipher_keeps_bit_length() {
+    let cipher = Aes256Ctr::new(&[7; 32]).unwrap();
+    let iv = crate::generate_iv();
+    let plaintext = WhiteflagBuffer::new(vec![0xab, 0xcd, 0xe0], 19);
+
+    let ciphertext = cipher.encrypt(&plaintext, &iv).unwrap();
+    assert_eq!(19, ciphertext.bit_length());
+
+    let last_byte: &[u8] = ciphertext.as_ref();
+    assert_eq!(
+        0,
+        last_byte[2] & 0b0001_1111,
+        "unused bits should be cleared"
+    );
+
+    let decrypted = cipher.decrypt(&ciphertext, &iv).unwrap();
+    assert_eq!(19, decrypted.bit_length());
+    assert_eq!(plaintext.as_hex(), decrypted.as_hex());
+}
+
+#[test]
+fn cipher_rejects_invalid_key_and_iv() {
+    assert!(matches!(
+        Aes256Ctr::new(&[0; 16]),
+        Err(CryptoError::InvalidKeyLength)
+    ));
 
-    assert_eq!(plaintext1, String::from_utf8_lossy(&plaintext2));
+    let cipher = Aes256Ctr::new(&[0; 32]).unwrap();
+    assert!(matches!(
+        cipher.encrypt(&WhiteflagBuffer::from(vec![1, 2, 3]), &[0; 8]),
+        Err(CryptoError::InvalidIvLength)
+    ));
 }
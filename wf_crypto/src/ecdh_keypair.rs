@@ -1,5 +1,5 @@
-use aes_tools::AESCipher;
-use dh_tools::{get_session_public_key, get_session_secret, get_shared_secret};
+use super::cipher::Aes256Ctr;
+use rand_core::OsRng;
 use x25519_dalek::{PublicKey, SharedSecret, StaticSecret};
 
 /// Whiteflag ECDH Key Pair class
@@ -25,13 +25,13 @@ impl AsRef<PublicKey> for WhiteflagECDHKeyPair {
 impl WhiteflagECDHKeyPair {
     /// Creates a new random ECDH key with the curve specified for Whiteflag key negotiation
     pub fn new() -> Self {
-        let secret: StaticSecret = get_session_secret();
+        let secret = StaticSecret::random_from_rng(OsRng);
         Self::from_secret(secret)
     }
 
     /// Creates an ECDH key pair from an existing private key with the curve specified for Whiteflag key negotiation
     pub fn from_secret(secret: StaticSecret) -> Self {
-        let public_key = get_session_public_key(&secret);
+        let public_key = PublicKey::from(&secret);
         WhiteflagECDHKeyPair {
             session_secret: secret,
             public_key,
@@ -45,11 +45,12 @@ impl WhiteflagECDHKeyPair {
 
     /// Calculates the negotiated shared key with an originator
     pub fn negotiate_as_shared_secret(&self, other: &PublicKey) -> SharedSecret {
-        get_shared_secret(self.session_secret.clone(), other)
+        self.session_secret.diffie_hellman(other)
     }
 
-    pub fn create_aes_cipher(&self, public_key: &PublicKey) -> AESCipher {
-        AESCipher::new_from_shared_secret(self.negotiate_as_shared_secret(public_key).as_bytes())
+    pub fn create_aes_cipher(&self, public_key: &PublicKey) -> Aes256Ctr {
+        Aes256Ctr::new(self.negotiate_as_shared_secret(public_key).as_bytes())
+            .expect("shared secret is 32 bytes")
     }
 }
 
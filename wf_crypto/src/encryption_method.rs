@@ -42,7 +42,7 @@ impl std::str::FromStr for WhiteflagEncryptionMethod {
     type Err = CryptoError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let n = s.parse::<usize>().unwrap();
+        let n = s.parse::<usize>().map_err(|_| CryptoError::InvalidMethod)?;
         Self::from_number(n)
     }
 }
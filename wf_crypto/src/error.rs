@@ -10,6 +10,10 @@ pub enum CryptoError {
     HkdfOutput(hkdf::InvalidLength),
     #[error("invalid crypto method")]
     InvalidMethod,
+    #[error("invalid encryption key length")]
+    InvalidKeyLength,
+    #[error("invalid initialisation vector length")]
+    InvalidIvLength,
     #[error(transparent)]
     Other(#[from] Box<dyn std::error::Error>),
 }
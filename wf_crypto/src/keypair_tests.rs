@@ -1,5 +1,10 @@
 use super::ecdh_keypair::WhiteflagECDHKeyPair;
-use dh_tools::{get_session_public_key, get_session_secret, get_shared_secret};
+use rand_core::OsRng;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+fn get_session_secret() -> StaticSecret {
+    StaticSecret::random_from_rng(OsRng)
+}
 
 fn assert_array_eq<T: PartialEq + std::fmt::Debug>(l: &[T], r: &[T], msg: Option<&str>) {
     let success = l.iter().eq(r.iter());
@@ -13,7 +18,7 @@ fn assert_array_eq<T: PartialEq + std::fmt::Debug>(l: &[T], r: &[T], msg: Option
 #[test]
 fn test_generate_keypair_from_key() {
     let static_secret = get_session_secret();
-    let public_key = get_session_public_key(&static_secret);
+    let public_key = PublicKey::from(&static_secret);
     let keypair = WhiteflagECDHKeyPair::from_secret(static_secret);
     assert_eq!(public_key.as_bytes(), keypair.as_ref().as_bytes());
 }
@@ -23,8 +28,8 @@ fn test_negotiate_key() {
     let static_secret = get_session_secret();
     let static_secret_two = get_session_secret();
 
-    let public_key_two = get_session_public_key(&static_secret_two);
-    let shared_secret = get_shared_secret(static_secret.clone(), &public_key_two.clone());
+    let public_key_two = PublicKey::from(&static_secret_two);
+    let shared_secret = static_secret.diffie_hellman(&public_key_two);
 
     let pair = WhiteflagECDHKeyPair::from_secret(static_secret);
     let result = pair.negotiate(WhiteflagECDHKeyPair::from_secret(static_secret_two).as_ref());
@@ -37,7 +42,7 @@ fn test_get_keypair_with_secret() {
     let static_secret = get_session_secret();
     let pair = WhiteflagECDHKeyPair::from_secret(static_secret.clone());
     assert_eq!(
-        get_session_public_key(&static_secret).to_bytes(),
+        PublicKey::from(&static_secret).to_bytes(),
         pair.as_ref().to_bytes()
     )
 }
@@ -7,6 +7,7 @@ mod crypto_util_tests;
 #[cfg(test)]
 mod cipher_tests;
 
+pub mod cipher;
 #[allow(dead_code)]
 mod crypto_util;
 pub mod ecdh_keypair;
@@ -15,6 +16,7 @@ pub mod encryption_method;
 mod error;
 pub mod wf_encryption_key;
 
+pub use cipher::{generate_iv, Aes256Ctr, WhiteflagCipher};
 pub use error::{CryptoError, CryptoResult};
 
 pub fn hkdf(ikm: &[u8], salt: &[u8], info: &[u8], length: usize) -> CryptoResult<Vec<u8>> {
@@ -1,6 +1,6 @@
+use super::cipher::Aes256Ctr;
 use super::ecdh_keypair::WhiteflagECDHKeyPair;
 use super::encryption_method::WhiteflagEncryptionMethod;
-use aes_tools::{AESCipher, AES256CTR};
 use x25519_dalek::PublicKey;
 
 /// This class represents a Whiteflag encryption key. Instances of this
@@ -48,12 +48,9 @@ impl WhiteflagEncryptionKey {
         init
     }
 
-    pub fn aes_cipher(&self) -> AESCipher {
-        AESCipher::new_from_shared_secret(&self.fixed_raw_secret())
-    }
-
-    pub fn aes_256_ctr_cipher<'a>(&'a self, iv: &'a [u8]) -> AES256CTR {
-        AES256CTR::new(&self.secret_key, Some(iv))
+    /// the default cipher for encryption methods 1 and 2 with this key
+    pub fn aes_256_ctr_cipher(&self) -> Aes256Ctr {
+        Aes256Ctr::new(&self.fixed_raw_secret()).expect("key is 32 bytes")
     }
 }
 
@@ -0,0 +1,136 @@
+#ifndef WHITEFLAG_H
+#define WHITEFLAG_H
+
+/* Warning, this file is autogenerated by cbindgen. Don't modify this manually. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * status returned by every fallible function, `WF_STATUS_OK` on success
+ *
+ * the reason for any other status is available from `wf_last_error_message`
+ */
+typedef enum WfStatus {
+  WF_STATUS_OK = 0,
+  /**
+   * a required pointer argument was null
+   */
+  WF_STATUS_NULL_POINTER = 1,
+  /**
+   * a string argument was not valid UTF-8
+   */
+  WF_STATUS_INVALID_UTF8 = 2,
+  /**
+   * the json could not be parsed or has invalid field values
+   */
+  WF_STATUS_INVALID_JSON = 3,
+  /**
+   * the hexadecimal string is not a valid encoded message
+   */
+  WF_STATUS_INVALID_HEX = 4,
+  /**
+   * the library panicked, this is a bug
+   */
+  WF_STATUS_PANIC = 5,
+  /**
+   * a returned string would contain a nul byte
+   */
+  WF_STATUS_INTERIOR_NUL = 6,
+} WfStatus;
+
+/**
+ * opaque handle to a decoded or compiled message
+ */
+typedef struct WfMessage WfMessage;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * decode hexadecimal encoded whiteflag message into a json message
+ *
+ * # Safety
+ * `hex` must be a nul terminated string and `out` a valid pointer
+ */
+enum WfStatus wf_decode_from_hex(const char *hex, char **out);
+
+/**
+ * encode whiteflag json message into a hexadecimal string
+ *
+ * # Safety
+ * `json` must be a nul terminated string and `out` a valid pointer
+ */
+enum WfStatus wf_encode_from_json(const char *json, char **out);
+
+/**
+ * the message of the last error on this thread, or null if the last call succeeded
+ *
+ * the string is owned by the library and valid until the next call on this thread
+ */
+const char *wf_last_error_message(void);
+
+/**
+ * the message code, e.g. 'A' for authentication messages
+ *
+ * # Safety
+ * `message` must be a handle returned by this library and `out` a valid pointer
+ */
+enum WfStatus wf_message_code(const struct WfMessage *message, char *out);
+
+/**
+ * releases a message handle, null is ignored
+ *
+ * # Safety
+ * `message` must be null or a handle returned by this library that was not freed before
+ */
+void wf_message_free(struct WfMessage *message);
+
+/**
+ * decodes a hexadecimal encoded message
+ *
+ * # Safety
+ * `hex` must be a nul terminated string and `out` a valid pointer to write the handle to
+ */
+enum WfStatus wf_message_from_hex(const char *hex, struct WfMessage **out);
+
+/**
+ * compiles a message from its json representation
+ *
+ * # Safety
+ * `json` must be a nul terminated string and `out` a valid pointer to write the handle to
+ */
+enum WfStatus wf_message_from_json(const char *json, struct WfMessage **out);
+
+/**
+ * encodes the message into a hexadecimal string
+ *
+ * # Safety
+ * `message` must be a handle returned by this library and `out` a valid pointer
+ */
+enum WfStatus wf_message_to_hex(const struct WfMessage *message, char **out);
+
+/**
+ * the json representation of the message
+ *
+ * # Safety
+ * `message` must be a handle returned by this library and `out` a valid pointer
+ */
+enum WfStatus wf_message_to_json(const struct WfMessage *message, char **out);
+
+/**
+ * releases a string returned by this library, null is ignored
+ *
+ * # Safety
+ * `value` must be null or a string returned by this library that was not freed before
+ */
+void wf_string_free(char *value);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* WHITEFLAG_H */
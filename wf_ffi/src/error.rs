@@ -0,0 +1,89 @@
+use std::{
+    cell::RefCell,
+    error::Error,
+    ffi::{c_char, CString},
+    panic::{catch_unwind, UnwindSafe},
+    ptr,
+};
+
+/// status returned by every fallible function, `WF_STATUS_OK` on success
+///
+/// the reason for any other status is available from `wf_last_error_message`
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WfStatus {
+    Ok = 0,
+    /// a required pointer argument was null
+    NullPointer = 1,
+    /// a string argument was not valid UTF-8
+    InvalidUtf8 = 2,
+    /// the json could not be parsed or has invalid field values
+    InvalidJson = 3,
+    /// the hexadecimal string is not a valid encoded message
+    InvalidHex = 4,
+    /// the library panicked, this is a bug
+    Panic = 5,
+    /// a returned string would contain a nul byte
+    InteriorNul = 6,
+}
+
+pub(crate) struct FfiError {
+    pub status: WfStatus,
+    pub message: String,
+}
+
+impl FfiError {
+    pub fn new<E: Error>(status: WfStatus, error: E) -> Self {
+        /* `WhiteflagError` only names the failing crate, the sources say what went wrong */
+        let mut message = error.to_string();
+        let mut source = error.source();
+        while let Some(error) = source {
+            message.push_str(": ");
+            message.push_str(&error.to_string());
+            source = error.source();
+        }
+
+        FfiError { status, message }
+    }
+
+    pub fn null_pointer(argument: &str) -> Self {
+        FfiError {
+            status: WfStatus::NullPointer,
+            message: format!("{} must not be null", argument),
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// runs `call`, recording its error for `wf_last_error_message` and never unwinding into C
+pub(crate) fn ffi_call<F: FnOnce() -> Result<(), FfiError> + UnwindSafe>(call: F) -> WfStatus {
+    let (status, message) = match catch_unwind(call) {
+        Ok(Ok(())) => (WfStatus::Ok, None),
+        Ok(Err(error)) => (error.status, Some(error.message)),
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            (WfStatus::Panic, Some(message))
+        }
+    };
+
+    LAST_ERROR.with(|last| {
+        *last.borrow_mut() = message.map(|m| CString::new(m.replace('\0', "")).unwrap_or_default())
+    });
+
+    status
+}
+
+/// the message of the last error on this thread, or null if the last call succeeded
+///
+/// the string is owned by the library and valid until the next call on this thread
+#[no_mangle]
+pub extern "C" fn wf_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |m| m.as_ptr()))
+}
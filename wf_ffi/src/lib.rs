@@ -0,0 +1,199 @@
+//! C ABI for encoding and decoding Whiteflag messages
+//!
+//! the header is generated with `cbindgen --config cbindgen.toml --output include/whiteflag.h`
+//!
+//! strings returned through `char **` out parameters are owned by the caller and must be
+//! released with `wf_string_free`, messages with `wf_message_free`
+
+mod error;
+
+#[cfg(test)]
+mod string_tests;
+
+use error::{ffi_call, FfiError};
+use fennel_whiteflag::Message;
+use std::ffi::{c_char, CStr, CString};
+
+pub use error::{wf_last_error_message, WfStatus};
+
+/// opaque handle to a decoded or compiled message
+pub struct WfMessage(Message);
+
+unsafe fn read_str<'a>(value: *const c_char, argument: &str) -> Result<&'a str, FfiError> {
+    if value.is_null() {
+        return Err(FfiError::null_pointer(argument));
+    }
+
+    CStr::from_ptr(value)
+        .to_str()
+        .map_err(|e| FfiError::new(WfStatus::InvalidUtf8, e))
+}
+
+unsafe fn write_out<T>(out: *mut *mut T, value: T) -> Result<(), FfiError> {
+    if out.is_null() {
+        return Err(FfiError::null_pointer("out"));
+    }
+
+    *out = Box::into_raw(Box::new(value));
+    Ok(())
+}
+
+unsafe fn write_string(out: *mut *mut c_char, value: String) -> Result<(), FfiError> {
+    if out.is_null() {
+        return Err(FfiError::null_pointer("out"));
+    }
+
+    /* json and hex never contain nul bytes, anything else is not silently cut short */
+    let value = CString::new(value).map_err(|e| FfiError::new(WfStatus::InteriorNul, e))?;
+    *out = value.into_raw();
+    Ok(())
+}
+
+unsafe fn read_message<'a>(message: *const WfMessage) -> Result<&'a Message, FfiError> {
+    message
+        .as_ref()
+        .map(|m| &m.0)
+        .ok_or_else(|| FfiError::null_pointer("message"))
+}
+
+/// compiles a message from its json representation
+///
+/// # Safety
+/// `json` must be a nul terminated string and `out` a valid pointer to write the handle to
+#[no_mangle]
+pub unsafe extern "C" fn wf_message_from_json(
+    json: *const c_char,
+    out: *mut *mut WfMessage,
+) -> WfStatus {
+    ffi_call(|| {
+        let json = read_str(json, "json")?;
+        let message = Message::deserialize_from_json(json)
+            .map_err(|e| FfiError::new(WfStatus::InvalidJson, e))?;
+        write_out(out, WfMessage(message))
+    })
+}
+
+/// decodes a hexadecimal encoded message
+///
+/// # Safety
+/// `hex` must be a nul terminated string and `out` a valid pointer to write the handle to
+#[no_mangle]
+pub unsafe extern "C" fn wf_message_from_hex(
+    hex: *const c_char,
+    out: *mut *mut WfMessage,
+) -> WfStatus {
+    ffi_call(|| {
+        let hex = read_str(hex, "hex")?;
+        let message = Message::decode_from_hexadecimal(hex)
+            .map_err(|e| FfiError::new(WfStatus::InvalidHex, e))?;
+        write_out(out, WfMessage(message))
+    })
+}
+
+/// encodes the message into a hexadecimal string
+///
+/// # Safety
+/// `message` must be a handle returned by this library and `out` a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn wf_message_to_hex(
+    message: *const WfMessage,
+    out: *mut *mut c_char,
+) -> WfStatus {
+    ffi_call(|| {
+        let message = read_message(message)?;
+        write_string(out, message.encode_as_hex())
+    })
+}
+
+/// the json representation of the message
+///
+/// # Safety
+/// `message` must be a handle returned by this library and `out` a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn wf_message_to_json(
+    message: *const WfMessage,
+    out: *mut *mut c_char,
+) -> WfStatus {
+    ffi_call(|| {
+        let message = read_message(message)?;
+        let json =
+            serde_json::to_string(message).map_err(|e| FfiError::new(WfStatus::InvalidJson, e))?;
+        write_string(out, json)
+    })
+}
+
+/// the message code, e.g. 'A' for authentication messages
+///
+/// # Safety
+/// `message` must be a handle returned by this library and `out` a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn wf_message_code(message: *const WfMessage, out: *mut c_char) -> WfStatus {
+    ffi_call(|| {
+        let message = read_message(message)?;
+        if out.is_null() {
+            return Err(FfiError::null_pointer("out"));
+        }
+
+        let code = message
+            .get_fields()
+            .into_iter()
+            .find(|f| f.get_name() == "MessageCode")
+            .and_then(|f| f.get().bytes().next())
+            .unwrap_or(0);
+        *out = code as c_char;
+        Ok(())
+    })
+}
+
+/// encode whiteflag json message into a hexadecimal string
+///
+/// # Safety
+/// `json` must be a nul terminated string and `out` a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn wf_encode_from_json(
+    json: *const c_char,
+    out: *mut *mut c_char,
+) -> WfStatus {
+    ffi_call(|| {
+        let json = read_str(json, "json")?;
+        let hex = fennel_whiteflag::encode_from_json(json)
+            .map_err(|e| FfiError::new(WfStatus::InvalidJson, e))?;
+        write_string(out, hex)
+    })
+}
+
+/// decode hexadecimal encoded whiteflag message into a json message
+///
+/// # Safety
+/// `hex` must be a nul terminated string and `out` a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn wf_decode_from_hex(hex: *const c_char, out: *mut *mut c_char) -> WfStatus {
+    ffi_call(|| {
+        let hex = read_str(hex, "hex")?;
+        let json = fennel_whiteflag::decode_from_hex(hex)
+            .map_err(|e| FfiError::new(WfStatus::InvalidHex, e))?;
+        write_string(out, json)
+    })
+}
+
+/// releases a message handle, null is ignored
+///
+/// # Safety
+/// `message` must be null or a handle returned by this library that was not freed before
+#[no_mangle]
+pub unsafe extern "C" fn wf_message_free(message: *mut WfMessage) {
+    if !message.is_null() {
+        drop(Box::from_raw(message));
+    }
+}
+
+/// releases a string returned by this library, null is ignored
+///
+/// # Safety
+/// `value` must be null or a string returned by this library that was not freed before
+#[no_mangle]
+pub unsafe extern "C" fn wf_string_free(value: *mut c_char) {
+    if !value.is_null() {
+        drop(CString::from_raw(value));
+    }
+}
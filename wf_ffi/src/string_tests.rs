@@ -0,0 +1,29 @@
+use super::{wf_last_error_message, wf_string_free, write_string, WfStatus};
+use crate::error::ffi_call;
+use std::{
+    ffi::{c_char, CStr},
+    ptr,
+};
+
+#[test]
+fn strings_are_written() {
+    let mut out: *mut c_char = ptr::null_mut();
+    let out_ptr: *mut *mut c_char = &mut out;
+    let status = ffi_call(|| unsafe { write_string(out_ptr, "5746".to_string()) });
+
+    assert_eq!(WfStatus::Ok, status);
+    assert_eq!("5746", unsafe { CStr::from_ptr(out) }.to_str().unwrap());
+    unsafe { wf_string_free(out) };
+}
+
+#[test]
+fn nul_bytes_are_an_error() {
+    let mut out: *mut c_char = ptr::null_mut();
+    let out_ptr: *mut *mut c_char = &mut out;
+    let status = ffi_call(|| unsafe { write_string(out_ptr, "white\0flag".to_string()) });
+
+    assert_eq!(WfStatus::InteriorNul, status);
+    assert!(out.is_null());
+    let message = unsafe { CStr::from_ptr(wf_last_error_message()) };
+    assert!(message.to_str().unwrap().contains("nul byte"));
+}
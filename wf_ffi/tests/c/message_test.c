@@ -0,0 +1,99 @@
+#include <stdio.h>
+#include <string.h>
+
+#include "whiteflag.h"
+
+static const char *AUTHENTICATION_JSON =
+    "{\"prefix\":\"WF\",\"version\":\"1\",\"encryptionIndicator\":\"0\",\"duressIndicator\":\"0\","
+    "\"messageCode\":\"A\",\"referenceIndicator\":\"0\","
+    "\"referencedMessage\":\"0000000000000000000000000000000000000000000000000000000000000000\","
+    "\"verificationMethod\":\"1\",\"verificationData\":\"https://organisation.int/whiteflag\"}";
+
+static const char *AUTHENTICATION_HEX =
+    "5746313020800000000000000000000000000000000000000000000000000000000000000000b43a3a38399d1797"
+    "b7b933b0b734b9b0ba34b7b71734b73a17bbb434ba32b33630b380";
+
+static int failures = 0;
+
+#define CHECK(condition)                                                \
+    do {                                                                \
+        if (!(condition)) {                                             \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__,      \
+                    __LINE__, #condition);                              \
+            failures++;                                                 \
+        }                                                               \
+    } while (0)
+
+static void encode_and_decode(void) {
+    char *hex = NULL;
+    CHECK(wf_encode_from_json(AUTHENTICATION_JSON, &hex) == WF_STATUS_OK);
+    CHECK(hex != NULL && strcmp(hex, AUTHENTICATION_HEX) == 0);
+    CHECK(wf_last_error_message() == NULL);
+
+    char *json = NULL;
+    CHECK(wf_decode_from_hex(hex, &json) == WF_STATUS_OK);
+    CHECK(json != NULL && strstr(json, "\"verificationData\":\"https://organisation.int/whiteflag\"") != NULL);
+
+    wf_string_free(hex);
+    wf_string_free(json);
+}
+
+static void message_handle(void) {
+    WfMessage *message = NULL;
+    CHECK(wf_message_from_hex(AUTHENTICATION_HEX, &message) == WF_STATUS_OK);
+    CHECK(message != NULL);
+
+    char code = 0;
+    CHECK(wf_message_code(message, &code) == WF_STATUS_OK);
+    CHECK(code == 'A');
+
+    char *hex = NULL;
+    CHECK(wf_message_to_hex(message, &hex) == WF_STATUS_OK);
+    CHECK(hex != NULL && strcmp(hex, AUTHENTICATION_HEX) == 0);
+
+    char *json = NULL;
+    CHECK(wf_message_to_json(message, &json) == WF_STATUS_OK);
+
+    WfMessage *compiled = NULL;
+    CHECK(wf_message_from_json(json, &compiled) == WF_STATUS_OK);
+
+    char *compiled_hex = NULL;
+    CHECK(wf_message_to_hex(compiled, &compiled_hex) == WF_STATUS_OK);
+    CHECK(compiled_hex != NULL && strcmp(compiled_hex, AUTHENTICATION_HEX) == 0);
+
+    wf_string_free(hex);
+    wf_string_free(json);
+    wf_string_free(compiled_hex);
+    wf_message_free(message);
+    wf_message_free(compiled);
+}
+
+static void errors(void) {
+    char *out = NULL;
+    WfMessage *message = NULL;
+
+    CHECK(wf_encode_from_json(NULL, &out) == WF_STATUS_NULL_POINTER);
+    CHECK(wf_last_error_message() != NULL);
+    CHECK(wf_encode_from_json("{\"colour\":\"white\"}", &out) == WF_STATUS_INVALID_JSON);
+    CHECK(strstr(wf_last_error_message(), "colour") != NULL);
+    CHECK(wf_decode_from_hex("zz", &out) == WF_STATUS_INVALID_HEX);
+    CHECK(wf_message_from_hex("57", &message) == WF_STATUS_INVALID_HEX);
+    CHECK(wf_message_from_json("\xff", &message) == WF_STATUS_INVALID_UTF8);
+    CHECK(wf_message_to_hex(NULL, &out) == WF_STATUS_NULL_POINTER);
+    CHECK(out == NULL && message == NULL);
+
+    wf_message_free(NULL);
+    wf_string_free(NULL);
+}
+
+int main(void) {
+    encode_and_decode();
+    message_handle();
+    errors();
+
+    if (failures > 0) {
+        fprintf(stderr, "%d checks failed\n", failures);
+        return 1;
+    }
+    return 0;
+}
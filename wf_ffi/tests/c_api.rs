@@ -0,0 +1,58 @@
+use std::{env, fs, path::PathBuf, process::Command};
+
+const MANIFEST_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+/// the directory cargo puts `libwf_ffi.so` in, next to the `deps` directory of this test binary
+fn library_dir() -> PathBuf {
+    let exe = env::current_exe().unwrap();
+    exe.parent().unwrap().parent().unwrap().to_path_buf()
+}
+
+#[test]
+fn c_program_uses_the_library() {
+    let library_dir = library_dir();
+    let program = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("wf_ffi_message_test");
+
+    let compiled = Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(PathBuf::from(MANIFEST_DIR).join("include"))
+        .arg(PathBuf::from(MANIFEST_DIR).join("tests/c/message_test.c"))
+        .arg("-o")
+        .arg(&program)
+        .arg("-L")
+        .arg(&library_dir)
+        .arg("-lwf_ffi")
+        .arg(format!("-Wl,-rpath,{}", library_dir.display()))
+        .status()
+        .expect("a C compiler is needed to test the C API");
+    assert!(compiled.success(), "compiling the C test program failed");
+
+    let output = Command::new(&program).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn header_is_generated_by_cbindgen() {
+    let config =
+        cbindgen::Config::from_file(PathBuf::from(MANIFEST_DIR).join("cbindgen.toml")).unwrap();
+    let mut generated = Vec::new();
+    cbindgen::Builder::new()
+        .with_crate(MANIFEST_DIR)
+        .with_config(config)
+        .generate()
+        .expect("cbindgen could not generate the header")
+        .write(&mut generated);
+
+    let header =
+        fs::read_to_string(PathBuf::from(MANIFEST_DIR).join("include/whiteflag.h")).unwrap();
+    assert!(
+        header == String::from_utf8(generated).unwrap(),
+        "include/whiteflag.h is out of date, regenerate it with `cbindgen --config cbindgen.toml --output include/whiteflag.h`"
+    );
+}
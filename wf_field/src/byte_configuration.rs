@@ -1,4 +1,5 @@
 use crate::codec_positions::CodecPositions;
+use alloc::string::String;
 use wf_codec::{
     encoding::{ByteLength, Encoding},
     CodecResult,
@@ -1,4 +1,5 @@
 use thiserror::Error;
+use wf_codec::CodecError;
 
 #[derive(Error, Debug)]
 pub enum Error {
@@ -6,10 +7,14 @@ pub enum Error {
     NumberNotSupported,
     #[error("no definition fields for undefined message type")]
     UndefinedMessageType,
+    #[cfg(feature = "json")]
     #[error("issue serializing struct")]
     SerdeJsonError(#[from] serde_json::error::Error),
     #[error("message code does not exist")]
     InvalidMessageCode,
+    #[error("wf_codec::CodecError")]
+    Codec(#[from] CodecError),
+    #[cfg(feature = "std")]
     #[error(transparent)]
     Other(#[from] Box<dyn std::error::Error>),
 }
@@ -1,3 +1,7 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
 use wf_codec::CodecError;
 
 use super::field_definition::FieldDefinition;
@@ -2,6 +2,11 @@ use crate::{
     byte_configuration::ByteConfiguration, codec_positions::CodecPositions,
     definitions::WhiteflagFields, Field,
 };
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
 use wf_codec::{encoding::Encoding, CodecError};
 use wf_validation::{Validation, ValidationError};
 
@@ -11,7 +16,7 @@ pub struct FieldDefinition {
     pub positions: CodecPositions,
 }
 
-impl std::ops::Deref for FieldDefinition {
+impl core::ops::Deref for FieldDefinition {
     type Target = CodecPositions;
 
     fn deref(&self) -> &Self::Target {
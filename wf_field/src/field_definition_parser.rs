@@ -1,3 +1,4 @@
+use alloc::{string::String, vec::Vec};
 use wf_codec::CodecError;
 
 use crate::{definitions, Field, FieldDefinition};
@@ -9,6 +10,17 @@ pub trait FieldDefinitionParser {
     fn remaining(&self) -> usize;
 }
 
+/// lets a parser be used by reference, to inspect its state after parsing
+impl<T: FieldDefinitionParser + ?Sized> FieldDefinitionParser for &mut T {
+    fn parse(&mut self, definition: &FieldDefinition) -> Result<String, CodecError> {
+        (**self).parse(definition)
+    }
+
+    fn remaining(&self) -> usize {
+        (**self).remaining()
+    }
+}
+
 pub trait FieldDefinitionParserBase {
     /// parse multiple FieldDefinitions and extract its assoicated values and converts it into Fields
     fn parse_fields(&mut self, field_defs: Vec<FieldDefinition>) -> Result<Vec<Field>, CodecError>;
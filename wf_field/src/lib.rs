@@ -1,3 +1,9 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
+
 #[cfg(test)]
 mod codec_tests;
 
@@ -29,13 +35,13 @@ pub use {
     field_definition::FieldDefinition,
     field_definition_parser::{FieldDefinitionParser, FieldDefinitionParserBase},
     message_body_types::MessageBodyType,
-    message_header::{Header, MessageHeaderOrder},
+    message_header::{Header, MessageHeaderOrder, DURESS, NO_DURESS},
     request::create_request_fields,
     types::MessageCodeType,
 };
 
-pub trait FieldValue: AsRef<str> + Into<String> + std::fmt::Debug {}
-impl<T> FieldValue for T where T: AsRef<str> + Into<String> + std::fmt::Debug {}
+pub trait FieldValue: AsRef<str> + Into<String> + core::fmt::Debug {}
+impl<T> FieldValue for T where T: AsRef<str> + Into<String> + core::fmt::Debug {}
 
 impl From<&Field> for Vec<u8> {
     fn from(field: &Field) -> Self {
@@ -1,4 +1,5 @@
 use crate::Header;
+use alloc::string::{String, ToString};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -1,4 +1,5 @@
 use crate::Header;
+use alloc::string::{String, ToString};
 use serde::{Deserialize, Serialize};
 
 /// 4.3.3.1 Free Text Message Fields
@@ -1,3 +1,4 @@
+#[cfg(feature = "json")]
 use crate::error::Error;
 
 mod authentication;
@@ -23,6 +24,7 @@ pub enum MessageBodyType {
     //REQUEST,
 }
 
+#[cfg(feature = "json")]
 impl MessageBodyType {
     pub fn to_string(&self) -> Result<String, Error> {
         Ok(match &self {
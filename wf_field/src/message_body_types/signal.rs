@@ -1,4 +1,5 @@
 use crate::Header;
+use alloc::string::{String, ToString};
 use serde::{Deserialize, Serialize};
 
 /// 4.3.1.1 Signs & Signals Message Fields
@@ -10,6 +11,7 @@ pub struct Signal {
     /// Indicates the sign/signal type with the value defined in Subject Code Field
     subject_code: String,
     /// Indicates when the sign/signal is valid, using an ISO 8601/ RFC 3339 timestamp
+    #[serde(rename = "dateTime", alias = "datetime")]
     datetime: String,
     /// Indicates how long the sign/signal will be valid, using the ISO 8601 format
     duration: String,
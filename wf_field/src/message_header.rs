@@ -1,11 +1,16 @@
 use crate::{
-    message_body_types::{Authentication, FreeText, MessageBodyType, Resource, Signal},
+    message_body_types::{Authentication, Crypto, FreeText, MessageBodyType, Resource, Signal},
     Error, Field, MessageCodeType,
 };
+use alloc::string::{String, ToString};
 use serde::{Deserialize, Serialize};
 
 const EMPTY_MESSAGE: &str = "0000000000000000000000000000000000000000000000000000000000000000";
 
+/// 4.2.1.5 Duress Indicator Field
+pub const DURESS: &str = "1";
+pub const NO_DURESS: &str = "0";
+
 /// 4.2.1.1 Generic Message Header Fields
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -26,7 +31,7 @@ impl Header {
             prefix: "WF".to_string(),
             version: "1".to_string(),
             encryption_indicator: "0".to_string(),
-            duress_indicator: "0".to_string(),
+            duress_indicator: NO_DURESS.to_string(),
             message_code: code,
             reference_indicator: "0".to_string(),
             referenced_message: EMPTY_MESSAGE.to_string(),
@@ -38,13 +43,23 @@ impl Header {
             prefix: "WF".to_string(),
             version: "1".to_string(),
             encryption_indicator: "0".to_string(),
-            duress_indicator: "0".to_string(),
+            duress_indicator: NO_DURESS.to_string(),
             message_code: code,
             reference_indicator: reference_indicator,
             referenced_message: EMPTY_MESSAGE.to_string(),
         }
     }
 
+    /// marks the message as sent under duress
+    pub fn with_duress(mut self, duress: bool) -> Self {
+        self.duress_indicator = if duress { DURESS } else { NO_DURESS }.to_string();
+        self
+    }
+
+    pub fn is_under_duress(&self) -> bool {
+        self.duress_indicator == DURESS
+    }
+
     pub fn code(&self) -> Result<MessageCodeType, Error> {
         MessageCodeType::get_message_code(&self.message_code)
     }
@@ -54,6 +69,7 @@ impl Header {
             MessageCodeType::Authentication => {
                 Ok(MessageBodyType::Authentication(Authentication::new(self)))
             }
+            MessageCodeType::Cryptographic => Ok(MessageBodyType::Crypto(Crypto::new(self))),
             MessageCodeType::Resource => Ok(MessageBodyType::Resource(Resource::new(self))),
             MessageCodeType::FreeText => Ok(MessageBodyType::Text(FreeText::new(self))),
             MessageCodeType::Protective
@@ -1,5 +1,6 @@
 use crate::{definitions::*, Field, FieldDefinition, FieldDefinitionParser};
-use std::ops::Mul;
+use alloc::{format, vec::Vec};
+use core::ops::Mul;
 
 const OBJECT_TYPE: FieldDefinition = request::OBJECT_TYPE;
 const OBJECT_TYPE_QUANT: FieldDefinition = request::OBJECT_TYPE_QUANT;
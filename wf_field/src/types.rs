@@ -1,5 +1,6 @@
 use crate::{definitions::*, Error, FieldDefinition, Header};
-use std::str::FromStr;
+use alloc::string::{String, ToString};
+use core::str::FromStr;
 
 impl MessageCodeType {
     pub fn from_code(code: char) -> Self {
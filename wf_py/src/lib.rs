@@ -0,0 +1,216 @@
+//! Python bindings, built with `maturin develop` from this directory
+//!
+//! messages are passed as dicts with the json field names, e.g. `{"prefix": "WF", ...}`
+
+use fennel_whiteflag::{CryptMode, Message};
+use pyo3::{
+    create_exception,
+    exceptions::PyException,
+    prelude::*,
+    types::{PyBytes, PyDict},
+};
+use std::{error::Error, str::FromStr};
+use wf_buffer::{BufferReader, WhiteflagBuffer};
+use wf_crypto::{
+    ecdh_keypair::WhiteflagECDHKeyPair, encryption_method::WhiteflagEncryptionMethod,
+    wf_encryption_key::WhiteflagEncryptionKey, Aes256Ctr, CryptoError as CipherError,
+};
+use wf_field::definitions;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+create_exception!(
+    whiteflag,
+    WhiteflagError,
+    PyException,
+    "base class of all whiteflag errors"
+);
+create_exception!(
+    whiteflag,
+    ValidationError,
+    WhiteflagError,
+    "a message has missing, unknown or invalid fields"
+);
+create_exception!(
+    whiteflag,
+    DecodeError,
+    WhiteflagError,
+    "a hexadecimal string is not a valid encoded message"
+);
+create_exception!(
+    whiteflag,
+    CryptoError,
+    WhiteflagError,
+    "a key or initialisation vector is invalid"
+);
+
+/// the error with its sources, the top level error only names the failing crate
+fn describe(error: &dyn Error) -> String {
+    let mut parts = vec![error.to_string()];
+    let mut source = error.source();
+    while let Some(error) = source {
+        parts.push(error.to_string());
+        source = error.source();
+    }
+
+    parts.join(": ")
+}
+
+fn validation_error<E: Error>(error: E) -> PyErr {
+    ValidationError::new_err(describe(&error))
+}
+
+fn decode_error<E: Error>(error: E) -> PyErr {
+    DecodeError::new_err(describe(&error))
+}
+
+fn crypto_error<E: Error>(error: E) -> PyErr {
+    CryptoError::new_err(describe(&error))
+}
+
+fn to_json(message: &Bound<'_, PyDict>) -> PyResult<String> {
+    let json = message.py().import("json")?;
+    json.call_method1("dumps", (message,))?.extract()
+}
+
+fn from_json<'py>(py: Python<'py>, json: &str) -> PyResult<Bound<'py, PyDict>> {
+    let message = py.import("json")?.call_method1("loads", (json,))?;
+    Ok(message.downcast_into::<PyDict>()?)
+}
+
+fn key_bytes<const N: usize>(value: &[u8], name: &str) -> PyResult<[u8; N]> {
+    value
+        .try_into()
+        .map_err(|_| CryptoError::new_err(format!("{} must be {} bytes", name, N)))
+}
+
+/// decodes a hexadecimal encoded message into a dict
+#[pyfunction]
+fn decode<'py>(py: Python<'py>, hex: &str) -> PyResult<Bound<'py, PyDict>> {
+    let json = fennel_whiteflag::decode_from_hex(hex).map_err(decode_error)?;
+    from_json(py, &json)
+}
+
+/// encodes a message dict into a hexadecimal string, raising ValidationError for invalid fields
+#[pyfunction]
+fn encode(message: &Bound<'_, PyDict>) -> PyResult<String> {
+    fennel_whiteflag::encode_from_json(to_json(message)?).map_err(validation_error)
+}
+
+/// raises ValidationError if the message dict cannot be encoded
+#[pyfunction]
+fn validate(message: &Bound<'_, PyDict>) -> PyResult<()> {
+    encode(message).map(|_| ())
+}
+
+/// a random 16 byte initialisation vector
+#[pyfunction]
+fn generate_iv(py: Python<'_>) -> Bound<'_, PyBytes> {
+    PyBytes::new(py, &wf_crypto::generate_iv())
+}
+
+/// a new ECDH key pair for encryption method 1, as `(secret, public_key)`
+#[pyfunction]
+fn ecdh_keypair(py: Python<'_>) -> (Bound<'_, PyBytes>, Bound<'_, PyBytes>) {
+    let secret = StaticSecret::random_from_rng(rand_core::OsRng);
+    let public_key = PublicKey::from(&secret);
+    (
+        PyBytes::new(py, secret.as_bytes()),
+        PyBytes::new(py, public_key.as_bytes()),
+    )
+}
+
+/// the encryption key for method 1, negotiated from our ECDH secret and the other party's public
+/// key, with the originator's blockchain address as context
+#[pyfunction]
+fn negotiate_key<'py>(
+    py: Python<'py>,
+    secret: &[u8],
+    public_key: &[u8],
+    originator_address: &[u8],
+) -> PyResult<Bound<'py, PyBytes>> {
+    let key_pair =
+        WhiteflagECDHKeyPair::from_secret(StaticSecret::from(key_bytes::<32>(secret, "secret")?));
+    let public_key = PublicKey::from(key_bytes::<32>(public_key, "public_key")?);
+
+    let mut key = WhiteflagEncryptionKey::from_ecdh_key(&public_key, &key_pair);
+    key.set_context(originator_address);
+    Ok(PyBytes::new(py, &key.fixed_raw_secret()))
+}
+
+/// the encryption key for method 2, derived from a pre-shared key with the originator's
+/// blockchain address as context
+#[pyfunction]
+fn preshared_key<'py>(
+    py: Python<'py>,
+    pre_shared_key: &[u8],
+    originator_address: &[u8],
+) -> PyResult<Bound<'py, PyBytes>> {
+    let mut key = WhiteflagEncryptionKey::from_preshared_key(&hex::encode(pre_shared_key));
+    key.set_context(originator_address);
+    Ok(PyBytes::new(py, &key.fixed_raw_secret()))
+}
+
+/// encodes the message dict and encrypts it with a 32 byte key from `negotiate_key` or
+/// `preshared_key`, unless its encryption indicator is "0"
+#[pyfunction]
+fn encrypt(message: &Bound<'_, PyDict>, key: &[u8], iv: &[u8]) -> PyResult<String> {
+    let message = Message::deserialize_from_json(to_json(message)?).map_err(validation_error)?;
+    let cipher = Aes256Ctr::new(key).map_err(crypto_error)?;
+
+    let encrypted = message
+        .encode_and_crypt(&cipher, iv, CryptMode::Encrypt)
+        .map_err(|error| match error {
+            /* an unknown encryption indicator is a field of the message that is not valid */
+            fennel_whiteflag::WhiteflagError::Crypto(CipherError::InvalidMethod) => {
+                validation_error(error)
+            }
+            fennel_whiteflag::WhiteflagError::Crypto(_) => crypto_error(error),
+            _ => validation_error(error),
+        })?;
+    Ok(encrypted.as_hex())
+}
+
+/// decrypts a hexadecimal encoded message with the key and initialisation vector it was
+/// encrypted with, and decodes it into a dict, unless its encryption indicator is "0"
+#[pyfunction]
+fn decrypt<'py>(py: Python<'py>, hex: &str, key: &[u8], iv: &[u8]) -> PyResult<Bound<'py, PyDict>> {
+    let buffer = WhiteflagBuffer::decode_from_hexadecimal(hex).map_err(decode_error)?;
+    let indicator = definitions::header::ENCRYPTION_INDICATOR
+        .read(&buffer)
+        .map_err(decode_error)?;
+
+    let message = match WhiteflagEncryptionMethod::from_str(&indicator).map_err(decode_error)? {
+        WhiteflagEncryptionMethod::NoEncryption => {
+            Message::decode_from_buffer(buffer).map_err(decode_error)?
+        }
+        _ => {
+            let cipher = Aes256Ctr::new(key).map_err(crypto_error)?;
+            Message::decode_and_crypt(buffer, &cipher, iv).map_err(|error| match error {
+                fennel_whiteflag::WhiteflagError::Crypto(_) => crypto_error(error),
+                _ => decode_error(error),
+            })?
+        }
+    };
+    let json = serde_json::to_string(&message).map_err(decode_error)?;
+    from_json(py, &json)
+}
+
+#[pymodule]
+fn whiteflag(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("WhiteflagError", py.get_type::<WhiteflagError>())?;
+    m.add("ValidationError", py.get_type::<ValidationError>())?;
+    m.add("DecodeError", py.get_type::<DecodeError>())?;
+    m.add("CryptoError", py.get_type::<CryptoError>())?;
+
+    m.add_function(wrap_pyfunction!(decode, m)?)?;
+    m.add_function(wrap_pyfunction!(encode, m)?)?;
+    m.add_function(wrap_pyfunction!(validate, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_iv, m)?)?;
+    m.add_function(wrap_pyfunction!(ecdh_keypair, m)?)?;
+    m.add_function(wrap_pyfunction!(negotiate_key, m)?)?;
+    m.add_function(wrap_pyfunction!(preshared_key, m)?)?;
+    m.add_function(wrap_pyfunction!(encrypt, m)?)?;
+    m.add_function(wrap_pyfunction!(decrypt, m)?)?;
+    Ok(())
+}
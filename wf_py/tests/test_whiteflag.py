@@ -0,0 +1,108 @@
+import pytest
+
+import whiteflag
+
+AUTHENTICATION = {
+    "prefix": "WF",
+    "version": "1",
+    "encryptionIndicator": "0",
+    "duressIndicator": "0",
+    "messageCode": "A",
+    "referenceIndicator": "0",
+    "referencedMessage": "0000000000000000000000000000000000000000000000000000000000000000",
+    "verificationMethod": "1",
+    "verificationData": "https://organisation.int/whiteflag",
+}
+AUTHENTICATION_HEX = (
+    "5746313020800000000000000000000000000000000000000000000000000000000000000000"
+    "b43a3a38399d1797b7b933b0b734b9b0ba34b7b71734b73a17bbb434ba32b33630b380"
+)
+ORIGINATOR_ADDRESS = bytes.fromhex("007a0baf6f84f0fa7402ea972686e56d50b707c9b67b108866")
+
+
+def test_encode():
+    assert whiteflag.encode(AUTHENTICATION) == AUTHENTICATION_HEX
+
+
+def test_decode():
+    assert whiteflag.decode(AUTHENTICATION_HEX) == AUTHENTICATION
+
+
+def test_validate():
+    whiteflag.validate(AUTHENTICATION)
+
+    with pytest.raises(whiteflag.ValidationError):
+        whiteflag.validate({**AUTHENTICATION, "verificationMethod": "X"})
+
+    with pytest.raises(whiteflag.ValidationError, match="colour"):
+        whiteflag.encode({**AUTHENTICATION, "colour": "white"})
+
+
+def test_decode_errors():
+    with pytest.raises(whiteflag.DecodeError):
+        whiteflag.decode("zz")
+
+    with pytest.raises(whiteflag.DecodeError):
+        whiteflag.decode("57")
+
+
+def test_preshared_key_encryption():
+    message = {**AUTHENTICATION, "encryptionIndicator": "2"}
+    key = whiteflag.preshared_key(bytes(range(32)), ORIGINATOR_ADDRESS)
+    iv = whiteflag.generate_iv()
+
+    assert len(key) == 32
+    assert len(iv) == 16
+
+    encrypted = whiteflag.encrypt(message, key, iv)
+    assert encrypted != whiteflag.encode(message)
+    assert encrypted.startswith("574631")
+
+    decrypted = whiteflag.decrypt(encrypted, key, iv)
+    assert decrypted["verificationData"] == message["verificationData"]
+    assert decrypted["encryptionIndicator"] == "2"
+
+
+def test_negotiated_key_encryption():
+    originator_secret, originator_public = whiteflag.ecdh_keypair()
+    recipient_secret, recipient_public = whiteflag.ecdh_keypair()
+
+    originator_key = whiteflag.negotiate_key(originator_secret, recipient_public, ORIGINATOR_ADDRESS)
+    recipient_key = whiteflag.negotiate_key(recipient_secret, originator_public, ORIGINATOR_ADDRESS)
+    assert originator_key == recipient_key
+
+    message = {**AUTHENTICATION, "encryptionIndicator": "1"}
+    iv = whiteflag.generate_iv()
+    encrypted = whiteflag.encrypt(message, originator_key, iv)
+    decrypted = whiteflag.decrypt(encrypted, recipient_key, iv)
+    assert decrypted["verificationData"] == message["verificationData"]
+
+
+def test_decrypt_unencrypted_message():
+    key = whiteflag.preshared_key(bytes(range(32)), ORIGINATOR_ADDRESS)
+    iv = whiteflag.generate_iv()
+
+    assert whiteflag.encrypt(AUTHENTICATION, key, iv) == AUTHENTICATION_HEX
+    assert whiteflag.decrypt(AUTHENTICATION_HEX, key, iv) == AUTHENTICATION
+
+
+def test_encrypt_validation_errors():
+    key = whiteflag.preshared_key(bytes(range(32)), ORIGINATOR_ADDRESS)
+    iv = whiteflag.generate_iv()
+
+    with pytest.raises(whiteflag.ValidationError):
+        whiteflag.encrypt({**AUTHENTICATION, "encryptionIndicator": "X"}, key, iv)
+
+    with pytest.raises(whiteflag.ValidationError):
+        whiteflag.encrypt({**AUTHENTICATION, "verificationMethod": "X"}, key, iv)
+
+    with pytest.raises(whiteflag.DecodeError):
+        whiteflag.decrypt("5746315820800000", key, iv)
+
+
+def test_crypto_errors():
+    with pytest.raises(whiteflag.CryptoError):
+        whiteflag.encrypt(AUTHENTICATION, b"short", whiteflag.generate_iv())
+
+    with pytest.raises(whiteflag.CryptoError):
+        whiteflag.negotiate_key(b"short", bytes(32), ORIGINATOR_ADDRESS)
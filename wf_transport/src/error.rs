@@ -0,0 +1,21 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum BlockchainError {
+    #[error("cannot read or write the ledger")]
+    Io(#[from] std::io::Error),
+    #[error("block {block} of the ledger is invalid: {reason}")]
+    InvalidBlock { block: u64, reason: String },
+    #[error("the originator has no address")]
+    MissingOriginatorAddress,
+    #[error("the transaction is invalid: {0}")]
+    InvalidTransaction(String),
+    #[error("the key is not a secp256k1 secret key")]
+    InvalidKey,
+    #[error("the node has no key of account {0}")]
+    UnknownAccount(String),
+    #[error(
+        "the encoded message is {size} bytes, more than the {limit} bytes a transaction holds"
+    )]
+    MessageTooLarge { size: usize, limit: usize },
+}
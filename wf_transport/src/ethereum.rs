@@ -0,0 +1,451 @@
+use crate::{now, BlockchainError, BlockchainResult, IncomingMessage, Transport, WHITEFLAG_PREFIX};
+use k256::{
+    ecdsa::{recoverable, signature::hazmat::PrehashSigner, SigningKey, VerifyingKey},
+    elliptic_curve::sec1::ToEncodedPoint,
+};
+use rlp::{Rlp, RlpStream};
+use sha3::{Digest, Keccak256};
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+use wf_buffer::WhiteflagBuffer;
+
+/// an ethereum account address, the last 20 bytes of the keccak-256 hash of the public key
+pub type Address = [u8; 20];
+
+/// the type of eip-1559 transactions in their eip-2718 envelope
+const EIP1559_TYPE: u8 = 0x02;
+
+/// the gas of a transaction without data, and of each zero and non-zero byte of its data
+const TRANSACTION_GAS: u64 = 21_000;
+const ZERO_BYTE_GAS: u64 = 4;
+const NON_ZERO_BYTE_GAS: u64 = 16;
+
+const DEFAULT_MAX_PRIORITY_FEE_PER_GAS: u128 = 1_000_000_000;
+const DEFAULT_MAX_FEE_PER_GAS: u128 = 2_000_000_000;
+
+/// an account owned by the originator, with the secp256k1 key its transactions are signed with
+#[derive(Clone)]
+pub struct EthereumAccount {
+    key: SigningKey,
+}
+
+impl EthereumAccount {
+    /// the account of a 32 byte secret key
+    pub fn from_secret_key(secret: &[u8]) -> BlockchainResult<Self> {
+        let key = SigningKey::from_bytes(secret).map_err(|_| BlockchainError::InvalidKey)?;
+        Ok(EthereumAccount { key })
+    }
+
+    pub fn address(&self) -> Address {
+        address_of(&self.key.verifying_key())
+    }
+
+    /// signs the transaction with the key of the account
+    pub fn sign(&self, transaction: Eip1559Transaction) -> BlockchainResult<SignedTransaction> {
+        let signature: recoverable::Signature = self
+            .key
+            .sign_prehash(&transaction.signing_hash())
+            .map_err(|e| BlockchainError::InvalidTransaction(e.to_string()))?;
+        let signature = signature.as_ref();
+
+        let mut r = [0u8; 32];
+        let mut s = [0u8; 32];
+        r.copy_from_slice(&signature[..32]);
+        s.copy_from_slice(&signature[32..64]);
+        Ok(SignedTransaction {
+            transaction,
+            y_parity: signature[64] == 1,
+            r,
+            s,
+        })
+    }
+}
+
+/// the secret key is left out
+impl fmt::Debug for EthereumAccount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EthereumAccount")
+            .field("address", &hex::encode(self.address()))
+            .finish()
+    }
+}
+
+/// an eip-1559 transaction without access list
+///
+/// Whiteflag on Ethereum: the encoded message is the data of a transaction of the originator to its
+/// own address, without value
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Eip1559Transaction {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub max_priority_fee_per_gas: u128,
+    pub max_fee_per_gas: u128,
+    pub gas_limit: u64,
+    /// `None` creates a contract
+    pub to: Option<Address>,
+    pub value: u128,
+    pub data: Vec<u8>,
+}
+
+impl Eip1559Transaction {
+    /// the transaction embedding an encoded message, with the gas its data needs and default fees
+    pub fn whiteflag(
+        chain_id: u64,
+        nonce: u64,
+        originator: Address,
+        message: &WhiteflagBuffer,
+    ) -> Self {
+        let data: &[u8] = message.as_ref();
+        Eip1559Transaction {
+            chain_id,
+            nonce,
+            max_priority_fee_per_gas: DEFAULT_MAX_PRIORITY_FEE_PER_GAS,
+            max_fee_per_gas: DEFAULT_MAX_FEE_PER_GAS,
+            gas_limit: intrinsic_gas(data),
+            to: Some(originator),
+            value: 0,
+            data: data.to_vec(),
+        }
+    }
+
+    /// the type byte and the rlp list of the fields, as signed
+    pub fn encode_unsigned(&self) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(9);
+        self.append_fields(&mut stream);
+        envelope(&stream)
+    }
+
+    /// the keccak-256 hash of the unsigned transaction
+    pub fn signing_hash(&self) -> [u8; 32] {
+        keccak256(&self.encode_unsigned())
+    }
+
+    /// the encoded message in the data of the transaction, if it carries one
+    pub fn whiteflag_message(&self) -> Option<WhiteflagBuffer> {
+        match self.data.starts_with(WHITEFLAG_PREFIX) {
+            true => Some(self.data.clone().into()),
+            false => None,
+        }
+    }
+
+    fn append_fields(&self, stream: &mut RlpStream) {
+        stream
+            .append(&self.chain_id)
+            .append(&self.nonce)
+            .append(&self.max_priority_fee_per_gas)
+            .append(&self.max_fee_per_gas)
+            .append(&self.gas_limit);
+        match &self.to {
+            Some(to) => stream.append(&to.as_slice()),
+            None => stream.append_empty_data(),
+        };
+        stream
+            .append(&self.value)
+            .append(&self.data.as_slice())
+            .begin_list(0);
+    }
+}
+
+/// a signed eip-1559 transaction, as it is sent to a node and stored in a block
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedTransaction {
+    pub transaction: Eip1559Transaction,
+    pub y_parity: bool,
+    pub r: [u8; 32],
+    pub s: [u8; 32],
+}
+
+impl SignedTransaction {
+    /// the raw transaction
+    pub fn encode(&self) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(12);
+        self.transaction.append_fields(&mut stream);
+        stream
+            .append(&u8::from(self.y_parity))
+            .append(&trim(&self.r))
+            .append(&trim(&self.s));
+        envelope(&stream)
+    }
+
+    /// reads a raw transaction, which must be a canonical eip-1559 transaction without access list
+    pub fn decode(raw: &[u8]) -> BlockchainResult<Self> {
+        match raw.first() {
+            Some(&EIP1559_TYPE) => {}
+            Some(&t) if t >= 0xc0 => return Err(invalid("legacy transactions are not supported")),
+            Some(&t) => {
+                return Err(invalid(format!(
+                    "transactions of type {} are not supported",
+                    t
+                )))
+            }
+            None => return Err(invalid("the transaction is empty")),
+        }
+
+        let rlp = Rlp::new(&raw[1..]);
+        if !rlp.is_list()
+            || rlp.payload_info().map(|p| p.total()).map_err(invalid)? != raw.len() - 1
+        {
+            return Err(invalid("the transaction is not a single rlp list"));
+        }
+        if rlp.item_count().map_err(invalid)? != 12 {
+            return Err(invalid("an eip-1559 transaction has 12 fields"));
+        }
+        if rlp.at(8).map_err(invalid)?.item_count().map_err(invalid)? != 0 {
+            return Err(invalid("access lists are not supported"));
+        }
+
+        let to = match rlp.at(5).and_then(|to| to.data()).map_err(invalid)? {
+            [] => None,
+            to => Some(Address::try_from(to).map_err(|_| invalid("the address is not 20 bytes"))?),
+        };
+        let transaction = Eip1559Transaction {
+            chain_id: rlp.val_at(0).map_err(invalid)?,
+            nonce: rlp.val_at(1).map_err(invalid)?,
+            max_priority_fee_per_gas: rlp.val_at(2).map_err(invalid)?,
+            max_fee_per_gas: rlp.val_at(3).map_err(invalid)?,
+            gas_limit: rlp.val_at(4).map_err(invalid)?,
+            to,
+            value: rlp.val_at(6).map_err(invalid)?,
+            data: rlp
+                .at(7)
+                .and_then(|data| data.data())
+                .map_err(invalid)?
+                .to_vec(),
+        };
+        let y_parity = match rlp.val_at::<u8>(9).map_err(invalid)? {
+            0 => false,
+            1 => true,
+            _ => return Err(invalid("the y parity of the signature is not 0 or 1")),
+        };
+        Ok(SignedTransaction {
+            transaction,
+            y_parity,
+            r: scalar(&rlp, 10)?,
+            s: scalar(&rlp, 11)?,
+        })
+    }
+
+    /// the keccak-256 hash of the raw transaction
+    pub fn hash(&self) -> [u8; 32] {
+        keccak256(&self.encode())
+    }
+
+    /// the address of the account that signed the transaction
+    pub fn sender(&self) -> BlockchainResult<Address> {
+        let mut signature = [0u8; 65];
+        signature[..32].copy_from_slice(&self.r);
+        signature[32..64].copy_from_slice(&self.s);
+        signature[64] = u8::from(self.y_parity);
+
+        let hash = self.transaction.signing_hash();
+        let key = recoverable::Signature::try_from(&signature[..])
+            .and_then(|signature| signature.recover_verifying_key_from_digest_bytes(&hash.into()))
+            .map_err(|_| invalid("the signature is invalid"))?;
+        Ok(address_of(&key))
+    }
+}
+
+/// a stand-in for a local development node, mining every transaction in a block of its own
+///
+/// like a dev node it holds the keys of its accounts to sign the messages sent from them, and it takes
+/// raw transactions signed elsewhere. clones share the chain
+#[derive(Clone, Debug)]
+pub struct EthereumDevNode {
+    chain_id: u64,
+    chain: Arc<Mutex<Chain>>,
+}
+
+#[derive(Debug, Default)]
+struct Chain {
+    accounts: Vec<EthereumAccount>,
+    blocks: Vec<Block>,
+}
+
+#[derive(Debug)]
+struct Block {
+    timestamp: u64,
+    transaction: Vec<u8>,
+}
+
+impl EthereumDevNode {
+    pub fn new(chain_id: u64) -> Self {
+        EthereumDevNode {
+            chain_id,
+            chain: Arc::default(),
+        }
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    /// lets the node sign the transactions of the account, returning its address
+    pub fn add_account(&self, account: EthereumAccount) -> Address {
+        let address = account.address();
+        self.lock().accounts.push(account);
+        address
+    }
+
+    /// the number of transactions sent from the address, the nonce of its next transaction
+    pub fn nonce(&self, address: &Address) -> BlockchainResult<u64> {
+        self.lock().nonce(address)
+    }
+
+    /// mines a raw transaction, returning its hexadecimal hash
+    pub fn send_raw_transaction(&self, raw: &[u8]) -> BlockchainResult<String> {
+        let transaction = SignedTransaction::decode(raw)?;
+        if transaction.transaction.chain_id != self.chain_id {
+            return Err(invalid(format!(
+                "the chain id is {}, not {}",
+                transaction.transaction.chain_id, self.chain_id
+            )));
+        }
+        let sender = transaction.sender()?;
+
+        let mut chain = self.lock();
+        let nonce = chain.nonce(&sender)?;
+        if transaction.transaction.nonce != nonce {
+            return Err(invalid(format!(
+                "the nonce is {}, not {}",
+                transaction.transaction.nonce, nonce
+            )));
+        }
+        chain.blocks.push(Block {
+            timestamp: now(),
+            transaction: raw.to_vec(),
+        });
+        Ok(hex::encode(transaction.hash()))
+    }
+
+    /// the chain of a poisoned lock is still whole, as blocks are only pushed
+    fn lock(&self) -> std::sync::MutexGuard<'_, Chain> {
+        self.chain.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Chain {
+    fn nonce(&self, address: &Address) -> BlockchainResult<u64> {
+        let mut nonce = 0;
+        for (block, transaction) in self.transactions() {
+            if transaction.map_err(|e| invalid_block(block, e))?.sender()? == *address {
+                nonce += 1;
+            }
+        }
+        Ok(nonce)
+    }
+
+    fn transactions(
+        &self,
+    ) -> impl Iterator<Item = (u64, BlockchainResult<SignedTransaction>)> + '_ {
+        self.blocks
+            .iter()
+            .enumerate()
+            .map(|(block, b)| (block as u64, SignedTransaction::decode(&b.transaction)))
+    }
+}
+
+impl Transport for EthereumDevNode {
+    /// signs and mines a transaction with the message from an account of the node
+    fn send(&mut self, originator: &[u8], message: &WhiteflagBuffer) -> BlockchainResult<String> {
+        if originator.is_empty() {
+            return Err(BlockchainError::MissingOriginatorAddress);
+        }
+        let account = self
+            .lock()
+            .accounts
+            .iter()
+            .find(|account| account.address() == originator)
+            .cloned()
+            .ok_or_else(|| BlockchainError::UnknownAccount(hex::encode(originator)))?;
+
+        let address = account.address();
+        let transaction =
+            Eip1559Transaction::whiteflag(self.chain_id, self.nonce(&address)?, address, message);
+        self.send_raw_transaction(&account.sign(transaction)?.encode())
+    }
+
+    /// the transactions carrying a message, others are skipped
+    fn messages_from(&mut self, block: u64) -> BlockchainResult<Vec<IncomingMessage>> {
+        let chain = self.lock();
+        let mut messages = Vec::new();
+        for (number, transaction) in chain.transactions().skip(block as usize) {
+            let transaction = transaction.map_err(|e| invalid_block(number, e))?;
+            let message = match transaction.transaction.whiteflag_message() {
+                Some(message) => message,
+                None => continue,
+            };
+            messages.push(IncomingMessage {
+                transaction_hash: hex::encode(transaction.hash()),
+                originator: transaction
+                    .sender()
+                    .map_err(|e| invalid_block(number, e))?
+                    .to_vec(),
+                block: number,
+                timestamp: chain.blocks[number as usize].timestamp,
+                message,
+            });
+        }
+        Ok(messages)
+    }
+
+    fn next_block(&mut self) -> BlockchainResult<u64> {
+        Ok(self.lock().blocks.len() as u64)
+    }
+}
+
+/// the gas a transaction needs before any execution, for its data
+fn intrinsic_gas(data: &[u8]) -> u64 {
+    data.iter().fold(TRANSACTION_GAS, |gas, byte| match byte {
+        0 => gas + ZERO_BYTE_GAS,
+        _ => gas + NON_ZERO_BYTE_GAS,
+    })
+}
+
+fn address_of(key: &VerifyingKey) -> Address {
+    let point = key.to_encoded_point(false);
+    let hash = keccak256(&point.as_bytes()[1..]);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    address
+}
+
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+fn envelope(stream: &RlpStream) -> Vec<u8> {
+    let mut raw = vec![EIP1559_TYPE];
+    raw.extend_from_slice(stream.as_raw());
+    raw
+}
+
+/// a signature scalar is an rlp integer, without leading zero bytes
+fn trim(scalar: &[u8; 32]) -> &[u8] {
+    let zeros = scalar.iter().take_while(|&&b| b == 0).count();
+    &scalar[zeros..]
+}
+
+fn scalar(rlp: &Rlp, index: usize) -> BlockchainResult<[u8; 32]> {
+    let bytes = rlp.at(index).and_then(|b| b.data()).map_err(invalid)?;
+    if bytes.len() > 32 || bytes.first() == Some(&0) {
+        return Err(invalid(
+            "a signature scalar is not a canonical 32 byte integer",
+        ));
+    }
+    let mut scalar = [0u8; 32];
+    scalar[32 - bytes.len()..].copy_from_slice(bytes);
+    Ok(scalar)
+}
+
+fn invalid<E: ToString>(reason: E) -> BlockchainError {
+    BlockchainError::InvalidTransaction(reason.to_string())
+}
+
+fn invalid_block(block: u64, error: BlockchainError) -> BlockchainError {
+    BlockchainError::InvalidBlock {
+        block,
+        reason: error.to_string(),
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{
+    BlockchainError, Eip1559Transaction, EthereumAccount, EthereumDevNode, SignedTransaction,
+    Transport,
+};
+use wf_buffer::WhiteflagBuffer;
+
+const SECRET_KEY: &str = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+const ADDRESS: &str = "2c7536e3605d9c16a7a3d7b1898e529396a65c23";
+const CHAIN_ID: u64 = 1337;
+const AUTH_MESSAGE: &str = "5746313020800000000000000000000000000000000000000000000000000000000000000000b43a3a38399d1797b7b933b0b734b9b0ba34b7b71734b73a17bbb434ba32b33630b380";
+
+/* the auth message sent from the account with nonce 0, signed by another implementation */
+const UNSIGNED: &str = "02f87382053980843b9aca008477359400825518942c7536e3605d9c16a7a3d7b1898e529396a65c2380b8495746313020800000000000000000000000000000000000000000000000000000000000000000b43a3a38399d1797b7b933b0b734b9b0ba34b7b71734b73a17bbb434ba32b33630b380c0";
+const SIGNING_HASH: &str = "3f8026f4e5d36aa46df07100c230533916e6bd706c720b4c1f0b072d44c24930";
+const RAW: &str = "02f8b682053980843b9aca008477359400825518942c7536e3605d9c16a7a3d7b1898e529396a65c2380b8495746313020800000000000000000000000000000000000000000000000000000000000000000b43a3a38399d1797b7b933b0b734b9b0ba34b7b71734b73a17bbb434ba32b33630b380c080a07e6ba3640aac03dbae6942af613533224c966750b69491a7194af2cc0d1359c7a04254aee30f9d283b0216eb1937ac45e636313753504b090ca72907fb4ba7a165";
+const HASH: &str = "79e994c4a09ae6f3f9a3384af140383b440762cce483e212b4a1fa6170c8c796";
+
+/* data that is not a message, with nonce 7 and a gas limit of 100000 */
+const RAW_OTHER: &str = "02f87182053907843b9aca008477359400830186a0942c7536e3605d9c16a7a3d7b1898e529396a65c238084deadbeefc080a0358c6354766b27fc99aa74f63f9785b86cf6de800b4bd7c9ad899b6d9df880dda064db8cba4736d63dceb010a38a7073d6bd365b2d24a9e960f8ed0d0faa041c6c";
+const HASH_OTHER: &str = "a6ff1b512523772761f871145288d9181eb181d73e0aabf618fb6031df7c5e2b";
+
+fn account() -> EthereumAccount {
+    EthereumAccount::from_secret_key(&hex::decode(SECRET_KEY).unwrap()).unwrap()
+}
+
+fn message() -> WhiteflagBuffer {
+    WhiteflagBuffer::decode_from_hexadecimal(AUTH_MESSAGE).unwrap()
+}
+
+#[test]
+fn sign_a_message_transaction() {
+    let account = account();
+    assert_eq!(ADDRESS, hex::encode(account.address()));
+
+    let transaction = Eip1559Transaction::whiteflag(CHAIN_ID, 0, account.address(), &message());
+    assert_eq!(21784, transaction.gas_limit);
+    assert_eq!(Some(account.address()), transaction.to);
+    assert_eq!(UNSIGNED, hex::encode(transaction.encode_unsigned()));
+    assert_eq!(SIGNING_HASH, hex::encode(transaction.signing_hash()));
+
+    let signed = account.sign(transaction).unwrap();
+    assert_eq!(RAW, hex::encode(signed.encode()));
+    assert_eq!(HASH, hex::encode(signed.hash()));
+}
+
+#[test]
+fn extract_a_message_from_a_raw_transaction() {
+    let signed = SignedTransaction::decode(&hex::decode(RAW).unwrap()).unwrap();
+    assert_eq!(CHAIN_ID, signed.transaction.chain_id);
+    assert_eq!(ADDRESS, hex::encode(signed.sender().unwrap()));
+    assert_eq!(HASH, hex::encode(signed.hash()));
+    assert_eq!(
+        AUTH_MESSAGE,
+        signed.transaction.whiteflag_message().unwrap().as_hex()
+    );
+
+    let other = SignedTransaction::decode(&hex::decode(RAW_OTHER).unwrap()).unwrap();
+    assert_eq!(
+        (7, 100_000),
+        (other.transaction.nonce, other.transaction.gas_limit)
+    );
+    assert_eq!(ADDRESS, hex::encode(other.sender().unwrap()));
+    assert_eq!(HASH_OTHER, hex::encode(other.hash()));
+    assert_eq!(None, other.transaction.whiteflag_message());
+}
+
+#[test]
+fn reject_invalid_raw_transactions() {
+    let raw = hex::decode(RAW).unwrap();
+    let invalid = |raw: &[u8]| {
+        matches!(
+            SignedTransaction::decode(raw),
+            Err(BlockchainError::InvalidTransaction(_))
+        )
+    };
+
+    assert!(invalid(&[]));
+    assert!(invalid(&raw[..raw.len() - 1]), "truncated");
+    assert!(invalid(&[raw.as_slice(), &[0]].concat()), "trailing byte");
+    assert!(invalid(&[&[0x01], &raw[1..]].concat()), "eip-2930 type");
+    assert!(invalid(&raw[1..]), "legacy");
+
+    /* a tampered message is signed by someone else */
+    let mut tampered = raw.clone();
+    tampered[60] ^= 1;
+    let signed = SignedTransaction::decode(&tampered).unwrap();
+    assert_ne!(ADDRESS, hex::encode(signed.sender().unwrap_or_default()));
+
+    assert!(matches!(
+        EthereumAccount::from_secret_key(&[0; 32]),
+        Err(BlockchainError::InvalidKey)
+    ));
+}
+
+#[test]
+fn send_and_receive_on_a_dev_node() {
+    let mut node = EthereumDevNode::new(CHAIN_ID);
+    let mut receiver = node.clone();
+    let address = node.add_account(account());
+
+    let hash = node.send(&address, &message()).unwrap();
+    assert_eq!(
+        HASH, hash,
+        "the first transaction of the account has nonce 0"
+    );
+
+    /* a raw transaction signed elsewhere, which does not carry a message */
+    let mut other = SignedTransaction::decode(&hex::decode(RAW_OTHER).unwrap()).unwrap();
+    other.transaction.nonce = 1;
+    let other = account().sign(other.transaction).unwrap();
+    node.send_raw_transaction(&other.encode()).unwrap();
+    let second = node.send(&address, &message()).unwrap();
+    assert_eq!(3, node.nonce(&address).unwrap());
+
+    let messages = receiver.messages_from(0).unwrap();
+    assert_eq!(2, messages.len());
+    assert_eq!(
+        (hash, 0),
+        (messages[0].transaction_hash.clone(), messages[0].block)
+    );
+    assert_eq!(
+        (second, 2),
+        (messages[1].transaction_hash.clone(), messages[1].block)
+    );
+    assert_eq!(address.to_vec(), messages[1].originator);
+    assert_eq!(AUTH_MESSAGE, messages[1].message.as_hex());
+    assert_eq!(3, receiver.next_block().unwrap());
+}
+
+#[test]
+fn dev_node_rejects_replays_and_unknown_accounts() {
+    let mut node = EthereumDevNode::new(CHAIN_ID);
+    let raw = hex::decode(RAW).unwrap();
+    node.send_raw_transaction(&raw).unwrap();
+    assert!(matches!(
+        node.send_raw_transaction(&raw),
+        Err(BlockchainError::InvalidTransaction(_))
+    ));
+    assert!(matches!(
+        EthereumDevNode::new(1).send_raw_transaction(&raw),
+        Err(BlockchainError::InvalidTransaction(_))
+    ));
+
+    let address = account().address();
+    assert!(matches!(
+        node.send(&address, &message()),
+        Err(BlockchainError::UnknownAccount(_))
+    ));
+    assert!(matches!(
+        node.send(&[], &message()),
+        Err(BlockchainError::MissingOriginatorAddress)
+    ));
+}
@@ -0,0 +1,97 @@
+pub use bitcoin::Network;
+pub use error::BlockchainError;
+pub use ethereum::{
+    Address, Eip1559Transaction, EthereumAccount, EthereumDevNode, SignedTransaction,
+};
+pub use mock::MockLedger;
+pub use op_return::{
+    block_file_messages, block_messages, is_block_file, op_return_message, op_return_script,
+    transaction_messages, BitcoinMessage, OP_RETURN_LIMIT,
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+use wf_buffer::WhiteflagBuffer;
+
+#[cfg(test)]
+mod ethereum_tests;
+#[cfg(test)]
+mod mock_tests;
+#[cfg(test)]
+mod op_return_tests;
+
+mod error;
+mod ethereum;
+mod mock;
+mod op_return;
+
+pub type BlockchainResult<T> = Result<T, BlockchainError>;
+
+/// the prefix and version of an encoded message, which start the data of a transaction carrying one
+pub(crate) const WHITEFLAG_PREFIX: &[u8] = b"WF1";
+
+/// a message read from a blockchain, with the transaction it was sent in
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IncomingMessage {
+    /// hexadecimal, the reference of the message in the referenced message field of other messages
+    pub transaction_hash: String,
+    /// the address of the originator
+    pub originator: Vec<u8>,
+    pub block: u64,
+    /// seconds since the unix epoch
+    pub timestamp: u64,
+    /// the encoded message, its last byte padded with zero bits as it is stored on the blockchain
+    pub message: WhiteflagBuffer,
+}
+
+/// a blockchain Whiteflag messages are sent on and read from
+///
+/// Whiteflag Specification 3 Message Transfer: a message is embedded in a transaction of its originator
+pub trait Transport {
+    /// sends an encoded message from the address of the originator, returning the hexadecimal
+    /// transaction hash
+    fn send(&mut self, originator: &[u8], message: &WhiteflagBuffer) -> BlockchainResult<String>;
+
+    /// the messages in the blocks from `block` on, in the order they were sent
+    fn messages_from(&mut self, block: u64) -> BlockchainResult<Vec<IncomingMessage>>;
+
+    /// the number of the next block
+    fn next_block(&mut self) -> BlockchainResult<u64>;
+
+    /// a subscription to the messages sent from now on
+    fn subscribe(&mut self) -> BlockchainResult<Subscription<'_, Self>>
+    where
+        Self: Sized,
+    {
+        let block = self.next_block()?;
+        Ok(Subscription::from_block(self, block))
+    }
+}
+
+/// the messages of a transport that have not been polled yet
+pub struct Subscription<'a, T: Transport> {
+    transport: &'a mut T,
+    block: u64,
+}
+
+impl<'a, T: Transport> Subscription<'a, T> {
+    /// a subscription to the messages in the blocks from `block` on
+    pub fn from_block(transport: &'a mut T, block: u64) -> Self {
+        Subscription { transport, block }
+    }
+
+    /// the messages sent since the last poll
+    pub fn poll(&mut self) -> BlockchainResult<Vec<IncomingMessage>> {
+        let messages = self.transport.messages_from(self.block)?;
+        if let Some(last) = messages.last() {
+            self.block = last.block + 1;
+        }
+        Ok(messages)
+    }
+}
+
+/// seconds since the unix epoch, the timestamp of a new block
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,158 @@
+use crate::{now, BlockchainError, BlockchainResult, IncomingMessage, Transport};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    fs,
+    io::{BufRead, BufReader, ErrorKind, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+use wf_buffer::WhiteflagBuffer;
+
+/// a local ledger of one transaction per block, to send and receive messages without a blockchain
+///
+/// an in-memory ledger is shared by its clones, a file-backed one by everyone opening the file, which
+/// holds one json transaction per line
+#[derive(Clone, Debug)]
+pub struct MockLedger {
+    backend: Backend,
+}
+
+#[derive(Clone, Debug)]
+enum Backend {
+    Memory(Arc<Mutex<Vec<Transaction>>>),
+    File(PathBuf),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Transaction {
+    hash: String,
+    originator: String,
+    timestamp: u64,
+    data: String,
+}
+
+impl MockLedger {
+    pub fn in_memory() -> Self {
+        MockLedger {
+            backend: Backend::Memory(Arc::default()),
+        }
+    }
+
+    /// the ledger in a file, which is created by the first message sent
+    pub fn open<P: Into<PathBuf>>(path: P) -> Self {
+        MockLedger {
+            backend: Backend::File(path.into()),
+        }
+    }
+
+    /// the file of a file-backed ledger
+    pub fn path(&self) -> Option<&Path> {
+        match &self.backend {
+            Backend::Memory(_) => None,
+            Backend::File(path) => Some(path),
+        }
+    }
+
+    fn transactions(&self) -> BlockchainResult<Vec<Transaction>> {
+        let path = match &self.backend {
+            Backend::Memory(transactions) => return Ok(lock(transactions).clone()),
+            Backend::File(path) => path,
+        };
+
+        let file = match fs::File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut transactions = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let block = transactions.len() as u64;
+            transactions.push(serde_json::from_str(&line?).map_err(|e| invalid(block, e))?);
+        }
+        Ok(transactions)
+    }
+
+    /// adds a transaction in a new block
+    fn append(&self, transaction: Transaction) -> BlockchainResult<()> {
+        match &self.backend {
+            Backend::Memory(transactions) => lock(transactions).push(transaction),
+            Backend::File(path) => {
+                if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                    fs::create_dir_all(dir)?;
+                }
+                let mut line = serde_json::to_string(&transaction).unwrap_or_default();
+                line.push('\n');
+                fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)?
+                    .write_all(line.as_bytes())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Transport for MockLedger {
+    fn send(&mut self, originator: &[u8], message: &WhiteflagBuffer) -> BlockchainResult<String> {
+        if originator.is_empty() {
+            return Err(BlockchainError::MissingOriginatorAddress);
+        }
+
+        let block = self.next_block()?;
+        let data: &[u8] = message.as_ref();
+        let mut hasher = Sha256::new();
+        hasher.update(block.to_be_bytes());
+        hasher.update(originator);
+        hasher.update(data);
+        let hash = hex::encode(hasher.finalize());
+
+        self.append(Transaction {
+            hash: hash.clone(),
+            originator: hex::encode(originator),
+            timestamp: now(),
+            data: hex::encode(data),
+        })?;
+        Ok(hash)
+    }
+
+    fn messages_from(&mut self, block: u64) -> BlockchainResult<Vec<IncomingMessage>> {
+        let transactions = self.transactions()?;
+        transactions
+            .into_iter()
+            .enumerate()
+            .skip(block as usize)
+            .map(|(block, transaction)| {
+                let block = block as u64;
+                Ok(IncomingMessage {
+                    transaction_hash: transaction.hash,
+                    originator: hex::decode(&transaction.originator)
+                        .map_err(|e| invalid(block, e))?,
+                    block,
+                    timestamp: transaction.timestamp,
+                    message: hex::decode(&transaction.data)
+                        .map_err(|e| invalid(block, e))?
+                        .into(),
+                })
+            })
+            .collect()
+    }
+
+    fn next_block(&mut self) -> BlockchainResult<u64> {
+        Ok(self.transactions()?.len() as u64)
+    }
+}
+
+/// the transactions of a poisoned lock are still whole, as they are only pushed
+fn lock(transactions: &Mutex<Vec<Transaction>>) -> std::sync::MutexGuard<'_, Vec<Transaction>> {
+    transactions.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn invalid<E: ToString>(block: u64, error: E) -> BlockchainError {
+    BlockchainError::InvalidBlock {
+        block,
+        reason: error.to_string(),
+    }
+}
@@ -0,0 +1,78 @@
+use crate::{BlockchainError, MockLedger, Transport};
+use wf_buffer::WhiteflagBuffer;
+
+const ALICE: &[u8] = &[0x00, 0x7a, 0x0b, 0xaf];
+const BOB: &[u8] = &[0x00, 0xbb, 0x22];
+const AUTH_MESSAGE: &str = "5746313020800000000000000000000000000000000000000000000000000000000000000000b43a3a38399d1797b7b933b0b734b9b0ba34b7b71734b73a17bbb434ba32b33630b380";
+
+fn message() -> WhiteflagBuffer {
+    WhiteflagBuffer::decode_from_hexadecimal(AUTH_MESSAGE).unwrap()
+}
+
+#[test]
+fn send_and_receive() {
+    let mut ledger = MockLedger::in_memory();
+    let mut receiver = ledger.clone();
+
+    let first = ledger.send(ALICE, &message()).unwrap();
+    let second = ledger.send(BOB, &message()).unwrap();
+    assert_eq!(64, first.len());
+    assert_ne!(first, second, "transaction hashes must be unique");
+
+    let messages = receiver.messages_from(0).unwrap();
+    assert_eq!(2, messages.len());
+    assert_eq!(first, messages[0].transaction_hash);
+    assert_eq!(ALICE, messages[0].originator);
+    assert_eq!((0, 1), (messages[0].block, messages[1].block));
+    assert_eq!(AUTH_MESSAGE, messages[0].message.as_hex());
+    assert_eq!(BOB, receiver.messages_from(1).unwrap()[0].originator);
+    assert_eq!(2, receiver.next_block().unwrap());
+}
+
+#[test]
+fn subscription_polls_new_messages() {
+    let mut ledger = MockLedger::in_memory();
+    let mut receiver = ledger.clone();
+    ledger.send(ALICE, &message()).unwrap();
+
+    let mut subscription = receiver.subscribe().unwrap();
+    assert!(subscription.poll().unwrap().is_empty());
+
+    let hash = ledger.send(BOB, &message()).unwrap();
+    let messages = subscription.poll().unwrap();
+    assert_eq!(1, messages.len());
+    assert_eq!(hash, messages[0].transaction_hash);
+    assert!(subscription.poll().unwrap().is_empty());
+}
+
+#[test]
+fn file_backed_ledger() {
+    let path = std::env::temp_dir()
+        .join(format!("wf_transport_{}", std::process::id()))
+        .join("ledger.jsonl");
+    let _ = std::fs::remove_file(&path);
+
+    let mut ledger = MockLedger::open(&path);
+    assert!(ledger.messages_from(0).unwrap().is_empty());
+    let hash = ledger.send(ALICE, &message()).unwrap();
+
+    /* another process opening the same file */
+    let mut reopened = MockLedger::open(&path);
+    let messages = reopened.messages_from(0).unwrap();
+    assert_eq!(hash, messages[0].transaction_hash);
+    assert_eq!(AUTH_MESSAGE, messages[0].message.as_hex());
+
+    std::fs::write(&path, "{}\n").unwrap();
+    assert!(matches!(
+        reopened.messages_from(0),
+        Err(BlockchainError::InvalidBlock { block: 0, .. })
+    ));
+}
+
+#[test]
+fn originator_needs_an_address() {
+    assert!(matches!(
+        MockLedger::in_memory().send(&[], &message()),
+        Err(BlockchainError::MissingOriginatorAddress)
+    ));
+}
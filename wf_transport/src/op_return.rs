@@ -0,0 +1,179 @@
+use crate::{BlockchainError, BlockchainResult, WHITEFLAG_PREFIX};
+use bitcoin::{
+    blockdata::script::Instruction, consensus::deserialize, Address, Block, Network, PublicKey,
+    Script, Transaction, TxIn,
+};
+use wf_buffer::WhiteflagBuffer;
+
+/// the most data of an `OP_RETURN` output nodes relay
+pub const OP_RETURN_LIMIT: usize = 80;
+
+/// a message found in an `OP_RETURN` output of a bitcoin transaction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitcoinMessage {
+    /// the txid, the reference of the message in the referenced message field of other messages
+    pub transaction_hash: String,
+    /// the index of the `OP_RETURN` output
+    pub output: u32,
+    /// the address spent by the first input, if its script or witness holds a public key
+    pub originator: Option<Address>,
+    /// the hash of the block the transaction was read from
+    pub block_hash: Option<String>,
+    /// the time of the block, in seconds since the unix epoch
+    pub timestamp: Option<u64>,
+    /// the encoded message, its last byte padded with zero bits as it is stored on the blockchain
+    pub message: WhiteflagBuffer,
+}
+
+/// the `OP_RETURN` script of an output embedding an encoded message
+///
+/// Whiteflag on Bitcoin: the encoded message is the data of an `OP_RETURN` output, which holds
+/// [`OP_RETURN_LIMIT`] bytes
+pub fn op_return_script(message: &WhiteflagBuffer) -> BlockchainResult<Script> {
+    let data: &[u8] = message.as_ref();
+    if data.len() > OP_RETURN_LIMIT {
+        return Err(BlockchainError::MessageTooLarge {
+            size: data.len(),
+            limit: OP_RETURN_LIMIT,
+        });
+    }
+    Ok(Script::new_op_return(data))
+}
+
+/// the encoded message in an `OP_RETURN` script, if it carries one
+pub fn op_return_message(script: &Script) -> Option<WhiteflagBuffer> {
+    if !script.is_op_return() {
+        return None;
+    }
+
+    let mut data = Vec::new();
+    for instruction in script.instructions().skip(1) {
+        match instruction {
+            Ok(Instruction::PushBytes(bytes)) => data.extend_from_slice(bytes),
+            _ => return None,
+        }
+    }
+    match data.starts_with(WHITEFLAG_PREFIX) {
+        true => Some(data.into()),
+        false => None,
+    }
+}
+
+/// the messages of a raw transaction
+pub fn transaction_messages(raw: &[u8], network: Network) -> BlockchainResult<Vec<BitcoinMessage>> {
+    let transaction: Transaction =
+        deserialize(raw).map_err(|e| BlockchainError::InvalidTransaction(e.to_string()))?;
+    Ok(messages(&transaction, network))
+}
+
+/// the messages of the transactions in a raw block, in the order of the block
+pub fn block_messages(raw: &[u8], network: Network) -> BlockchainResult<Vec<BitcoinMessage>> {
+    let block: Block = deserialize(raw).map_err(|e| invalid_block(0, e))?;
+    Ok(messages_in(&block, network))
+}
+
+/// the messages of the blocks in a `blk*.dat` file of a node, where every block follows the magic
+/// bytes of the network and its size
+///
+/// the zero bytes a node reserves at the end of the file are skipped
+pub fn block_file_messages(
+    bytes: &[u8],
+    network: Network,
+) -> BlockchainResult<Vec<BitcoinMessage>> {
+    let magic = network.magic().to_le_bytes();
+    let mut messages = Vec::new();
+    let mut rest = bytes;
+    let mut block = 0;
+    while !rest.iter().all(|&b| b == 0) {
+        if rest.len() < 8 || rest[..4] != magic {
+            return Err(invalid_block(
+                block,
+                "the block does not start with the network magic",
+            ));
+        }
+        let size = u32::from_le_bytes([rest[4], rest[5], rest[6], rest[7]]) as usize;
+        let raw = rest
+            .get(8..8 + size)
+            .ok_or_else(|| invalid_block(block, "the file ends within the block"))?;
+        let parsed: Block = deserialize(raw).map_err(|e| invalid_block(block, e))?;
+
+        messages.extend(messages_in(&parsed, network));
+        rest = &rest[8 + size..];
+        block += 1;
+    }
+    Ok(messages)
+}
+
+/// tells whether bytes are a `blk*.dat` file of the network rather than a raw block
+pub fn is_block_file(bytes: &[u8], network: Network) -> bool {
+    bytes.starts_with(&network.magic().to_le_bytes())
+}
+
+fn messages_in(block: &Block, network: Network) -> Vec<BitcoinMessage> {
+    let block_hash = block.block_hash().to_string();
+    block
+        .txdata
+        .iter()
+        .flat_map(|transaction| messages(transaction, network))
+        .map(|message| BitcoinMessage {
+            block_hash: Some(block_hash.clone()),
+            timestamp: Some(block.header.time.into()),
+            ..message
+        })
+        .collect()
+}
+
+fn messages(transaction: &Transaction, network: Network) -> Vec<BitcoinMessage> {
+    let originator = match transaction.is_coin_base() {
+        true => None,
+        false => transaction
+            .input
+            .first()
+            .and_then(|input| originator(input, network)),
+    };
+
+    transaction
+        .output
+        .iter()
+        .enumerate()
+        .filter_map(|(output, txout)| {
+            Some(BitcoinMessage {
+                transaction_hash: transaction.txid().to_string(),
+                output: output as u32,
+                originator: originator.clone(),
+                block_hash: None,
+                timestamp: None,
+                message: op_return_message(&txout.script_pubkey)?,
+            })
+        })
+        .collect()
+}
+
+/// the address an input spends, from the public key of a p2pkh script, a p2wpkh witness or a
+/// p2wpkh witness nested in p2sh
+fn originator(input: &TxIn, network: Network) -> Option<Address> {
+    let mut pushes = Vec::new();
+    for instruction in input.script_sig.instructions() {
+        match instruction {
+            Ok(Instruction::PushBytes(bytes)) => pushes.push(bytes),
+            _ => return None,
+        }
+    }
+    let witness = input.witness.to_vec();
+
+    match (pushes.as_slice(), witness.as_slice()) {
+        ([], [_, key]) => Address::p2wpkh(&PublicKey::from_slice(key).ok()?, network).ok(),
+        ([redeem], [_, key]) if redeem.len() == 22 && redeem[..2] == [0x00, 0x14] => {
+            Address::p2shwpkh(&PublicKey::from_slice(key).ok()?, network).ok()
+        }
+        ([_, key], []) => Some(Address::p2pkh(&PublicKey::from_slice(key).ok()?, network)),
+        _ => None,
+    }
+}
+
+fn invalid_block<E: ToString>(block: u64, error: E) -> BlockchainError {
+    BlockchainError::InvalidBlock {
+        block,
+        reason: error.to_string(),
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{
+    block_file_messages, block_messages, is_block_file, op_return_message, op_return_script,
+    transaction_messages, BlockchainError, Network, OP_RETURN_LIMIT,
+};
+use bitcoin::{
+    blockdata::constants::genesis_block, consensus::deserialize, consensus::serialize, Block,
+    Script, Transaction,
+};
+use wf_buffer::WhiteflagBuffer;
+
+const AUTH_MESSAGE: &str = "5746313020800000000000000000000000000000000000000000000000000000000000000000b43a3a38399d1797b7b933b0b734b9b0ba34b7b71734b73a17bbb434ba32b33630b380";
+const OP_RETURN: &str = "6a495746313020800000000000000000000000000000000000000000000000000000000000000000b43a3a38399d1797b7b933b0b734b9b0ba34b7b71734b73a17bbb434ba32b33630b380";
+
+/* the auth message in the first output of a p2wpkh spend on regtest */
+const P2WPKH_TX: &str = "020000000001018dd4f5fbd5e980fc02f35c6ce145935b11e284605bf599a13c6d415db55d07a10000000000fdffffff0200000000000000004b6a495746313020800000000000000000000000000000000000000000000000000000000000000000b43a3a38399d1797b7b933b0b734b9b0ba34b7b71734b73a17bbb434ba32b33630b380b8820100000000001600149b78039087bd663f20ace711f15be0eaf7d07005024730440220500ea079c4cf5cccf6c5a2cf266e8c0e7fac9d260acae7e3a39994807195a5830220447fc5dfed2eedb4256b8b62b204e5b622bd5e6590fa84570c9db82cecb876590121024e3b81af9c2234cad09d679ce6035ed1392347ce64ce405f5dcd36228a25de6e00000000";
+const P2WPKH_TXID: &str = "38031463385a28a7bc868b9932afed71f4226521ef0e4ba04a6b2680fa8aa370";
+const P2WPKH_ADDRESS: &str = "bcrt1qnduq8yy8h4nr7g9vuuglzklqatmaquq94hh56s";
+
+/* the auth message in the second output of a p2pkh spend of the same key */
+const P2PKH_TX: &str = "01000000018dd4f5fbd5e980fc02f35c6ce145935b11e284605bf599a13c6d415db55d07a1010000006b483045022100e98995f76c9a4ee4023664bf06451124e615e846614e01644e72cba8c0b3c1e10220598592cffb0b2899e012e1c4d5eff3649ea34873b4d7f49bbdfc64f8656e08cc0121024e3b81af9c2234cad09d679ce6035ed1392347ce64ce405f5dcd36228a25de6effffffff02d07e0100000000001976a9149b78039087bd663f20ace711f15be0eaf7d0700588ac00000000000000004b6a495746313020800000000000000000000000000000000000000000000000000000000000000000b43a3a38399d1797b7b933b0b734b9b0ba34b7b71734b73a17bbb434ba32b33630b38000000000";
+const P2PKH_TXID: &str = "99384215536612be8b44f1b8117e359811df22039d320dda858d7ffc052a23b0";
+const P2PKH_ADDRESS: &str = "mugzoW2s3yhbBbEzXd8n51KD2vm7ohjpcz";
+
+fn message() -> WhiteflagBuffer {
+    WhiteflagBuffer::decode_from_hexadecimal(AUTH_MESSAGE).unwrap()
+}
+
+/// the regtest genesis block followed by both transactions
+fn block() -> Block {
+    let mut block = genesis_block(Network::Regtest);
+    for raw in [P2WPKH_TX, P2PKH_TX] {
+        let transaction: Transaction = deserialize(&hex::decode(raw).unwrap()).unwrap();
+        block.txdata.push(transaction);
+    }
+    block.header.merkle_root = block.compute_merkle_root().unwrap();
+    block
+}
+
+#[test]
+fn build_an_op_return_script() {
+    let script = op_return_script(&message()).unwrap();
+    assert_eq!(OP_RETURN, hex::encode(script.as_bytes()));
+    assert_eq!(AUTH_MESSAGE, op_return_message(&script).unwrap().as_hex());
+
+    let largest: WhiteflagBuffer = [b"WF1".as_slice(), &[0; OP_RETURN_LIMIT - 3]]
+        .concat()
+        .into();
+    assert!(op_return_script(&largest).is_ok());
+    let too_large: WhiteflagBuffer = [b"WF1".as_slice(), &[0; OP_RETURN_LIMIT - 2]]
+        .concat()
+        .into();
+    assert!(matches!(
+        op_return_script(&too_large),
+        Err(BlockchainError::MessageTooLarge {
+            size: 81,
+            limit: 80
+        })
+    ));
+}
+
+#[test]
+fn only_op_return_scripts_of_messages_carry_one() {
+    assert_eq!(
+        None,
+        op_return_message(&Script::new_op_return(b"not a message"))
+    );
+    assert_eq!(None, op_return_message(&Script::new()));
+
+    let p2pkh =
+        Script::from(hex::decode("76a9149b78039087bd663f20ace711f15be0eaf7d0700588ac").unwrap());
+    assert_eq!(None, op_return_message(&p2pkh));
+}
+
+#[test]
+fn extract_messages_from_raw_transactions() {
+    let messages =
+        transaction_messages(&hex::decode(P2WPKH_TX).unwrap(), Network::Regtest).unwrap();
+    assert_eq!(1, messages.len());
+    assert_eq!(P2WPKH_TXID, messages[0].transaction_hash);
+    assert_eq!(0, messages[0].output);
+    assert_eq!(
+        P2WPKH_ADDRESS,
+        messages[0].originator.as_ref().unwrap().to_string()
+    );
+    assert_eq!(AUTH_MESSAGE, messages[0].message.as_hex());
+    assert_eq!(None, messages[0].block_hash);
+
+    let messages = transaction_messages(&hex::decode(P2PKH_TX).unwrap(), Network::Regtest).unwrap();
+    assert_eq!(P2PKH_TXID, messages[0].transaction_hash);
+    assert_eq!(1, messages[0].output);
+    assert_eq!(
+        P2PKH_ADDRESS,
+        messages[0].originator.as_ref().unwrap().to_string()
+    );
+
+    let raw = hex::decode(P2WPKH_TX).unwrap();
+    assert!(matches!(
+        transaction_messages(&raw[..raw.len() - 1], Network::Regtest),
+        Err(BlockchainError::InvalidTransaction(_))
+    ));
+}
+
+#[test]
+fn extract_messages_from_blocks() {
+    let block = block();
+    let raw = serialize(&block);
+    assert!(!is_block_file(&raw, Network::Regtest));
+
+    let messages = block_messages(&raw, Network::Regtest).unwrap();
+    assert_eq!(2, messages.len(), "the coinbase carries no message");
+    assert_eq!(P2WPKH_TXID, messages[0].transaction_hash);
+    assert_eq!(P2PKH_TXID, messages[1].transaction_hash);
+    assert_eq!(Some(block.block_hash().to_string()), messages[1].block_hash);
+    assert_eq!(Some(block.header.time as u64), messages[1].timestamp);
+}
+
+#[test]
+fn extract_messages_from_a_block_file() {
+    let raw = serialize(&block());
+    let mut file = Vec::new();
+    for _ in 0..2 {
+        file.extend_from_slice(&Network::Regtest.magic().to_le_bytes());
+        file.extend_from_slice(&(raw.len() as u32).to_le_bytes());
+        file.extend_from_slice(&raw);
+    }
+    file.extend_from_slice(&[0; 16]);
+    assert!(is_block_file(&file, Network::Regtest));
+
+    let messages = block_file_messages(&file, Network::Regtest).unwrap();
+    assert_eq!(4, messages.len());
+
+    assert!(matches!(
+        block_file_messages(&file, Network::Bitcoin),
+        Err(BlockchainError::InvalidBlock { block: 0, .. })
+    ));
+    let truncated = &file[..raw.len() + 8 + 100];
+    assert!(matches!(
+        block_file_messages(truncated, Network::Regtest),
+        Err(BlockchainError::InvalidBlock { block: 1, .. })
+    ));
+}
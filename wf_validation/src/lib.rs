@@ -1,3 +1,9 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::string::String;
+
 /// there are four ways a value can be resolved as invalid
 /// - [Encoding] invalid length according to encoding
 /// - [Encoding] invalid character set according to encoding
@@ -19,6 +25,7 @@ pub trait Validation {
     fn validate(&self, value: &str) -> Result<(), ValidationError>;
 }
 
+#[cfg(feature = "std")]
 pub fn test<T: Validation>(validator: T, data: &str) -> Result<(), ValidationError> {
     let result = validator.validate(data);
     assert!(result.is_err(), "the test did not throw an error");
@@ -28,6 +35,7 @@ pub fn test<T: Validation>(validator: T, data: &str) -> Result<(), ValidationErr
     result
 }
 
+#[cfg(feature = "std")]
 pub fn invalid_length_test<T: Validation>(validator: T, data: &str, expected_length: usize) {
     // ensure we have an incorrect length
     assert!(
@@ -0,0 +1,102 @@
+use super::to_js_error;
+use fennel_whiteflag::WhiteflagMessage;
+use serde_json::{Map, Value};
+use wasm_bindgen::prelude::*;
+use wf_field::{DURESS, NO_DURESS};
+
+/// composes a message from the default field values of its message type
+///
+/// setters consume the builder and return it, so calls can be chained from javascript:
+/// `MessageBuilder.freeText("hello").duress(true).encode()`
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct MessageBuilder {
+    fields: Map<String, Value>,
+}
+
+#[wasm_bindgen]
+impl MessageBuilder {
+    /// starts a message of the given message code, e.g. "A", "F" or "P"
+    #[wasm_bindgen(constructor)]
+    pub fn new(code: &str) -> Result<MessageBuilder, JsError> {
+        let json = WhiteflagMessage::new(code.to_string())
+            .map_err(to_js_error)?
+            .as_json();
+
+        Ok(MessageBuilder {
+            fields: serde_json::from_str(&json).map_err(to_js_error)?,
+        })
+    }
+
+    /// 4.3.4 Authentication Message
+    pub fn authentication(
+        verification_method: &str,
+        verification_data: &str,
+    ) -> Result<MessageBuilder, JsError> {
+        Ok(MessageBuilder::new("A")?
+            .set("verificationMethod", verification_method)
+            .set("verificationData", verification_data))
+    }
+
+    /// 4.3.5 Cryptographic Support Message
+    pub fn crypto(crypto_data_type: &str, crypto_data: &str) -> Result<MessageBuilder, JsError> {
+        Ok(MessageBuilder::new("K")?
+            .set("cryptoDataType", crypto_data_type)
+            .set("cryptoData", crypto_data))
+    }
+
+    /// 4.3.3 Free Text Message
+    #[wasm_bindgen(js_name = freeText)]
+    pub fn free_text(text: &str) -> Result<MessageBuilder, JsError> {
+        Ok(MessageBuilder::new("F")?.set("text", text))
+    }
+
+    /// 4.3.2 Resource Message
+    pub fn resource(resource_method: &str, resource_data: &str) -> Result<MessageBuilder, JsError> {
+        Ok(MessageBuilder::new("R")?
+            .set("resourceMethod", resource_method)
+            .set("resourceData", resource_data))
+    }
+
+    /// 4.3.1 Signs & Signals Message, for the protective sign and signal message codes
+    pub fn signal(code: &str, subject_code: &str) -> Result<MessageBuilder, JsError> {
+        Ok(MessageBuilder::new(code)?.set("subjectCode", subject_code))
+    }
+
+    /// sets the value of a field by its json name, values are validated when encoding
+    pub fn set(mut self, field: &str, value: &str) -> MessageBuilder {
+        self.fields
+            .insert(field.to_string(), Value::String(value.to_string()));
+        self
+    }
+
+    /// 4.2.1.5 Duress Indicator Field
+    pub fn duress(self, duress: bool) -> MessageBuilder {
+        self.set("duressIndicator", if duress { DURESS } else { NO_DURESS })
+    }
+
+    /// 4.2.1.7 Reference Indicator Field and 4.2.1.8 Referenced Message Field
+    pub fn reference(self, reference_indicator: &str, referenced_message: &str) -> MessageBuilder {
+        self.set("referenceIndicator", reference_indicator)
+            .set("referencedMessage", referenced_message)
+    }
+
+    /// the value of a field by its json name
+    pub fn get(&self, field: &str) -> Option<String> {
+        self.fields
+            .get(field)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    }
+
+    /// the message as json, see `WhiteflagMessageJson`
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> String {
+        Value::Object(self.fields.clone()).to_string()
+    }
+
+    /// validates and encodes the message into a hexadecimal string
+    pub fn encode(&self) -> Result<String, JsError> {
+        fennel_whiteflag::encode_from_json(self.to_json()).map_err(to_js_error)
+    }
+}
@@ -0,0 +1,81 @@
+use super::{decode_from_hex, encode_from_json, validate_hex, validate_json, MessageBuilder};
+use serde_json::{Map, Value};
+
+const AUTHENTICATION_HEX: &str = "5746313020800000000000000000000000000000000000000000000000000000000000000000b43a3a38399d1797b7b933b0b734b9b0ba34b7b71734b73a17bbb434ba32b33630b380";
+
+fn fields(json: &str) -> Map<String, Value> {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn authentication_builder_encodes() {
+    let builder =
+        MessageBuilder::authentication("1", "https://organisation.int/whiteflag").unwrap();
+
+    assert_eq!(AUTHENTICATION_HEX, builder.encode().unwrap());
+    assert_eq!(
+        fields(&builder.to_json()),
+        fields(&decode_from_hex(AUTHENTICATION_HEX).unwrap())
+    );
+}
+
+#[test]
+fn typed_builders_round_trip() {
+    let builders = [
+        MessageBuilder::free_text("whiteflag test message").unwrap(),
+        MessageBuilder::resource("1", "https://organisation.int/whiteflag").unwrap(),
+        MessageBuilder::crypto(
+            "11",
+            "d426bbe111221675e333f30ef608b1aa6e60a47080dd33cb49e96395894ef42f",
+        )
+        .unwrap(),
+        MessageBuilder::signal("P", "52").unwrap(),
+    ];
+
+    for builder in builders {
+        let hex = builder.encode().unwrap();
+        assert_eq!(
+            fields(&builder.to_json()),
+            fields(&decode_from_hex(&hex).unwrap())
+        );
+        assert_eq!(hex, encode_from_json(&builder.to_json()).unwrap());
+    }
+}
+
+#[test]
+fn setters_change_fields() {
+    let referenced = "3efb4e0cfa83122b242634254c1920a769d615dfcc4c670bb53eb6f12843c3ae";
+    let builder = MessageBuilder::free_text("under duress")
+        .unwrap()
+        .duress(true)
+        .reference("3", referenced);
+
+    assert_eq!(Some("1".to_string()), builder.get("duressIndicator"));
+    assert_eq!(Some("3".to_string()), builder.get("referenceIndicator"));
+    assert_eq!(
+        Some(referenced.to_string()),
+        builder.get("referencedMessage")
+    );
+    assert_eq!(None, builder.get("verificationData"));
+
+    let decoded = fields(&decode_from_hex(&builder.encode().unwrap()).unwrap());
+    assert_eq!("1", decoded["duressIndicator"]);
+    assert_eq!("under duress", decoded["text"]);
+}
+
+#[test]
+fn validation_reports_errors() {
+    let builder =
+        MessageBuilder::authentication("1", "https://organisation.int/whiteflag").unwrap();
+    assert!(validate_json(&builder.to_json()).valid());
+    assert!(validate_hex(AUTHENTICATION_HEX).valid());
+
+    let invalid = validate_json(&builder.set("verificationMethod", "X").to_json());
+    assert!(!invalid.valid());
+    assert!(invalid.error().is_some());
+
+    let unknown = validate_json(r#"{"prefix": "WF", "colour": "white"}"#);
+    assert!(unknown.error().unwrap().contains("colour"));
+
+    assert!(!validate_hex("57").valid());
+}
@@ -0,0 +1,116 @@
+//! WebAssembly bindings for composing, encoding, decoding and validating Whiteflag messages
+//!
+//! build for node with `wasm-pack build wf_wasm --target nodejs`, or `--target web` for browsers
+
+#[cfg(test)]
+mod builder_tests;
+
+mod builder;
+
+use std::error::Error;
+use wasm_bindgen::prelude::*;
+
+pub use builder::MessageBuilder;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_MESSAGE_JSON: &'static str = r#"
+/** the JSON representation of a Whiteflag message, with all field values as strings */
+export interface WhiteflagMessageJson {
+    prefix: string;
+    version: string;
+    encryptionIndicator: string;
+    duressIndicator: string;
+    messageCode: string;
+    referenceIndicator: string;
+    referencedMessage: string;
+    verificationMethod?: string;
+    verificationData?: string;
+    cryptoDataType?: string;
+    cryptoData?: string;
+    text?: string;
+    resourceMethod?: string;
+    resourceData?: string;
+    pseudoMessageCode?: string;
+    subjectCode?: string;
+    dateTime?: string;
+    duration?: string;
+    objectType?: string;
+    objectLatitude?: string;
+    objectLongitude?: string;
+    objectSizeDim1?: string;
+    objectSizeDim2?: string;
+    objectOrientation?: string;
+    /** the numbered object type and quantity pairs of a request signal */
+    [objectType: `objectType${number}`]: string;
+    [objectTypeQuant: `objectType${number}Quant`]: string;
+}
+"#;
+
+/// encode whiteflag json message into a hexadecimal string
+#[wasm_bindgen(js_name = encodeFromJson)]
+pub fn encode_from_json(json: &str) -> Result<String, JsError> {
+    fennel_whiteflag::encode_from_json(json).map_err(to_js_error)
+}
+
+/// decode hexadecimal encoded whiteflag message into a json message
+#[wasm_bindgen(js_name = decodeFromHex)]
+pub fn decode_from_hex(hex: &str) -> Result<String, JsError> {
+    fennel_whiteflag::decode_from_hex(hex).map_err(to_js_error)
+}
+
+/// the outcome of validating a message, without throwing
+#[wasm_bindgen]
+pub struct ValidationResult {
+    error: Option<String>,
+}
+
+#[wasm_bindgen]
+impl ValidationResult {
+    #[wasm_bindgen(getter)]
+    pub fn valid(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// the reason the message is invalid, undefined if it is valid
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+impl<T, E: Error> From<Result<T, E>> for ValidationResult {
+    fn from(result: Result<T, E>) -> Self {
+        ValidationResult {
+            error: result.err().map(|e| error_message(&e)),
+        }
+    }
+}
+
+/// checks that a json message has valid values for all fields of its message type
+#[wasm_bindgen(js_name = validateJson)]
+pub fn validate_json(json: &str) -> ValidationResult {
+    fennel_whiteflag::encode_from_json(json).into()
+}
+
+/// checks that a hexadecimal string decodes to a valid message
+#[wasm_bindgen(js_name = validateHex)]
+pub fn validate_hex(hex: &str) -> ValidationResult {
+    fennel_whiteflag::decode_from_hex(hex).into()
+}
+
+/// the error followed by its sources, as `WhiteflagError` only names the failing crate
+fn error_message(error: &dyn Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(error) = source {
+        message.push_str(": ");
+        message.push_str(&error.to_string());
+        source = error.source();
+    }
+
+    message
+}
+
+fn to_js_error<E: Error>(error: E) -> JsError {
+    JsError::new(&error_message(&error))
+}
@@ -0,0 +1,86 @@
+//! run with `wasm-pack test --node wf_wasm`
+#![cfg(target_arch = "wasm32")]
+
+use wasm_bindgen_test::wasm_bindgen_test;
+use wf_wasm::{decode_from_hex, encode_from_json, validate_hex, validate_json, MessageBuilder};
+
+const AUTHENTICATION_JSON: &str = r#"{
+    "prefix": "WF",
+    "version": "1",
+    "encryptionIndicator": "0",
+    "duressIndicator": "0",
+    "messageCode": "A",
+    "referenceIndicator": "0",
+    "referencedMessage": "0000000000000000000000000000000000000000000000000000000000000000",
+    "verificationMethod": "1",
+    "verificationData": "https://organisation.int/whiteflag"
+}"#;
+const AUTHENTICATION_HEX: &str = "5746313020800000000000000000000000000000000000000000000000000000000000000000b43a3a38399d1797b7b933b0b734b9b0ba34b7b71734b73a17bbb434ba32b33630b380";
+const REQUEST_HEX: &str = "574631302880000000000000000000000000000000000000000000000000000000000000000401009841882148a808000114c5e596006704c050f32642000020044006";
+
+#[wasm_bindgen_test]
+fn encode_and_decode() {
+    assert_eq!(
+        AUTHENTICATION_HEX,
+        encode_from_json(AUTHENTICATION_JSON).unwrap()
+    );
+
+    let decoded: serde_json::Value =
+        serde_json::from_str(&decode_from_hex(AUTHENTICATION_HEX).unwrap()).unwrap();
+    let expected: serde_json::Value = serde_json::from_str(AUTHENTICATION_JSON).unwrap();
+    assert_eq!(expected, decoded);
+}
+
+#[wasm_bindgen_test]
+fn decode_request_pairs() {
+    let decoded: serde_json::Value =
+        serde_json::from_str(&decode_from_hex(REQUEST_HEX).unwrap()).unwrap();
+    assert_eq!("Q", decoded["messageCode"]);
+    assert_eq!("10", decoded["objectType1"]);
+    assert_eq!("02", decoded["objectType1Quant"]);
+    assert_eq!("20", decoded["objectType2"]);
+    assert_eq!("03", decoded["objectType2Quant"]);
+    assert!(decoded.get("objectTypeQuant").is_none());
+
+    assert_eq!(REQUEST_HEX, encode_from_json(&decoded.to_string()).unwrap());
+}
+
+#[wasm_bindgen_test]
+fn invalid_input_throws() {
+    assert!(encode_from_json("{}").is_err());
+    assert!(encode_from_json(r#"{"prefix": "WF", "colour": "white"}"#).is_err());
+    assert!(decode_from_hex("zz").is_err());
+    assert!(decode_from_hex("57").is_err());
+}
+
+#[wasm_bindgen_test]
+fn builders_encode() {
+    let builder = MessageBuilder::new("A")
+        .unwrap()
+        .set("verificationData", "https://organisation.int/whiteflag");
+    assert_eq!(AUTHENTICATION_HEX, builder.encode().unwrap());
+
+    let free_text = MessageBuilder::free_text("whiteflag test message")
+        .unwrap()
+        .duress(true);
+    let decoded: serde_json::Value =
+        serde_json::from_str(&decode_from_hex(&free_text.encode().unwrap()).unwrap()).unwrap();
+    assert_eq!("1", decoded["duressIndicator"]);
+    assert_eq!("whiteflag test message", decoded["text"]);
+
+    assert!(MessageBuilder::new("?").is_err());
+    assert!(MessageBuilder::resource("9", "https://organisation.int")
+        .unwrap()
+        .encode()
+        .is_err());
+}
+
+#[wasm_bindgen_test]
+fn validation() {
+    assert!(validate_json(AUTHENTICATION_JSON).valid());
+    assert!(validate_hex(AUTHENTICATION_HEX).valid());
+
+    let result = validate_json(&AUTHENTICATION_JSON.replace(r#""1""#, r#""X""#));
+    assert!(!result.valid());
+    assert!(result.error().is_some());
+}